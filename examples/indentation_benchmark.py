@@ -0,0 +1,42 @@
+"""
+Time toons.dumps on a large, deeply indented tabular document.
+
+Every row of the table sits ten levels deep, so most of each written line is
+indentation. Run with a release build (`maturin develop --release`).
+"""
+
+import time
+
+import toons
+
+ROWS = 100_000
+DEPTH = 9
+
+
+def build_document():
+    rows = [
+        {"id": i, "name": f"user{i}", "score": i * 0.5} for i in range(ROWS)
+    ]
+    doc = {"rows": rows}
+    for level in range(DEPTH):
+        doc = {f"level{level}": doc}
+    return doc
+
+
+def main():
+    doc = build_document()
+    toons.dumps(doc, indent=4)  # warm up
+
+    runs = 10
+    start = time.perf_counter()
+    for _ in range(runs):
+        text = toons.dumps(doc, indent=4)
+    elapsed = (time.perf_counter() - start) / runs
+
+    lines = text.count("\n") + 1
+    print(f"{lines} lines, {len(text) / 1e6:.1f} MB")
+    print(f"toons.dumps: {elapsed * 1000:.1f} ms per document")
+
+
+if __name__ == "__main__":
+    main()
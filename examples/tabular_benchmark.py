@@ -0,0 +1,53 @@
+"""
+Time toons.dumps and toons.loads on 50,000-row tabular arrays: a narrow
+table of four columns and a wide one of 100.
+
+Run with a release build (`maturin develop --release`). Reports the best of
+several runs, which is less noisy than the mean on a busy machine.
+"""
+
+import time
+
+import toons
+
+ROWS = 50_000
+WIDE_COLUMNS = 100
+RUNS = 15
+WIDE_RUNS = 5
+
+
+def best_of(func, runs=RUNS):
+    best = float("inf")
+    for _ in range(runs):
+        start = time.perf_counter()
+        func()
+        best = min(best, time.perf_counter() - start)
+    return best
+
+
+def report(name, doc, runs=RUNS):
+    text = toons.dumps(doc)
+    dumps_ms = best_of(lambda: toons.dumps(doc), runs) * 1000
+    loads_ms = best_of(lambda: toons.loads(text), runs) * 1000
+    print(f"{name}: {len(text) / 1e6:.1f} MB")
+    print(f"  toons.dumps: {dumps_ms:.1f} ms")
+    print(f"  toons.loads: {loads_ms:.1f} ms")
+
+
+def main():
+    rows = [
+        {"id": i, "name": f"user{i}", "score": i * 0.5, "active": i % 2 == 0}
+        for i in range(ROWS)
+    ]
+    report(f"{ROWS} rows x 4 columns", {"rows": rows})
+
+    columns = [f"col{j}" for j in range(WIDE_COLUMNS)]
+    wide = [
+        {column: i + j for j, column in enumerate(columns)}
+        for i in range(ROWS)
+    ]
+    report(f"{ROWS} rows x {WIDE_COLUMNS} columns", {"rows": wide}, WIDE_RUNS)
+
+
+if __name__ == "__main__":
+    main()
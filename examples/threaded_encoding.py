@@ -0,0 +1,45 @@
+"""Compare encoding a large table on one thread and on two threads.
+
+dumps() formats large tables of plain values with the GIL released, so two
+threads encoding at once finish sooner than running the same work in turn.
+"""
+
+import threading
+import time
+
+import toons
+
+ROWS = [
+    {"id": i, "name": f"user{i}", "score": i * 0.25, "active": i % 2 == 0}
+    for i in range(200_000)
+]
+
+
+def encode():
+    toons.dumps({"rows": ROWS})
+
+
+def sequential(count):
+    start = time.perf_counter()
+    for _ in range(count):
+        encode()
+    return time.perf_counter() - start
+
+
+def concurrent(count):
+    threads = [threading.Thread(target=encode) for _ in range(count)]
+    start = time.perf_counter()
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+    return time.perf_counter() - start
+
+
+if __name__ == "__main__":
+    encode()  # warm up
+    one_by_one = sequential(2)
+    together = concurrent(2)
+    print(f"2 encodes, one after another: {one_by_one:.3f}s")
+    print(f"2 encodes on 2 threads:       {together:.3f}s")
+    print(f"speedup: {one_by_one / together:.2f}x")
@@ -1,9 +1,19 @@
+use crate::schema::{Schema, apply_schema};
+use crate::serialization::{ColumnType, FloatTokens, Literals};
+use crate::text::{
+    COMMENT_IN_STRICT_MODE, comment_lines, find_unquoted_char, is_tabular_row, is_valid_delimiter,
+    split_by_delimiter, split_radix_literal, strip_digit_separators, strip_plus_sign, unescape,
+};
 use pyo3::prelude::*;
-use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString};
+use pyo3::types::{
+    PyBool, PyByteArray, PyBytes, PyDate, PyDateTime, PyDelta, PyDict, PyFloat, PyInt, PyList,
+    PyString, PyTime, PyTuple, PyTzInfo,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 /// Build a `ToonDecodeError` with `.line` and `.source` attributes set
 /// (either may be `None` when the offending location is unknown).
-fn make_decode_error(
+pub fn make_decode_error(
     py: Python,
     message: String,
     line: Option<usize>,
@@ -16,31 +26,896 @@ fn make_decode_error(
     err
 }
 
+/// How a bare `-` list item (no value after the hyphen) is decoded
+#[derive(Clone, Copy, PartialEq)]
+pub enum BareHyphen {
+    /// `{}`, matching what the encoder emits for an empty object item
+    EmptyObject,
+    Null,
+}
+
+impl BareHyphen {
+    /// Interpret the `bare_hyphen` argument: `"empty_object"` or `"null"`
+    pub fn from_arg(arg: &str) -> PyResult<Self> {
+        match arg {
+            "empty_object" => Ok(BareHyphen::EmptyObject),
+            "null" => Ok(BareHyphen::Null),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "bare_hyphen must be \"empty_object\" or \"null\"",
+            )),
+        }
+    }
+}
+
+/// How an unquoted number with leading zeros (`007`, `-007`) is decoded
+#[derive(Clone, Copy, PartialEq)]
+pub enum LeadingZero {
+    /// The token as a string, preserving IDs and codes
+    String,
+    /// The number it spells, ignoring the zeros
+    Number,
+}
+
+impl LeadingZero {
+    /// Interpret the `leading_zero` argument: `"string"` or `"number"`
+    pub fn from_arg(arg: &str) -> PyResult<Self> {
+        match arg {
+            "string" => Ok(LeadingZero::String),
+            "number" => Ok(LeadingZero::Number),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "leading_zero must be \"string\" or \"number\"",
+            )),
+        }
+    }
+}
+
+/// How an empty value between delimiters (`a,,c`) in an inline array or
+/// tabular row is decoded
+#[derive(Clone, Copy, PartialEq)]
+pub enum EmptyCell {
+    /// The empty string, as the spec reads it
+    String,
+    /// `None`, for generators that leave missing values blank
+    Null,
+}
+
+impl EmptyCell {
+    /// Interpret the `empty_cell` argument: `"string"` or `"null"`
+    pub fn from_arg(arg: &str) -> PyResult<Self> {
+        match arg {
+            "string" => Ok(EmptyCell::String),
+            "null" => Ok(EmptyCell::Null),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "empty_cell must be \"string\" or \"null\"",
+            )),
+        }
+    }
+}
+
+/// Decoder options shared by `loads` and `load`; the defaults are those of
+/// `loads`
+pub struct DecodeOptions {
+    /// Enable strict mode validation
+    pub strict: bool,
+    /// Path expansion mode ("off" | "safe" | "always")
+    pub expand_paths: String,
+    /// Expected indentation size (None for auto-detect)
+    pub indent: Option<usize>,
+    /// Return `(value, warnings)` listing tolerated deviations
+    pub collect_warnings: bool,
+    pub bare_hyphen: BareHyphen,
+    /// Turn unquoted `*/path` values into references to the object at `path`
+    pub resolve_aliases: bool,
+    /// Decode ISO-8601 date/time/datetime values into `datetime` objects
+    pub parse_datetime: bool,
+    /// Deepest nesting of objects and lists accepted before raising
+    pub max_depth: usize,
+    /// Called with each decoded object, which is replaced by the result
+    pub object_hook: Option<Py<PyAny>>,
+    /// Called with each decoded object's `(key, value)` pairs instead of
+    /// `object_hook`; the object is replaced by the result
+    pub object_pairs_hook: Option<Py<PyAny>>,
+    /// Tokens read as `None`, `True` and `False` besides the standard ones
+    pub literals: Literals,
+    /// Quoted strings read as NaN, Infinity and -Infinity
+    pub float_tokens: FloatTokens,
+    /// Drop one empty value after a trailing delimiter in inline arrays and
+    /// tabular rows (`1,2,`)
+    pub allow_trailing_delimiter: bool,
+    /// Accept tabs in indentation, one tab per level
+    pub allow_tab_indent: bool,
+    pub leading_zero: LeadingZero,
+    pub empty_cell: EmptyCell,
+    /// Shape the decoded value must match, with unambiguous numbers and
+    /// booleans coerced to it
+    pub schema: Option<Schema>,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        DecodeOptions {
+            strict: true,
+            expand_paths: "off".to_string(),
+            indent: None,
+            collect_warnings: false,
+            bare_hyphen: BareHyphen::EmptyObject,
+            resolve_aliases: false,
+            parse_datetime: false,
+            max_depth: 1000,
+            object_hook: None,
+            object_pairs_hook: None,
+            literals: Literals::default(),
+            float_tokens: FloatTokens::default(),
+            allow_trailing_delimiter: false,
+            allow_tab_indent: false,
+            leading_zero: LeadingZero::String,
+            empty_cell: EmptyCell::String,
+            schema: None,
+        }
+    }
+}
+
+/// Split `input` into lines ending in `\n`, `\r\n` or a lone `\r`. Like
+/// `str::lines`, a final line ending does not start another (empty) line.
+fn split_lines(input: &str) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let mut rest = input;
+    while let Some(end) = rest.find(['\n', '\r']) {
+        lines.push(&rest[..end]);
+        let ending = if rest[end..].starts_with("\r\n") {
+            2
+        } else {
+            1
+        };
+        rest = &rest[end + ending..];
+    }
+    if !rest.is_empty() {
+        lines.push(rest);
+    }
+    lines
+}
+
+/// Inputs at least this large are validated and split into lines with the
+/// GIL released
+const DETACH_MIN_BYTES: usize = 64 * 1024;
+
+/// Text of a `loads`/`load` input: a `str` as is, or `bytes`/`bytearray`
+/// decoded as UTF-8
+pub fn input_text(obj: &Bound<'_, PyAny>) -> PyResult<String> {
+    if let Ok(bytes) = obj.cast::<PyBytes>() {
+        decode_utf8(obj.py(), bytes.as_bytes())
+    } else if let Ok(bytes) = obj.cast::<PyByteArray>() {
+        decode_utf8(obj.py(), &bytes.to_vec())
+    } else if let Ok(s) = obj.cast::<PyString>() {
+        Ok(s.to_cow()?.into_owned())
+    } else {
+        Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+            "TOON input must be str, bytes or bytearray, not {}",
+            obj.get_type().name()?
+        )))
+    }
+}
+
+fn decode_utf8(py: Python, bytes: &[u8]) -> PyResult<String> {
+    let decoded = if bytes.len() >= DETACH_MIN_BYTES {
+        py.detach(|| std::str::from_utf8(bytes).map(str::to_owned))
+    } else {
+        std::str::from_utf8(bytes).map(str::to_owned)
+    };
+    match decoded {
+        Ok(text) => Ok(text),
+        Err(e) => {
+            let valid = &bytes[..e.valid_up_to()];
+            let line = valid.iter().filter(|&&b| b == b'\n').count() + 1;
+            Err(make_decode_error(
+                py,
+                format!("TOON parse error at line {}: Invalid UTF-8: {}", line, e),
+                Some(line),
+                None,
+            ))
+        }
+    }
+}
+
+/// Create a parser for `input`. Line splitting and indentation scanning need
+/// no Python objects, so large inputs are prepared with the GIL released.
+fn new_parser<'a>(py: Python, input: &'a str, options: &'a DecodeOptions) -> Parser<'a> {
+    if input.len() >= DETACH_MIN_BYTES {
+        py.detach(|| Parser::new(input, options))
+    } else {
+        Parser::new(input, options)
+    }
+}
+
+/// Check that `input` is well-formed, running the full parser but without
+/// creating Python objects for values or tabular rows
+pub fn validate(py: Python, input: &str, options: &DecodeOptions) -> PyResult<()> {
+    let mut parser = new_parser(py, input, options);
+    parser.validate_only = true;
+    parser.parse(py)?;
+    Ok(())
+}
+
 /// Deserialize a TOON format string to a Python object.
 ///
 /// # Arguments
 ///
 /// * `py` - Python interpreter handle
 /// * `input` - TOON format string
-/// * `strict` - Enable strict mode validation
-/// * `expand_paths` - Path expansion mode ("off" | "safe" | "always")
-/// * `indent` - Expected indentation size (None for auto-detect)
+/// * `options` - Decoder options
 ///
 /// # Returns
 ///
-/// Python object (dict, list, or primitive)
-pub fn deserialize(
-    py: Python,
-    input: &str,
+/// Python object (dict, list, or primitive), or a `(value, warnings)` tuple
+/// where `warnings` is a list of `(line, message)` tuples
+pub fn deserialize(py: Python, input: &str, options: &DecodeOptions) -> PyResult<Py<PyAny>> {
+    let mut parser = new_parser(py, input, options);
+    let mut value = parser.parse(py)?;
+    let mut duplicates = parser.duplicate_pairs.take().unwrap_or_default();
+    if let Some(schema) = &options.schema {
+        value = apply_schema(value.bind(py), schema)?;
+    }
+    // Hooks see resolved aliases, and may turn objects into anything
+    if options.resolve_aliases {
+        let root = value.bind(py);
+        resolve_aliases(root, root, &mut duplicates)?;
+    }
+    if options.object_hook.is_some() || options.object_pairs_hook.is_some() {
+        let mut hooks = ObjectHooks {
+            options,
+            duplicates,
+            done: HashMap::new(),
+        };
+        value = hooks.apply(value.bind(py))?;
+    }
+    if options.collect_warnings {
+        let warnings = PyList::new(py, parser.warnings)?;
+        Ok(PyTuple::new(py, [value, warnings.into_any().unbind()])?.into())
+    } else {
+        Ok(value)
+    }
+}
+
+/// A field of a tabular header: `name`, or `name:type` for a typed column
+pub struct TabularField {
+    name: String,
+    column_type: Option<ColumnType>,
+}
+
+/// Bytes (or characters, for text files) read per step by `iter_rows`
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Header of the root tabular array streamed by a `RowIterator`
+struct StreamHeader {
+    length: usize,
+    delimiter: char,
+    fields: Vec<TabularField>,
+    /// 1-based line and raw source of the header, for the final length check
+    line: usize,
+    source: String,
+    /// Indentation of the header; row depths are measured relative to it
+    base_indent: usize,
+}
+
+/// Iterator returned by `iter_rows`: decodes the rows of a root tabular
+/// array one chunk of the file at a time, so only that chunk's rows are
+/// held in memory
+#[pyclass(module = "toons")]
+pub struct RowIterator {
+    file: Py<PyAny>,
+    /// Close `file` once done (it was opened from a path by `iter_rows`)
+    owns_file: bool,
     strict: bool,
-    expand_paths: &str,
-    indent: Option<usize>,
-) -> PyResult<Py<PyAny>> {
-    let mut parser = Parser::new(input, strict, expand_paths, indent);
-    parser.parse(py)
+    header: Option<StreamHeader>,
+    indent_size: Option<usize>,
+    /// Bytes read but not parsed yet: an incomplete last line, plus blank
+    /// lines that may still turn out to sit inside the array
+    pending: Vec<u8>,
+    /// Source lines parsed before `pending`
+    line_offset: usize,
+    rows: VecDeque<Py<PyAny>>,
+    /// Rows yielded so far
+    count: usize,
+    /// An error met while decoding, raised once the rows before it are out
+    error: Option<PyErr>,
+    /// No more rows will be decoded (end of file, or a line that ends the array)
+    ended: bool,
+    done: bool,
+}
+
+impl RowIterator {
+    /// Start streaming `file`, reading up to and including the header so a
+    /// document that is not a root tabular array is rejected immediately
+    pub fn new(py: Python, file: Py<PyAny>, owns_file: bool, strict: bool) -> PyResult<Self> {
+        let mut iter = RowIterator {
+            file,
+            owns_file,
+            strict,
+            header: None,
+            indent_size: None,
+            pending: Vec::new(),
+            line_offset: 0,
+            rows: VecDeque::new(),
+            count: 0,
+            error: None,
+            ended: false,
+            done: false,
+        };
+        let started = (|| {
+            while iter.header.is_none() && !iter.ended {
+                iter.fill(py)?;
+            }
+            if iter.header.is_none() {
+                return Err(make_decode_error(
+                    py,
+                    "TOON parse error: Expected a root tabular array header like [N]{fields}:"
+                        .to_string(),
+                    None,
+                    None,
+                ));
+            }
+            Ok(())
+        })();
+        if let Err(err) = started {
+            iter.close(py)?;
+            return Err(err);
+        }
+        Ok(iter)
+    }
+
+    /// Read the next chunk and decode the complete rows in it
+    fn fill(&mut self, py: Python) -> PyResult<()> {
+        let chunk = self
+            .file
+            .bind(py)
+            .call_method1("read", (STREAM_CHUNK_SIZE,))?;
+        let eof = if let Ok(bytes) = chunk.cast::<PyBytes>() {
+            self.pending.extend_from_slice(bytes.as_bytes());
+            bytes.as_bytes().is_empty()
+        } else if let Ok(text) = chunk.cast::<PyString>() {
+            let text = text.to_cow()?;
+            self.pending.extend_from_slice(text.as_bytes());
+            text.is_empty()
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                "read() must return str or bytes, not {}",
+                chunk.get_type().name()?
+            )));
+        };
+
+        let ready = if eof {
+            self.pending.len()
+        } else {
+            complete_lines_len(&self.pending)
+        };
+        if ready > 0 {
+            let bytes: Vec<u8> = self.pending.drain(..ready).collect();
+            let text = String::from_utf8(bytes).map_err(|e| {
+                let valid = &e.as_bytes()[..e.utf8_error().valid_up_to()];
+                let line = self.line_offset + valid.iter().filter(|&&b| b == b'\n').count() + 1;
+                make_decode_error(
+                    py,
+                    format!(
+                        "TOON parse error at line {}: Invalid UTF-8: {}",
+                        line,
+                        e.utf8_error()
+                    ),
+                    Some(line),
+                    None,
+                )
+            })?;
+            self.decode_chunk(py, &text)?;
+            self.line_offset += split_lines(&text).len();
+        }
+        if eof {
+            self.ended = true;
+        }
+        Ok(())
+    }
+
+    /// Decode the header (on the first call) and the rows in `text`, which
+    /// holds whole lines. Header errors are raised; row errors are kept in
+    /// `self.error` until the rows before them have been yielded.
+    fn decode_chunk(&mut self, py: Python, text: &str) -> PyResult<()> {
+        let options = DecodeOptions {
+            strict: self.strict,
+            // Rows are flat; nothing below the header nests
+            max_depth: usize::MAX,
+            ..Default::default()
+        };
+        let table_indent = self.header.as_ref().map(|header| header.base_indent);
+        let mut parser = Parser::with_open_table(text, &options, table_indent);
+        // Number lines from where this chunk starts in the file
+        if parser.line_numbers.is_empty() {
+            parser.line_numbers = (0..parser.lines.len()).collect();
+        }
+        for line_number in &mut parser.line_numbers {
+            *line_number += self.line_offset;
+        }
+
+        if self.header.is_none() {
+            while parser.pos < parser.lines.len() && parser.lines[parser.pos].trim().is_empty() {
+                parser.pos += 1;
+            }
+            if parser.pos >= parser.lines.len() {
+                return Ok(());
+            }
+            let header_idx = parser.pos;
+            let line = parser.lines[header_idx];
+            let not_tabular = |parser: &Parser| {
+                parser.err_at(
+                    py,
+                    header_idx,
+                    "Expected a root tabular array header like [N]{fields}:",
+                )
+            };
+            if !line.trim().starts_with('[') || !line.contains(':') {
+                return Err(not_tabular(&parser));
+            }
+            let (length, delimiter, fields) = parser.parse_header(py, line, header_idx)?;
+            let fields = fields.ok_or_else(|| not_tabular(&parser))?;
+            self.header = Some(StreamHeader {
+                length,
+                delimiter,
+                fields,
+                line: parser.line_number(header_idx),
+                source: line.to_string(),
+                base_indent: line.len() - line.trim_start().len(),
+            });
+            parser.pos += 1;
+        }
+        let Some(header) = &self.header else {
+            return Ok(());
+        };
+
+        parser.base_indent = header.base_indent;
+        match self.indent_size {
+            Some(indent_size) => parser.indent_size = indent_size,
+            None => {
+                parser.detect_indent_size();
+                if parser.pos < parser.lines.len() {
+                    self.indent_size = Some(parser.indent_size);
+                }
+            }
+        }
+
+        loop {
+            match parser.next_tabular_row(py, header.delimiter, &header.fields, 1) {
+                Ok(Some(row)) => self.rows.push_back(row),
+                Ok(None) => break,
+                Err(err) => {
+                    self.error = Some(err);
+                    self.ended = true;
+                    return Ok(());
+                }
+            }
+        }
+        // Stopped before the end of the chunk: the array is over
+        if parser.pos < parser.lines.len() {
+            self.ended = true;
+        }
+        Ok(())
+    }
+
+    fn close(&mut self, py: Python) -> PyResult<()> {
+        if self.owns_file {
+            self.owns_file = false;
+            self.file.bind(py).call_method0("close")?;
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl RowIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> PyResult<Option<Py<PyAny>>> {
+        loop {
+            if let Some(row) = self.rows.pop_front() {
+                self.count += 1;
+                return Ok(Some(row));
+            }
+            if self.done {
+                return Ok(None);
+            }
+            if let Some(err) = self.error.take() {
+                self.done = true;
+                self.close(py)?;
+                return Err(err);
+            }
+            if self.ended {
+                self.done = true;
+                self.close(py)?;
+                let Some(header) = &self.header else {
+                    return Ok(None);
+                };
+                if self.count != header.length {
+                    return Err(make_decode_error(
+                        py,
+                        format!(
+                            "TOON parse error at line {}: Array declared length {} but found {} elements",
+                            header.line, header.length, self.count
+                        ),
+                        Some(header.line),
+                        Some(&header.source),
+                    ));
+                }
+                return Ok(None);
+            }
+            if let Err(err) = self.fill(py) {
+                self.error = Some(err);
+            }
+        }
+    }
+}
+
+/// Length of the prefix of `buf` made of whole lines and ending with a
+/// non-blank one. What follows may be extended by the next read, and blank
+/// lines are held back until it is known whether more rows follow them.
+fn complete_lines_len(buf: &[u8]) -> usize {
+    let Some(cut) = buf.iter().rposition(|&b| b == b'\n') else {
+        return 0;
+    };
+    let complete = &buf[..=cut];
+    let Some(last) = complete.iter().rposition(|b| !b" \t\r\n".contains(b)) else {
+        return 0;
+    };
+    let line_end = complete[last..].iter().position(|&b| b == b'\n');
+    last + line_end.map_or(0, |end| end + 1)
+}
+
+/// Every `(key, value)` pair, in document order, of each object that
+/// repeats a key, by address of the dict (which is kept alive)
+type DuplicatePairs = HashMap<usize, (Py<PyDict>, Vec<(String, Py<PyAny>)>)>;
+
+/// Applies `object_hook` or `object_pairs_hook` to a decoded document
+struct ObjectHooks<'a> {
+    options: &'a DecodeOptions,
+    duplicates: DuplicatePairs,
+    /// Each container already visited (kept alive, so its address is not
+    /// reused) by address, with what replaces it; `None` while the values of
+    /// an object are still being converted. Aliases share containers, which
+    /// are converted once.
+    done: HashMap<usize, (Py<PyAny>, Option<Py<PyAny>>)>,
+}
+
+impl ObjectHooks<'_> {
+    /// Replace every object below and including `node`, innermost first,
+    /// with what `object_pairs_hook` returns for its `(key, value)` pairs in
+    /// document order or, without one, what `object_hook` returns for the
+    /// object itself
+    fn apply(&mut self, node: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        let py = node.py();
+        let id = node.as_ptr() as usize;
+        if let Ok(list) = node.cast::<PyList>() {
+            // Lists are converted in place, so a list seen before is done
+            let seen = (node.clone().unbind(), Some(node.clone().unbind()));
+            if self.done.insert(id, seen).is_none() {
+                for (i, item) in list.iter().enumerate() {
+                    list.set_item(i, self.apply(&item)?)?;
+                }
+            }
+        } else if let Ok(dict) = node.cast::<PyDict>() {
+            match self.done.get(&id) {
+                Some((_, Some(converted))) => return Ok(converted.clone_ref(py)),
+                Some((_, None)) => {
+                    return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                        "object_hook and object_pairs_hook cannot convert an \
+                         object that contains an alias of itself",
+                    ));
+                }
+                None => {}
+            }
+            self.done.insert(id, (node.clone().unbind(), None));
+            let converted = self.convert_dict(dict)?;
+            self.done
+                .insert(id, (node.clone().unbind(), Some(converted.clone_ref(py))));
+            return Ok(converted);
+        }
+        Ok(node.clone().unbind())
+    }
+
+    fn convert_dict(&mut self, dict: &Bound<'_, PyDict>) -> PyResult<Py<PyAny>> {
+        let py = dict.py();
+        if let Some(hook) = &self.options.object_pairs_hook {
+            let pairs = self
+                .document_pairs(dict)?
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = self.apply(value.bind(py))?;
+                    PyTuple::new(py, [key, value])
+                })
+                .collect::<PyResult<Vec<_>>>()?;
+            return hook.call1(py, (PyList::new(py, pairs)?,));
+        }
+        // Iterate over a snapshot, since the values are replaced
+        for item in dict.items() {
+            let (key, value): (Bound<'_, PyAny>, Bound<'_, PyAny>) = item.extract()?;
+            dict.set_item(key, self.apply(&value)?)?;
+        }
+        match &self.options.object_hook {
+            Some(hook) => hook.call1(py, (dict,)),
+            None => Ok(dict.clone().into_any().unbind()),
+        }
+    }
+
+    /// The pairs of `dict` as written, repeated keys included. Keys whose
+    /// value the dict holds take it from the dict, as resolved aliases and
+    /// schema coercions are only applied there.
+    fn document_pairs(&self, dict: &Bound<'_, PyDict>) -> PyResult<Vec<(Py<PyAny>, Py<PyAny>)>> {
+        let py = dict.py();
+        let Some((_, written)) = self.duplicates.get(&(dict.as_ptr() as usize)) else {
+            return Ok(dict
+                .iter()
+                .map(|(key, value)| (key.unbind(), value.unbind()))
+                .collect());
+        };
+        let last: HashMap<&str, usize> = written
+            .iter()
+            .enumerate()
+            .map(|(i, (key, _))| (key.as_str(), i))
+            .collect();
+        let mut pairs = Vec::with_capacity(written.len());
+        for (i, (key, value)) in written.iter().enumerate() {
+            let value = match dict.get_item(key)? {
+                Some(current) if last[key.as_str()] == i => current.unbind(),
+                _ => value.clone_ref(py),
+            };
+            pairs.push((key.into_pyobject(py)?.into_any().unbind(), value));
+        }
+        // Keys merged in by path expansion after the first repeated key
+        for (key, value) in dict.iter() {
+            if !last.contains_key(key.extract::<&str>()?) {
+                pairs.push((key.unbind(), value.unbind()));
+            }
+        }
+        Ok(pairs)
+    }
+}
+
+/// An unresolved `*/path` alias produced by `parse_primitive`
+#[pyclass(frozen)]
+pub struct PendingAlias {
+    path: String,
+    /// 1-based line and raw source of the alias, for error reporting
+    line: usize,
+    source: String,
+}
+
+/// Replace every `PendingAlias` below `node` with the object its JSON-pointer
+/// path names, starting from `root`. Resolved values are not descended into,
+/// so the shared (possibly cyclic) structure is never walked twice.
+fn resolve_aliases(
+    root: &Bound<'_, PyAny>,
+    node: &Bound<'_, PyAny>,
+    duplicates: &mut DuplicatePairs,
+) -> PyResult<()> {
+    if let Ok(dict) = node.cast::<PyDict>() {
+        for (key, value) in dict.iter() {
+            if let Ok(alias) = value.cast::<PendingAlias>() {
+                dict.set_item(key, lookup_alias(root, alias.get())?)?;
+            } else {
+                resolve_aliases(root, &value, duplicates)?;
+            }
+        }
+        // Values of repeated keys that the dict no longer holds
+        let id = dict.as_ptr() as usize;
+        if let Some((owner, mut pairs)) = duplicates.remove(&id) {
+            for (_, value) in &mut pairs {
+                let value_ref = value.bind(root.py()).clone();
+                if let Ok(alias) = value_ref.cast::<PendingAlias>() {
+                    *value = lookup_alias(root, alias.get())?.unbind();
+                } else {
+                    resolve_aliases(root, &value_ref, duplicates)?;
+                }
+            }
+            duplicates.insert(id, (owner, pairs));
+        }
+    } else if let Ok(list) = node.cast::<PyList>() {
+        for (i, item) in list.iter().enumerate() {
+            if let Ok(alias) = item.cast::<PendingAlias>() {
+                list.set_item(i, lookup_alias(root, alias.get())?)?;
+            } else {
+                resolve_aliases(root, &item, duplicates)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Follow an alias path (percent-encoded JSON pointer segments) from `root`
+fn lookup_alias<'py>(
+    root: &Bound<'py, PyAny>,
+    alias: &PendingAlias,
+) -> PyResult<Bound<'py, PyAny>> {
+    let py = root.py();
+    let unresolved = || {
+        make_decode_error(
+            py,
+            format!(
+                "TOON parse error at line {}: Unresolved alias *{}",
+                alias.line, alias.path
+            ),
+            Some(alias.line),
+            Some(&alias.source),
+        )
+    };
+    let mut target = root.clone();
+    for segment in alias.path.split('/').skip(1) {
+        let segment = percent_decode(segment).ok_or_else(unresolved)?;
+        let next = if let Ok(dict) = target.cast::<PyDict>() {
+            dict.get_item(&segment)?
+        } else if let Ok(list) = target.cast::<PyList>() {
+            segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| list.get_item(i).ok())
+        } else {
+            None
+        };
+        target = next.ok_or_else(unresolved)?;
+    }
+    Ok(target)
+}
+
+/// Decode `%XX` escapes in an alias path segment
+fn percent_decode(segment: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(segment.len());
+    let mut rest = segment.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(b);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Time-of-day fields of an ISO-8601 token; `offset` is in seconds east of UTC
+struct IsoTime {
+    hour: u8,
+    minute: u8,
+    second: u8,
+    microsecond: u32,
+    offset: Option<i32>,
+}
+
+/// Value of the `n` ASCII digits at the start of `s`
+fn iso_digits(s: &[u8], n: usize) -> Option<u32> {
+    let digits = s.get(..n)?;
+    digits.iter().try_fold(0, |acc, &b| {
+        b.is_ascii_digit().then(|| acc * 10 + u32::from(b - b'0'))
+    })
+}
+
+/// `YYYY-MM-DD`
+fn parse_iso_date(s: &[u8]) -> Option<(i32, u8, u8)> {
+    if s.len() != 10 || s[4] != b'-' || s[7] != b'-' {
+        return None;
+    }
+    let year = iso_digits(s, 4)? as i32;
+    let month = iso_digits(&s[5..], 2)? as u8;
+    let day = iso_digits(&s[8..], 2)? as u8;
+    Some((year, month, day))
+}
+
+/// `HH:MM[:SS[.f{1,6}]]` followed by nothing, `Z` or `±HH:MM[:SS]`. A bare
+/// time must have seconds, so `12:30` alone stays a string.
+fn parse_iso_time(s: &[u8], require_seconds: bool) -> Option<IsoTime> {
+    if s.get(2) != Some(&b':') {
+        return None;
+    }
+    let hour = iso_digits(s, 2)? as u8;
+    let minute = iso_digits(&s[3..], 2)? as u8;
+    let mut rest = &s[5..];
+    let (mut second, mut microsecond) = (0, 0);
+    if rest.first() == Some(&b':') {
+        second = iso_digits(&rest[1..], 2)? as u8;
+        rest = &rest[3..];
+        if rest.first() == Some(&b'.') {
+            let len = rest[1..].iter().take_while(|b| b.is_ascii_digit()).count();
+            if !(1..=6).contains(&len) {
+                return None;
+            }
+            microsecond = iso_digits(&rest[1..], len)? * 10u32.pow(6 - len as u32);
+            rest = &rest[1 + len..];
+        }
+    } else if require_seconds {
+        return None;
+    }
+    let offset = match rest {
+        [] => None,
+        [b'Z'] => Some(0),
+        [sign @ (b'+' | b'-'), hh, h2, b':', mm, m2, tail @ ..] => {
+            let seconds = match tail {
+                [] => 0,
+                [b':', ss @ ..] if ss.len() == 2 => iso_digits(ss, 2)?,
+                _ => return None,
+            };
+            let total =
+                iso_digits(&[*hh, *h2], 2)? * 3600 + iso_digits(&[*mm, *m2], 2)? * 60 + seconds;
+            Some(if *sign == b'-' {
+                -(total as i32)
+            } else {
+                total as i32
+            })
+        }
+        _ => return None,
+    };
+    Some(IsoTime {
+        hour,
+        minute,
+        second,
+        microsecond,
+        offset,
+    })
+}
+
+/// Build the `date`, `time` or `datetime` an ISO-8601 token denotes: a date,
+/// a time with seconds, or a date and time joined by `T` or a space (what
+/// `isoformat()` and `str()` produce). Anything else, including field values
+/// Python rejects such as `2024-02-30`, yields `None` so it stays a string.
+fn parse_iso_datetime<'py>(py: Python<'py>, s: &str) -> Option<Bound<'py, PyAny>> {
+    let bytes = s.as_bytes();
+    if bytes.len() < 8 || !s.is_ascii() {
+        return None;
+    }
+    let tzinfo = |time: &IsoTime| -> Option<Option<Bound<'py, PyTzInfo>>> {
+        match time.offset {
+            Some(offset) => {
+                let delta = PyDelta::new(py, 0, offset, 0, true).ok()?;
+                Some(Some(PyTzInfo::fixed_offset(py, delta).ok()?))
+            }
+            None => Some(None),
+        }
+    };
+    if bytes.len() == 10 {
+        let (year, month, day) = parse_iso_date(bytes)?;
+        return PyDate::new(py, year, month, day).ok().map(Bound::into_any);
+    }
+    if bytes.len() > 11 && matches!(bytes[10], b'T' | b' ') {
+        let (year, month, day) = parse_iso_date(&bytes[..10])?;
+        let time = parse_iso_time(&bytes[11..], false)?;
+        let tz = tzinfo(&time)?;
+        return PyDateTime::new(
+            py,
+            year,
+            month,
+            day,
+            time.hour,
+            time.minute,
+            time.second,
+            time.microsecond,
+            tz.as_ref(),
+        )
+        .ok()
+        .map(Bound::into_any);
+    }
+    let time = parse_iso_time(bytes, true)?;
+    let tz = tzinfo(&time)?;
+    PyTime::new(
+        py,
+        time.hour,
+        time.minute,
+        time.second,
+        time.microsecond,
+        tz.as_ref(),
+    )
+    .ok()
+    .map(Bound::into_any)
 }
 
 /// Check if a segment is a valid identifier for path expansion (unquoted alphanumeric with dots/underscores)
+///
+/// Mirrors the encoder's `is_valid_unquoted_key`, so Unicode identifier
+/// letters (XID_Start / XID_Continue) are accepted as well.
 fn is_valid_identifier_segment(s: &str) -> bool {
     if s.is_empty() {
         return false;
@@ -48,12 +923,12 @@ fn is_valid_identifier_segment(s: &str) -> bool {
     // Must start with letter or underscore
     let mut chars = s.chars();
     let first = chars.next().unwrap();
-    if !first.is_ascii_alphabetic() && first != '_' {
+    if !unicode_ident::is_xid_start(first) && first != '_' {
         return false;
     }
     // Rest can be alphanumeric, underscore, or dot
     for c in chars {
-        if !c.is_ascii_alphanumeric() && c != '_' && c != '.' {
+        if !unicode_ident::is_xid_continue(c) && c != '.' {
             return false;
         }
     }
@@ -196,33 +1071,200 @@ pub fn deep_merge_path(
     deep_merge_path(py, &next_obj, remaining_segments, value, strict)
 }
 
+/// The integer written with `digits` in `radix`, negated if `negative`
+fn radix_int(py: Python, negative: bool, radix: u32, digits: &str) -> PyResult<Py<PyAny>> {
+    match i64::from_str_radix(digits, radix) {
+        Ok(i) => Ok(PyInt::new(py, if negative { -i } else { i })
+            .into_any()
+            .unbind()),
+        // Too large for i64: let Python parse it exactly
+        Err(_) => {
+            let sign = if negative { "-" } else { "" };
+            Ok(py
+                .get_type::<PyInt>()
+                .call1((format!("{}{}", sign, digits), radix))?
+                .unbind())
+        }
+    }
+}
+
 pub struct Parser<'a> {
     lines: Vec<&'a str>,
     pos: usize,
     indent_size: usize,
     explicit_indent: Option<usize>,
+    /// Common leading indentation of the document; depths are measured relative to it
+    base_indent: usize,
     strict: bool,
     expand_paths: &'a str,
+    bare_hyphen: BareHyphen,
+    resolve_aliases: bool,
+    parse_datetime: bool,
+    /// Only check structure: primitives decode to `None` and tabular rows are
+    /// not built (object keys are kept for conflict checks)
+    validate_only: bool,
+    /// Deviations tolerated in lenient mode, as `(1-based line, message)`
+    warnings: Vec<(usize, String)>,
+    /// Source line index of each entry in `lines`; empty unless lenient mode
+    /// dropped `#` comment lines
+    line_numbers: Vec<usize>,
+    max_depth: usize,
+    /// Objects and lists currently being parsed, outermost first
+    nesting: usize,
+    literals: Literals,
+    float_tokens: FloatTokens,
+    allow_trailing_delimiter: bool,
+    allow_tab_indent: bool,
+    leading_zero: LeadingZero,
+    empty_cell: EmptyCell,
+    /// Set when `object_pairs_hook` is, to keep the pairs of objects that
+    /// repeat a key
+    duplicate_pairs: Option<DuplicatePairs>,
 }
 
 impl<'a> Parser<'a> {
-    pub fn new(
+    pub fn new(input: &'a str, options: &'a DecodeOptions) -> Self {
+        Parser::with_open_table(input, options, None)
+    }
+
+    /// A parser for `input`, which continues the rows of a tabular array
+    /// whose header is indented by `table_indent` spaces, if given
+    fn with_open_table(
         input: &'a str,
-        strict: bool,
-        expand_paths: &'a str,
-        explicit_indent: Option<usize>,
+        options: &'a DecodeOptions,
+        table_indent: Option<usize>,
     ) -> Self {
-        let lines: Vec<&str> = input.lines().collect();
+        // Editors on Windows may save a byte order mark; it is not content
+        let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+        let mut lines = split_lines(input);
+        // Lenient mode drops full-line `#` comments, remembering where the
+        // remaining lines came from so errors still cite the right line
+        let mut line_numbers = Vec::new();
+        if !options.strict && lines.iter().any(|line| line.trim_start().starts_with('#')) {
+            let comments = comment_lines(&lines, table_indent);
+            (line_numbers, lines) = lines
+                .into_iter()
+                .enumerate()
+                .filter(|&(i, _)| !comments[i])
+                .unzip();
+        }
+        let base_indent = lines
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.chars().take_while(|&c| c == ' ').count())
+            .min()
+            .unwrap_or(0);
         Parser {
             lines,
             pos: 0,
             indent_size: 0,
-            explicit_indent,
-            strict,
-            expand_paths,
+            explicit_indent: options.indent,
+            base_indent,
+            strict: options.strict,
+            expand_paths: &options.expand_paths,
+            bare_hyphen: options.bare_hyphen,
+            resolve_aliases: options.resolve_aliases,
+            parse_datetime: options.parse_datetime,
+            validate_only: false,
+            warnings: Vec::new(),
+            line_numbers,
+            max_depth: options.max_depth,
+            nesting: 0,
+            literals: options.literals.clone(),
+            float_tokens: options.float_tokens.clone(),
+            allow_trailing_delimiter: options.allow_trailing_delimiter,
+            allow_tab_indent: options.allow_tab_indent,
+            leading_zero: options.leading_zero,
+            empty_cell: options.empty_cell,
+            duplicate_pairs: options
+                .object_pairs_hook
+                .is_some()
+                .then(DuplicatePairs::new),
+        }
+    }
+
+    /// Set `key` of an object being parsed. A repeated key replaces the
+    /// value, but `object_pairs_hook` is given every pair, so those of an
+    /// object are recorded from its first repeated key on.
+    fn set_field(&mut self, dict: &Bound<'_, PyDict>, key: &str, value: Py<PyAny>) -> PyResult<()> {
+        if let Some(duplicates) = &mut self.duplicate_pairs {
+            let py = dict.py();
+            let id = dict.as_ptr() as usize;
+            if let Some((_, pairs)) = duplicates.get_mut(&id) {
+                pairs.push((key.to_string(), value.clone_ref(py)));
+            } else if dict.contains(key)? {
+                let mut pairs = dict
+                    .iter()
+                    .map(|(key, value)| Ok((key.extract()?, value.unbind())))
+                    .collect::<PyResult<Vec<_>>>()?;
+                pairs.push((key.to_string(), value.clone_ref(py)));
+                duplicates.insert(id, (dict.clone().unbind(), pairs));
+            }
+        }
+        dict.set_item(key, value)
+    }
+
+    /// 1-based source line number of `lines[line_idx]`
+    fn line_number(&self, line_idx: usize) -> usize {
+        self.line_numbers.get(line_idx).copied().unwrap_or(line_idx) + 1
+    }
+
+    /// Index of the line `token` was sliced from, which callers may already
+    /// have moved `self.pos` past; `self.pos` for a token built elsewhere
+    fn token_line(&self, token: &str) -> usize {
+        let start = token.as_ptr() as usize;
+        let last = self.pos.min(self.lines.len().saturating_sub(1));
+        (0..=last)
+            .rev()
+            .find(|&idx| {
+                let line = self.lines.get(idx).map_or("", |line| line);
+                let begin = line.as_ptr() as usize;
+                (begin..=begin + line.len()).contains(&start)
+            })
+            .unwrap_or(self.pos)
+    }
+
+    /// Record a tolerated deviation at `line_idx` (deduplicated, since some
+    /// lines are inspected more than once)
+    fn warn_at(&mut self, line_idx: usize, msg: impl Into<String>) {
+        let warning = (self.line_number(line_idx), msg.into());
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
         }
     }
 
+    /// Go one nesting level deeper, raising instead once that would exceed
+    /// `max_depth`, so hostile input cannot overflow the stack. Pair with
+    /// `self.nesting -= 1` once the nested value is parsed.
+    fn enter_nested(&mut self, py: Python) -> PyResult<()> {
+        self.check_depth(py, self.nesting + 1)?;
+        self.nesting += 1;
+        Ok(())
+    }
+
+    fn check_depth(&self, py: Python, depth: usize) -> PyResult<()> {
+        if depth > self.max_depth {
+            return Err(self.err_here(
+                py,
+                format!("maximum nesting depth exceeded ({})", self.max_depth),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Expand the dotted key `segments` into nested objects under `dict`
+    fn merge_path(
+        &self,
+        py: Python,
+        dict: &Bound<'_, PyDict>,
+        segments: &[&str],
+        value: Py<PyAny>,
+    ) -> PyResult<()> {
+        // Each segment but the last adds an object below the current one
+        self.check_depth(py, self.nesting + segments.len() - 1)?;
+        deep_merge_path(py, dict, segments, value, self.strict)
+    }
+
     /// Build a `ToonDecodeError` with structured line context from `self.pos`.
     fn err_here(&self, py: Python, msg: impl Into<String>) -> PyErr {
         self.err_at(py, self.pos, msg)
@@ -236,7 +1278,7 @@ impl<'a> Parser<'a> {
             (None, None)
         } else {
             let clamped = line_idx.min(self.lines.len() - 1);
-            (Some(clamped + 1), Some(self.lines[clamped]))
+            (Some(self.line_number(clamped)), Some(self.lines[clamped]))
         };
         let formatted = match line_num {
             Some(n) => format!("TOON parse error at line {}: {}", n, msg.into()),
@@ -249,7 +1291,7 @@ impl<'a> Parser<'a> {
         // Auto-detect indent size by finding first indented line
         for line in &self.lines {
             if !line.trim().is_empty() && line.starts_with(' ') {
-                let spaces = line.chars().take_while(|&c| c == ' ').count();
+                let spaces = line.chars().take_while(|&c| c == ' ').count() - self.base_indent;
                 if spaces > 0 {
                     self.indent_size = spaces;
                     return;
@@ -260,11 +1302,9 @@ impl<'a> Parser<'a> {
         self.indent_size = 2;
     }
 
-    fn validate_indentation(&self, py: Python, line: &str) -> PyResult<()> {
-        if !self.strict {
-            return Ok(());
-        }
-
+    /// Check indentation of `line` (at `self.pos`); errors in strict mode,
+    /// records a warning otherwise
+    fn validate_indentation(&mut self, py: Python, line: &str) -> PyResult<()> {
         // Skip validation for lines that are only whitespace (empty lines)
         if line.trim().is_empty() {
             return Ok(());
@@ -273,9 +1313,14 @@ impl<'a> Parser<'a> {
         let indent_len = line.len() - line.trim_start().len();
         let indent_part = &line[..indent_len];
 
-        if indent_part.contains('\t') {
-            return Err(self.err_here(py, "Tabs are not allowed in indentation"));
+        if indent_part.contains('\t') && !self.allow_tab_indent {
+            if self.strict {
+                return Err(self.err_here(py, "Tabs are not allowed in indentation"));
+            }
+            self.warn_at(self.pos, "Tabs are not allowed in indentation");
+            return Ok(());
         }
+        let indent_len = self.get_indent_spaces(line);
 
         // Use explicit_indent if provided, otherwise use auto-detected indent_size
         let check_indent = if let Some(explicit) = self.explicit_indent {
@@ -284,14 +1329,15 @@ impl<'a> Parser<'a> {
             self.indent_size
         };
 
-        if check_indent > 0 && indent_len % check_indent != 0 {
-            return Err(self.err_here(
-                py,
-                format!(
-                    "Indentation {} is not a multiple of indent size {}",
-                    indent_len, check_indent
-                ),
-            ));
+        if check_indent > 0 && !indent_len.is_multiple_of(check_indent) {
+            let msg = format!(
+                "Indentation {} is not a multiple of indent size {}",
+                indent_len, check_indent
+            );
+            if self.strict {
+                return Err(self.err_here(py, msg));
+            }
+            self.warn_at(self.pos, msg);
         }
 
         Ok(())
@@ -318,16 +1364,34 @@ impl<'a> Parser<'a> {
         let first_line_trimmed = first_line.trim();
 
         // Check if it's a root array header - can be [N]: or [N]{fields}:
-        if first_line_trimmed.starts_with('[') && first_line_trimmed.contains(':') {
-            // Make sure it's not an object field by checking there's no space before [
-            if first_line == first_line_trimmed {
-                return self.parse_root_array(py);
-            }
+        // The header must sit at the document's base indentation (usually column 0)
+        if first_line_trimmed.starts_with('[')
+            && first_line_trimmed.contains(':')
+            && self.get_indent_spaces(first_line) == 0
+        {
+            return self.parse_root_array(py);
         }
 
-        // Check if it's a single primitive (one line, no colon outside quotes, not a header)
-        if self.lines.len() == 1 && self.find_key_value_colon(first_line_trimmed).is_none() {
-            return self.parse_primitive(py, first_line_trimmed);
+        // Check if it's a single primitive (one non-blank line, no colon outside
+        // quotes, not a header). Newlines inside a quoted string are escaped,
+        // so a root string is always one physical line.
+        if self.find_key_value_colon(first_line_trimmed).is_none() {
+            let more_lines = self.lines[self.pos + 1..]
+                .iter()
+                .any(|line| !line.trim().is_empty());
+            if !more_lines {
+                return self.parse_primitive(py, first_line_trimmed);
+            }
+            if first_line_trimmed.starts_with('#') {
+                return Err(self.err_here(py, COMMENT_IN_STRICT_MODE));
+            }
+            if !first_line_trimmed.starts_with('-') {
+                return Err(self.err_here(
+                    py,
+                    "A root primitive must be a single line; \
+                     write line breaks in a quoted string as \\n",
+                ));
+            }
         }
 
         // Otherwise, parse as object
@@ -363,6 +1427,13 @@ impl<'a> Parser<'a> {
     }
 
     pub fn parse_object(&mut self, py: Python, depth: usize) -> PyResult<Py<PyAny>> {
+        self.enter_nested(py)?;
+        let result = self.parse_object_fields(py, depth);
+        self.nesting -= 1;
+        result
+    }
+
+    fn parse_object_fields(&mut self, py: Python, depth: usize) -> PyResult<Py<PyAny>> {
         let dict = PyDict::new(py);
 
         while self.pos < self.lines.len() {
@@ -399,6 +1470,7 @@ impl<'a> Parser<'a> {
 
             if line_depth > depth {
                 // Shouldn't happen at start, skip
+                self.warn_at(self.pos, "Skipped over-indented line");
                 self.pos += 1;
                 continue;
             }
@@ -408,52 +1480,20 @@ impl<'a> Parser<'a> {
                 let key_part = &line_trimmed[..colon_pos];
                 let value_part = line_trimmed[colon_pos + 1..].trim();
 
-                // Check if this is an array syntax like: key[N] or "key"[N] or key[N]{fields}
-                let first_quote_pos = key_part.find('"');
-                let first_bracket_pos = key_part.find('[');
-
-                let has_array_syntax = match (first_quote_pos, first_bracket_pos) {
-                    (None, Some(_)) => key_part.contains(']'),
-                    (Some(q), Some(b)) if b < q => key_part.contains(']'),
-                    (Some(_q), Some(_b)) => {
-                        if let Some(last_quote) = key_part.rfind('"') {
-                            key_part[last_quote + 1..].contains('[')
-                                && key_part[last_quote + 1..].contains(']')
-                        } else {
-                            false
-                        }
-                    }
-                    _ => false,
-                };
-
                 // Check if key contains array header (e.g., key[N] or key[N]{fields})
-                if has_array_syntax {
+                if let Some(key_name) = self.array_field_key(line_trimmed, colon_pos) {
                     // Array as object value
                     let value = self.parse_field_array(py, line_trimmed, depth)?;
 
-                    // Extract key name before the array bracket
-                    let key_name = if key_part.starts_with('"') {
-                        // Quoted key - find the closing quote
-                        if let Some(close_quote) = key_part[1..].find('"').map(|p| p + 1) {
-                            &key_part[..close_quote + 1]
-                        } else {
-                            key_part.split('[').next().unwrap()
-                        }
-                    } else if let Some(first_bracket) = key_part.find('[') {
-                        &key_part[..first_bracket]
-                    } else {
-                        key_part
-                    };
-
                     // Check for path expansion on the key name
                     let (should_expand, was_quoted) = self.should_expand_key(key_name);
                     if should_expand {
                         if let Some(segments) = split_dotted_key(key_name) {
-                            deep_merge_path(py, &dict, &segments, value, self.strict)?;
+                            self.merge_path(py, &dict, &segments, value)?;
                         } else {
                             check_key_conflict(&dict, key_name, value.bind(py), self.strict)?;
                             let key = self.parse_key(py, key_name)?;
-                            dict.set_item(key, value)?;
+                            self.set_field(&dict, &key, value)?;
                         }
                     } else {
                         let key = if was_quoted {
@@ -462,7 +1502,7 @@ impl<'a> Parser<'a> {
                             key_name.to_string()
                         };
                         check_key_conflict(&dict, &key, value.bind(py), self.strict)?;
-                        dict.set_item(key, value)?;
+                        self.set_field(&dict, &key, value)?;
                     }
                     continue;
                 }
@@ -511,14 +1551,14 @@ impl<'a> Parser<'a> {
                     // Apply path expansion if enabled
                     if should_expand && !was_quoted {
                         if let Some(segments) = split_dotted_key(&parsed_key) {
-                            deep_merge_path(py, &dict, &segments, value, self.strict)?;
+                            self.merge_path(py, &dict, &segments, value)?;
                         } else {
                             check_key_conflict(&dict, &parsed_key, value.bind(py), self.strict)?;
-                            dict.set_item(parsed_key, value)?;
+                            self.set_field(&dict, &parsed_key, value)?;
                         }
                     } else {
                         check_key_conflict(&dict, &parsed_key, value.bind(py), self.strict)?;
-                        dict.set_item(parsed_key, value)?;
+                        self.set_field(&dict, &parsed_key, value)?;
                     }
                 } else {
                     // Primitive value
@@ -527,16 +1567,19 @@ impl<'a> Parser<'a> {
                     // Apply path expansion if enabled
                     if should_expand && !was_quoted {
                         if let Some(segments) = split_dotted_key(&parsed_key) {
-                            deep_merge_path(py, &dict, &segments, value, self.strict)?;
+                            self.merge_path(py, &dict, &segments, value)?;
                         } else {
                             check_key_conflict(&dict, &parsed_key, value.bind(py), self.strict)?;
-                            dict.set_item(parsed_key, value)?;
+                            self.set_field(&dict, &parsed_key, value)?;
                         }
                     } else {
                         check_key_conflict(&dict, &parsed_key, value.bind(py), self.strict)?;
-                        dict.set_item(parsed_key, value)?;
+                        self.set_field(&dict, &parsed_key, value)?;
                     }
                 }
+            } else if line_trimmed.starts_with('#') {
+                // Only lenient mode drops comments before parsing
+                return Err(self.err_here(py, COMMENT_IN_STRICT_MODE));
             } else {
                 // Missing colon error
                 return Err(self.err_here(py, format!("Missing colon in line: {}", line_trimmed)));
@@ -574,11 +1617,11 @@ impl<'a> Parser<'a> {
     }
 
     pub fn parse_header(
-        &self,
+        &mut self,
         py: Python,
         header: &str,
         header_line_idx: usize,
-    ) -> PyResult<(usize, char, Option<Vec<String>>)> {
+    ) -> PyResult<(usize, char, Option<Vec<TabularField>>)> {
         let trimmed = header.trim();
 
         let bracket_start = self
@@ -600,14 +1643,15 @@ impl<'a> Parser<'a> {
             ));
         }
 
-        let (length_str, delimiter) = if bracket_content.contains('\t') {
-            let parts: Vec<&str> = bracket_content.split('\t').collect();
-            (parts[0], '\t')
-        } else if bracket_content.contains('|') {
-            let parts: Vec<&str> = bracket_content.split('|').collect();
-            (parts[0], '|')
-        } else {
-            (bracket_content, ',')
+        // `[N]` means commas; any other delimiter follows the length
+        let digits_end = bracket_content
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(bracket_content.len());
+        let mut marker = bracket_content[digits_end..].chars();
+        let (length_str, delimiter) = match (marker.next(), marker.next()) {
+            (Some(ch), None) if is_valid_delimiter(ch) => (&bracket_content[..digits_end], ch),
+            // Anything else fails to parse as a length below
+            _ => (bracket_content, ','),
         };
 
         let length = length_str.parse::<usize>().map_err(|_| {
@@ -618,28 +1662,50 @@ impl<'a> Parser<'a> {
             )
         })?;
 
-        let substring_after_bracket = &trimmed[bracket_end..];
-        let colon_pos = self
-            .find_unquoted_char(substring_after_bracket, ':')
-            .unwrap_or(substring_after_bracket.len());
-        let fields = if let Some(brace_start) = substring_after_bracket[..colon_pos].find('{') {
-            let brace_end_relative =
-                substring_after_bracket[..colon_pos]
-                    .find('}')
-                    .ok_or_else(|| {
-                        self.err_at(py, header_line_idx, "Invalid field list: missing '}'")
-                    })?;
-
-            let field_content = &substring_after_bracket[brace_start + 1..brace_end_relative];
-            let field_parts = self.split_by_delimiter(field_content, delimiter);
-            let field_names: Vec<String> = field_parts
+        // Fields may carry `:type` annotations, so the list ends at the
+        // closing brace rather than at the first colon
+        let after_bracket = trimmed[bracket_end + 1..].trim_start();
+        let fields = if let Some(field_list) = after_bracket.strip_prefix('{') {
+            let brace_end = find_unquoted_char(field_list, '}').ok_or_else(|| {
+                self.err_at(py, header_line_idx, "Invalid field list: missing '}'")
+            })?;
+
+            let field_list = &field_list[..brace_end];
+            let mut field_parts = split_by_delimiter(field_list, delimiter);
+            // A single field holding another unquoted delimiter is a list
+            // written with the wrong one, as no unquoted key contains it
+            if field_parts.len() == 1
+                && let Some(actual) = [',', '|', '\t']
+                    .into_iter()
+                    .find(|&d| d != delimiter && find_unquoted_char(field_list, d).is_some())
+            {
+                let message = format!(
+                    "Fields are separated by {:?} but the header declares {:?}",
+                    actual, delimiter
+                );
+                if self.strict {
+                    return Err(self.err_at(py, header_line_idx, message));
+                }
+                self.warn_at(header_line_idx, message);
+                field_parts = split_by_delimiter(field_list, actual);
+            }
+            let fields = field_parts
                 .iter()
-                .map(|f| {
-                    self.parse_key(py, f.trim())
-                        .unwrap_or_else(|_| f.trim().to_string())
-                })
-                .collect();
-            Some(field_names)
+                .map(|f| self.parse_field(py, f.trim(), header_line_idx))
+                .collect::<PyResult<Vec<_>>>()?;
+            // A repeated name would have its later column overwrite the
+            // earlier one in every row
+            let mut seen = HashSet::new();
+            for field in &fields {
+                if !seen.insert(field.name.as_str()) {
+                    let message = format!("Duplicate field name in header: {}", field.name);
+                    if self.strict {
+                        return Err(self.err_at(py, header_line_idx, message));
+                    }
+                    self.warn_at(header_line_idx, message);
+                }
+            }
+            Some(fields)
         } else {
             None
         };
@@ -647,17 +1713,85 @@ impl<'a> Parser<'a> {
         Ok((length, delimiter, fields))
     }
 
+    /// Parse one field of a tabular header, with its optional `:type`.
+    /// Unknown types raise in strict mode; lenient mode leaves the column
+    /// untyped.
+    fn parse_field(
+        &mut self,
+        py: Python,
+        field: &str,
+        header_line_idx: usize,
+    ) -> PyResult<TabularField> {
+        let (name, column_type) = match find_unquoted_char(field, ':') {
+            Some(colon) => {
+                let annotation = field[colon + 1..].trim();
+                let column_type = ColumnType::from_annotation(annotation);
+                if column_type.is_none() {
+                    let message = format!("Unknown column type: {}", annotation);
+                    if self.strict {
+                        return Err(self.err_at(py, header_line_idx, message));
+                    }
+                    self.warn_at(header_line_idx, message);
+                }
+                (field[..colon].trim(), column_type)
+            }
+            None => (field, None),
+        };
+        let name = if name.trim().is_empty() {
+            let message = "Empty field name must be quoted as \"\"";
+            if self.strict {
+                return Err(self.err_at(py, header_line_idx, message));
+            }
+            self.warn_at(header_line_idx, message);
+            String::new()
+        } else {
+            self.parse_key(py, name)
+                .unwrap_or_else(|_| name.to_string())
+        };
+        Ok(TabularField { name, column_type })
+    }
+
     pub fn parse_tabular_array(
         &mut self,
         py: Python,
         length: usize,
         delimiter: char,
-        fields: &[String],
+        fields: &[TabularField],
         expected_depth: usize,
         header_line_idx: usize,
     ) -> PyResult<Py<PyAny>> {
-        let list = PyList::empty(py);
+        // Each row takes a line; never trust a huge declared length blindly
+        let mut rows = Vec::with_capacity(length.min(self.lines.len() - self.pos));
+
+        while let Some(row) = self.next_tabular_row(py, delimiter, fields, expected_depth)? {
+            rows.push(row);
+        }
+
+        let actual_len = rows.len();
+        if actual_len != length {
+            return Err(self.err_at(
+                py,
+                header_line_idx,
+                format!(
+                    "Array declared length {} but found {} elements",
+                    length, actual_len
+                ),
+            ));
+        }
+
+        Ok(PyList::new(py, rows)?.into())
+    }
 
+    /// Decode the tabular row at `self.pos`, skipping blank and over-indented
+    /// lines before it. Returns `None` once the rows end: at a shallower or
+    /// non-row line, or when `self.lines` runs out.
+    fn next_tabular_row(
+        &mut self,
+        py: Python,
+        delimiter: char,
+        fields: &[TabularField],
+        expected_depth: usize,
+    ) -> PyResult<Option<Py<PyAny>>> {
         while self.pos < self.lines.len() {
             let line = self.lines[self.pos];
             let line_trimmed = line.trim();
@@ -667,10 +1801,11 @@ impl<'a> Parser<'a> {
                 let line_depth = self.get_depth(line);
 
                 if line_depth < expected_depth {
-                    break;
+                    return Ok(None);
                 }
 
                 if line_depth > expected_depth {
+                    self.warn_at(self.pos, "Skipped over-indented line");
                     self.pos += 1;
                     continue;
                 }
@@ -683,72 +1818,195 @@ impl<'a> Parser<'a> {
                 if lookahead < self.lines.len() {
                     let next_depth = self.get_depth(self.lines[lookahead]);
                     if next_depth < expected_depth {
-                        break;
+                        return Ok(None);
                     }
                 }
 
                 if self.strict {
                     return Err(self.err_here(py, "Blank line inside array"));
                 }
+                self.warn_at(self.pos, "Blank line inside array");
                 self.pos += 1;
                 continue;
             }
 
-            if !self.is_tabular_row(line_trimmed, delimiter) {
-                break;
+            if !is_tabular_row(line_trimmed, delimiter) {
+                return Ok(None);
             }
 
-            let values = self.split_by_delimiter(line_trimmed, delimiter);
+            let values = self.split_row(py, line_trimmed, delimiter, fields.len(), self.pos)?;
 
             if values.len() != fields.len() {
+                // Name what is off so the bad cell is easy to spot in a wide row
+                let (kind, names) = if values.len() < fields.len() {
+                    let names = fields[values.len()..].iter().map(|f| f.name.as_str());
+                    ("missing", names.collect::<Vec<_>>())
+                } else {
+                    ("extra", values[fields.len()..].to_vec())
+                };
                 return Err(self.err_here(
                     py,
                     format!(
-                        "Tabular row has {} values but header defines {} fields",
+                        "Tabular row has {} values but header defines {} fields ({} {}): {}",
                         values.len(),
-                        fields.len()
+                        fields.len(),
+                        kind,
+                        names.join(", "),
+                        line_trimmed
                     ),
                 ));
             }
 
+            if self.validate_only {
+                for (field, value) in fields.iter().zip(values) {
+                    self.parse_cell(py, field, value)?;
+                }
+                // Only the row count matters
+                self.pos += 1;
+                return Ok(Some(py.None()));
+            }
+
             let dict = PyDict::new(py);
 
-            for (i, field) in fields.iter().enumerate() {
-                if i < values.len() {
-                    let value = self.parse_primitive(py, values[i])?;
-                    dict.set_item(field, value)?;
-                }
+            for (field, value) in fields.iter().zip(values) {
+                let value = self.parse_cell(py, field, value)?;
+                dict.set_item(&field.name, value)?;
             }
 
-            list.append(dict)?;
             self.pos += 1;
+            return Ok(Some(dict.into()));
         }
 
-        let actual_len = list.len();
-        if length > 0 && actual_len != length {
-            return Err(self.err_at(
-                py,
-                header_line_idx,
-                format!(
-                    "Array declared length {} but found {} elements",
-                    length, actual_len
-                ),
-            ));
+        Ok(None)
+    }
+
+    /// Decode a tabular cell, converting it to its column's type if the
+    /// header declares one. `null` fits any column.
+    fn parse_cell(&mut self, py: Python, field: &TabularField, token: &str) -> PyResult<Py<PyAny>> {
+        if let Some(list) = self.parse_array_cell(py, token)? {
+            return Ok(list);
+        }
+        if self.is_null_cell(token) {
+            return Ok(py.None());
+        }
+        let Some(column_type) = field.column_type else {
+            return self.parse_primitive(py, token);
+        };
+        // Alternate literals stand for the standard ones in any column
+        let standard = self.standard_literal(token);
+        if standard == "null" {
+            return Ok(py.None());
+        }
+        // Special float tokens are quoted, whatever the column's type
+        if let Some(f) = token
+            .trim()
+            .strip_prefix('"')
+            .and_then(|t| t.strip_suffix('"'))
+            .and_then(|t| self.float_tokens.value(t))
+        {
+            return Ok(PyFloat::new(py, f).into_any().unbind());
+        }
+        let number = self.unsigned_number(token);
+        let value = match column_type {
+            ColumnType::Int | ColumnType::Float if number.is_none() => None,
+            ColumnType::Int => {
+                let token = number.unwrap_or(token);
+                let digits = token.strip_prefix('-').unwrap_or(token);
+                if let Ok(i) = token.parse::<i64>() {
+                    Some(PyInt::new(py, i).into_any().unbind())
+                } else if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+                    // Too long for i64: let Python parse it exactly
+                    Some(py.get_type::<PyInt>().call1((token,))?.unbind())
+                } else {
+                    // Integral floats such as `2.0` or `1e3` also fit
+                    match token.parse::<f64>() {
+                        Ok(f) if f.is_finite() && f.fract() == 0.0 => {
+                            Some(py.get_type::<PyInt>().call1((f,))?.unbind())
+                        }
+                        _ => None,
+                    }
+                }
+            }
+            ColumnType::Float => number
+                .unwrap_or(token)
+                .parse::<f64>()
+                .ok()
+                .map(|f| PyFloat::new(py, f).into_any().unbind()),
+            // Quoted `"true"`/`"false"` were written as text but the header
+            // says the column is boolean
+            ColumnType::Bool => match standard {
+                "true" | "\"true\"" => Some(PyBool::new(py, true).to_owned().into_any().unbind()),
+                "false" | "\"false\"" => {
+                    Some(PyBool::new(py, false).to_owned().into_any().unbind())
+                }
+                _ => None,
+            },
+            // Quoted cells are unescaped; anything else is kept verbatim
+            ColumnType::Str => {
+                let token = token.trim();
+                let text = match token.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+                    Some(inner) => self.unescape_string(py, inner)?,
+                    // Let the untyped path report the unterminated string
+                    None if token.starts_with('"') => return self.parse_primitive(py, token),
+                    None => token.to_string(),
+                };
+                Some(PyString::new(py, &text).into_any().unbind())
+            }
+            // Only `null` itself, handled above, fits a null column
+            ColumnType::Null => None,
+        };
+        match value {
+            Some(value) => Ok(value),
+            None => {
+                let message = format!(
+                    "Column '{}' is typed {} but holds {}",
+                    field.name,
+                    column_type.annotation(),
+                    token
+                );
+                if self.strict {
+                    return Err(self.err_here(py, message));
+                }
+                self.warn_at(self.pos, message);
+                self.parse_primitive(py, token)
+            }
+        }
+    }
+
+    /// Decode a cell holding an inline array, as `dumps` writes with
+    /// `tabular_nested_arrays=True` (`[2|]: a|b`). `None` if the cell is not
+    /// one; strings starting with `[` are always quoted, so none is mistaken.
+    fn parse_array_cell(&mut self, py: Python, token: &str) -> PyResult<Option<Py<PyAny>>> {
+        let trimmed = token.trim();
+        if !trimmed.starts_with('[') {
+            return Ok(None);
         }
+        let Some(header_end) = trimmed.find("]:").map(|i| i + 2) else {
+            return Ok(None);
+        };
+        let Ok((length, delimiter, None)) = self.parse_header(py, &trimmed[..header_end], self.pos)
+        else {
+            return Ok(None);
+        };
+        let values = trimmed[header_end..].trim();
+        self.parse_inline_array(py, values, delimiter, length, self.pos)
+            .map(Some)
+    }
 
-        Ok(list.into())
+    /// Whether `token`, a value between delimiters, is empty and decodes to
+    /// `None` under `empty_cell="null"`
+    fn is_null_cell(&self, token: &str) -> bool {
+        self.empty_cell == EmptyCell::Null && token.trim().is_empty()
     }
 
     pub fn parse_inline_array(
-        &self,
+        &mut self,
         py: Python,
         values_str: &str,
         delimiter: char,
         length: usize,
         header_line_idx: usize,
     ) -> PyResult<Py<PyAny>> {
-        let list = PyList::empty(py);
-
         if values_str.is_empty() {
             if length > 0 {
                 return Err(self.err_at(
@@ -757,29 +2015,36 @@ impl<'a> Parser<'a> {
                     format!("Array declared length {} but found 0 elements", length),
                 ));
             }
-            return Ok(list.into());
+            return Ok(PyList::empty(py).into());
         }
 
-        let values = self.split_by_delimiter(values_str, delimiter);
+        let values = self.split_row(py, values_str, delimiter, length, header_line_idx)?;
 
-        if length > 0 && values.len() != length {
+        if values.len() != length {
             return Err(self.err_at(
                 py,
                 header_line_idx,
                 format!(
-                    "Array declared length {} but found {} elements",
+                    "Array declared length {} but found {} elements: {}",
                     length,
-                    values.len()
+                    values.len(),
+                    values_str
                 ),
             ));
         }
 
-        for value_str in values {
-            let value = self.parse_primitive(py, value_str)?;
-            list.append(value)?;
-        }
+        let items = values
+            .into_iter()
+            .map(|value_str| {
+                if self.is_null_cell(value_str) {
+                    Ok(py.None())
+                } else {
+                    self.parse_primitive(py, value_str)
+                }
+            })
+            .collect::<PyResult<Vec<_>>>()?;
 
-        Ok(list.into())
+        Ok(PyList::new(py, items)?.into())
     }
 
     pub fn parse_expanded_array(
@@ -789,7 +2054,21 @@ impl<'a> Parser<'a> {
         expected_depth: usize,
         header_line_idx: usize,
     ) -> PyResult<Py<PyAny>> {
-        let list = PyList::empty(py);
+        self.enter_nested(py)?;
+        let result = self.parse_expanded_items(py, length, expected_depth, header_line_idx);
+        self.nesting -= 1;
+        result
+    }
+
+    fn parse_expanded_items(
+        &mut self,
+        py: Python,
+        length: usize,
+        expected_depth: usize,
+        header_line_idx: usize,
+    ) -> PyResult<Py<PyAny>> {
+        // Each item takes at least a line
+        let mut items: Vec<Py<PyAny>> = Vec::with_capacity(length.min(self.lines.len() - self.pos));
 
         while self.pos < self.lines.len() {
             let line = self.lines[self.pos];
@@ -804,6 +2083,7 @@ impl<'a> Parser<'a> {
                 }
 
                 if line_depth > expected_depth {
+                    self.warn_at(self.pos, "Skipped over-indented line");
                     self.pos += 1;
                     continue;
                 }
@@ -823,6 +2103,7 @@ impl<'a> Parser<'a> {
                 if self.strict {
                     return Err(self.err_here(py, "Blank line inside array"));
                 }
+                self.warn_at(self.pos, "Blank line inside array");
                 self.pos += 1;
                 continue;
             }
@@ -842,8 +2123,26 @@ impl<'a> Parser<'a> {
             self.pos += 1;
 
             if item_str.is_empty() {
-                let empty_obj = PyDict::new(py);
-                list.append(empty_obj)?;
+                match self.bare_hyphen {
+                    BareHyphen::EmptyObject => items.push(PyDict::new(py).into_any().unbind()),
+                    BareHyphen::Null => items.push(py.None()),
+                }
+                continue;
+            }
+
+            if item_str.starts_with('[') && item_str.contains("]{") && item_str.ends_with("}:") {
+                // Tabular array as a list item: `- [N]{fields}:` with rows one level deeper
+                let (inner_len, inner_delim, fields) =
+                    self.parse_header(py, item_str, item_line_idx)?;
+                let value = self.parse_tabular_array(
+                    py,
+                    inner_len,
+                    inner_delim,
+                    &fields.unwrap_or_default(),
+                    expected_depth + 1,
+                    item_line_idx,
+                )?;
+                items.push(value);
                 continue;
             }
 
@@ -862,7 +2161,7 @@ impl<'a> Parser<'a> {
                         expected_depth + 1,
                         item_line_idx,
                     )?;
-                    list.append(value)?;
+                    items.push(value);
                 } else {
                     let value = self.parse_inline_array(
                         py,
@@ -871,20 +2170,20 @@ impl<'a> Parser<'a> {
                         inner_len,
                         item_line_idx,
                     )?;
-                    list.append(value)?;
+                    items.push(value);
                 }
             } else if self.find_key_value_colon(item_str).is_some() {
                 self.pos -= 1;
                 let value = self.parse_list_item_object(py, expected_depth)?;
-                list.append(value)?;
+                items.push(value);
             } else {
                 let value = self.parse_primitive(py, item_str)?;
-                list.append(value)?;
+                items.push(value);
             }
         }
 
-        let actual_len = list.len();
-        if length > 0 && actual_len != length {
+        let actual_len = items.len();
+        if actual_len != length {
             return Err(self.err_at(
                 py,
                 header_line_idx,
@@ -895,54 +2194,50 @@ impl<'a> Parser<'a> {
             ));
         }
 
-        Ok(list.into())
+        Ok(PyList::new(py, items)?.into())
     }
 
     fn parse_list_item_object(&mut self, py: Python, list_depth: usize) -> PyResult<Py<PyAny>> {
+        self.enter_nested(py)?;
+        let result = self.parse_list_item_fields(py, list_depth);
+        self.nesting -= 1;
+        result
+    }
+
+    fn parse_list_item_fields(&mut self, py: Python, list_depth: usize) -> PyResult<Py<PyAny>> {
         let dict = PyDict::new(py);
         let line = self.lines[self.pos];
         let line_trimmed = line.trim();
 
-        if let Some(item_content) = line_trimmed.strip_prefix("- ") {
-            if let Some(colon_pos) = item_content.find(':') {
-                let key_part = &item_content[..colon_pos];
-                let value_part = item_content[colon_pos + 1..].trim();
-
-                let quote_end_pos = key_part.rfind('"');
-
-                let has_array_syntax = if let Some(quote_end) = quote_end_pos {
-                    key_part[quote_end + 1..].contains('[')
-                        && key_part[quote_end + 1..].contains(']')
-                } else {
-                    key_part.contains('[') && key_part.contains(']')
-                };
+        if let Some(item_content) = line_trimmed.strip_prefix("- ")
+            && let Some(colon_pos) = self.find_key_value_colon(item_content)
+        {
+            let key_part = &item_content[..colon_pos];
+            let value_part = item_content[colon_pos + 1..].trim();
 
-                if has_array_syntax {
-                    let value = self.parse_field_array(py, item_content, list_depth + 1)?;
+            if let Some(key_name) = self.array_field_key(item_content, colon_pos) {
+                let value = self.parse_field_array(py, item_content, list_depth + 1)?;
+                let key = self.parse_key(py, key_name)?;
+                self.set_field(&dict, &key, value)?;
+            } else {
+                let key = self.parse_key(py, key_part)?;
+                self.pos += 1;
 
-                    let key_name = if let Some(quote_end) = quote_end_pos {
-                        &key_part[..quote_end + 1]
+                if value_part.is_empty() {
+                    // `- key:` with nothing nested below is an empty object;
+                    // lines at `list_depth + 1` are the item's next fields
+                    let has_children = self.pos < self.lines.len()
+                        && self.get_depth(self.lines[self.pos]) > list_depth + 1;
+                    let value = if has_children {
+                        let depth = self.nested_object_depth(list_depth + 2);
+                        self.parse_object(py, depth)?
                     } else {
-                        key_part.split('[').next().unwrap()
+                        PyDict::new(py).into()
                     };
-                    let key = self.parse_key(py, key_name)?;
-                    dict.set_item(key, value)?;
+                    self.set_field(&dict, &key, value)?;
                 } else {
-                    let key = self.parse_key(py, key_part)?;
-                    self.pos += 1;
-
-                    if value_part.is_empty() {
-                        if self.pos < self.lines.len() {
-                            let next_depth = self.get_depth(self.lines[self.pos]);
-                            if next_depth > list_depth + 1 {
-                                let value = self.parse_object(py, list_depth + 2)?;
-                                dict.set_item(key, value)?;
-                            }
-                        }
-                    } else {
-                        let value = self.parse_primitive(py, value_part)?;
-                        dict.set_item(key, value)?;
-                    }
+                    let value = self.parse_primitive(py, value_part)?;
+                    self.set_field(&dict, &key, value)?;
                 }
             }
         }
@@ -957,34 +2252,20 @@ impl<'a> Parser<'a> {
             }
 
             if line_depth != list_depth + 1 {
+                self.warn_at(self.pos, "Skipped over-indented line");
                 self.pos += 1;
                 continue;
             }
 
             let line_trimmed = line.trim();
-            if let Some(colon_pos) = line_trimmed.find(':') {
+            if let Some(colon_pos) = self.find_key_value_colon(line_trimmed) {
                 let key_part = &line_trimmed[..colon_pos];
                 let value_part = line_trimmed[colon_pos + 1..].trim();
 
-                let quote_end_pos = key_part.rfind('"');
-
-                let has_array_syntax = if let Some(quote_end) = quote_end_pos {
-                    key_part[quote_end + 1..].contains('[')
-                        && key_part[quote_end + 1..].contains(']')
-                } else {
-                    key_part.contains('[') && key_part.contains(']')
-                };
-
-                if has_array_syntax {
+                if let Some(key_name) = self.array_field_key(line_trimmed, colon_pos) {
                     let value = self.parse_field_array(py, line_trimmed, list_depth + 1)?;
-
-                    let key_name = if let Some(quote_end) = quote_end_pos {
-                        &key_part[..quote_end + 1]
-                    } else {
-                        key_part.split('[').next().unwrap()
-                    };
                     let key = self.parse_key(py, key_name)?;
-                    dict.set_item(key, value)?;
+                    self.set_field(&dict, &key, value)?;
                     continue;
                 }
 
@@ -992,13 +2273,18 @@ impl<'a> Parser<'a> {
                 self.pos += 1;
 
                 if value_part.is_empty() {
-                    let value = self.parse_object(py, line_depth + 1)?;
-                    dict.set_item(key, value)?;
+                    let depth = self.nested_object_depth(line_depth + 1);
+                    let value = self.parse_object(py, depth)?;
+                    self.set_field(&dict, &key, value)?;
                 } else {
                     let value = self.parse_primitive(py, value_part)?;
-                    dict.set_item(key, value)?;
+                    self.set_field(&dict, &key, value)?;
                 }
             } else {
+                self.warn_at(
+                    self.pos,
+                    format!("Skipped line without colon: {}", line_trimmed),
+                );
                 self.pos += 1;
             }
         }
@@ -1006,38 +2292,122 @@ impl<'a> Parser<'a> {
         Ok(dict.into())
     }
 
+    /// Depth of the object nested under a value-less list item field, whose
+    /// fields belong at `depth`. Lenient mode with auto-detected indentation
+    /// takes the depth of a deeper next line instead, as `parse_object_fields`
+    /// does, so an over-indented block is kept rather than skipped.
+    fn nested_object_depth(&self, depth: usize) -> usize {
+        if self.strict || self.explicit_indent.is_some() || self.pos >= self.lines.len() {
+            return depth;
+        }
+        let next_line = self.lines[self.pos];
+        if next_line.trim().starts_with('-') {
+            return depth;
+        }
+        self.get_depth(next_line).max(depth)
+    }
+
+    /// The standard literal (`null`, `true` or `false`) an unquoted token
+    /// stands for: a configured alternate literal or, in lenient mode, any
+    /// casing of `true`, `false`, `null`, `none` or `nil`. Other tokens are
+    /// returned unchanged.
+    fn standard_literal<'t>(&self, token: &'t str) -> &'t str {
+        let token = self.literals.standard(token);
+        if self.strict {
+            return token;
+        }
+        if token.eq_ignore_ascii_case("true") {
+            "true"
+        } else if token.eq_ignore_ascii_case("false") {
+            "false"
+        } else if ["null", "none", "nil"]
+            .iter()
+            .any(|null| token.eq_ignore_ascii_case(null))
+        {
+            "null"
+        } else {
+            token
+        }
+    }
+
     fn parse_primitive(&self, py: Python, s: &str) -> PyResult<Py<PyAny>> {
         let trimmed = s.trim();
 
         if trimmed.starts_with('"') {
             if !trimmed.ends_with('"') || trimmed.len() < 2 {
-                return Err(self.err_here(py, "Unterminated string"));
+                return Err(self.err_at(py, self.token_line(trimmed), "Unterminated string"));
             }
             let unescaped = self.unescape_string(py, &trimmed[1..trimmed.len() - 1])?;
+            if self.validate_only {
+                return Ok(py.None());
+            }
+            if let Some(f) = self.float_tokens.value(&unescaped) {
+                return Ok(PyFloat::new(py, f).into());
+            }
+            if self.parse_datetime
+                && let Some(value) = parse_iso_datetime(py, &unescaped)
+            {
+                return Ok(value.unbind());
+            }
             return Ok(PyString::new(py, &unescaped).into());
         }
 
-        match trimmed {
+        // Unquoted tokens always decode to something
+        if self.validate_only {
+            return Ok(py.None());
+        }
+
+        if self.parse_datetime
+            && let Some(value) = parse_iso_datetime(py, trimmed)
+        {
+            return Ok(value.unbind());
+        }
+
+        if self.resolve_aliases && (trimmed == "*" || trimmed.starts_with("*/")) {
+            let line_idx = self.token_line(trimmed);
+            let alias = PendingAlias {
+                path: trimmed[1..].to_string(),
+                line: self.line_number(line_idx),
+                source: self.lines.get(line_idx).copied().unwrap_or("").to_string(),
+            };
+            return Ok(Py::new(py, alias)?.into_any());
+        }
+
+        match self.standard_literal(trimmed) {
             "null" => Ok(py.None()),
             "true" => Ok(PyBool::new(py, true).to_owned().into()),
             "false" => Ok(PyBool::new(py, false).to_owned().into()),
             _ => {
-                let check_s = if trimmed.starts_with('-') {
-                    &trimmed[1..]
+                // Lenient mode reads `0xff`, `0o17` and `0b1010` as integers
+                if !self.strict
+                    && let Some((negative, radix, digits)) = split_radix_literal(trimmed)
+                {
+                    return radix_int(py, negative, radix, digits);
+                }
+                let Some(unsigned) = self.unsigned_number(trimmed) else {
+                    return Ok(PyString::new(py, trimmed).into());
+                };
+                // Lenient mode reads `1_000` as `1000`
+                let separated = if self.strict {
+                    None
                 } else {
-                    trimmed
+                    strip_digit_separators(unsigned)
                 };
+                let number = separated.as_deref().unwrap_or(unsigned);
+                let check_s = number.strip_prefix('-').unwrap_or(number);
 
-                if check_s.len() > 1
+                // `007` stays a string unless `leading_zero="number"`
+                if self.leading_zero == LeadingZero::String
+                    && check_s.len() > 1
                     && check_s.starts_with('0')
                     && check_s.chars().nth(1).unwrap().is_ascii_digit()
                 {
                     return Ok(PyString::new(py, trimmed).into());
                 }
 
-                if let Ok(i) = trimmed.parse::<i64>() {
+                if let Ok(i) = number.parse::<i64>() {
                     Ok(PyInt::new(py, i).into())
-                } else if let Ok(f) = trimmed.parse::<f64>() {
+                } else if let Ok(f) = number.parse::<f64>() {
                     Ok(PyFloat::new(py, f).into())
                 } else {
                     Ok(PyString::new(py, trimmed).into())
@@ -1046,6 +2416,17 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// `token` ready for number parsing, which would accept a leading `+`
+    /// on its own: lenient mode drops the sign of `+5`, strict mode keeps
+    /// any token starting with `+` a string (`None`)
+    fn unsigned_number<'t>(&self, token: &'t str) -> Option<&'t str> {
+        match token.strip_prefix('+') {
+            None => Some(token),
+            Some(_) if self.strict => None,
+            Some(_) => strip_plus_sign(token),
+        }
+    }
+
     fn should_expand_key(&self, key: &str) -> (bool, bool) {
         let trimmed = key.trim();
         let was_quoted = trimmed.starts_with('"') && trimmed.ends_with('"');
@@ -1058,6 +2439,7 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Byte offset of the first unquoted `[` (safe to use for slicing `line`)
     fn find_array_bracket_start(&self, line: &str) -> Option<usize> {
         let mut in_quotes = false;
         let mut escape_next = false;
@@ -1086,6 +2468,16 @@ impl<'a> Parser<'a> {
         None
     }
 
+    /// The key of a `key[N]...:` field line, whose first unquoted `[` and
+    /// a `]` come before its key-value colon at `colon_pos`; `None` for other
+    /// fields. Quoted keys and field names may hold `[`, `:` or delimiters.
+    fn array_field_key<'b>(&self, line: &'b str, colon_pos: usize) -> Option<&'b str> {
+        let bracket = self.find_array_bracket_start(line)?;
+        (bracket < colon_pos && line[bracket..colon_pos].contains(']'))
+            .then(|| line[..bracket].trim_end())
+    }
+
+    /// Byte offset of the first unquoted `:` (safe to use for slicing `line`)
     fn find_key_value_colon(&self, line: &str) -> Option<usize> {
         let mut in_quotes = false;
         let mut escape_next = false;
@@ -1114,10 +2506,20 @@ impl<'a> Parser<'a> {
         None
     }
 
-    fn parse_key(&self, py: Python, s: &str) -> PyResult<String> {
+    fn parse_key(&mut self, py: Python, s: &str) -> PyResult<String> {
         let trimmed = s.trim();
 
-        if trimmed.starts_with('"') && trimmed.ends_with('"') {
+        // The empty key is written `""`; a bare one is a missing key
+        if trimmed.is_empty() {
+            let message = "Empty key must be quoted as \"\"";
+            if self.strict {
+                return Err(self.err_here(py, message));
+            }
+            self.warn_at(self.pos, message);
+            return Ok(String::new());
+        }
+
+        if trimmed.len() >= 2 && trimmed.starts_with('"') && trimmed.ends_with('"') {
             self.unescape_string(py, &trimmed[1..trimmed.len() - 1])
         } else {
             Ok(trimmed.to_string())
@@ -1125,138 +2527,76 @@ impl<'a> Parser<'a> {
     }
 
     fn unescape_string(&self, py: Python, s: &str) -> PyResult<String> {
-        let mut result = String::new();
-        let mut chars = s.chars();
-
-        while let Some(ch) = chars.next() {
-            if ch == '\\' {
-                match chars.next() {
-                    Some('\\') => result.push('\\'),
-                    Some('"') => result.push('"'),
-                    Some('n') => result.push('\n'),
-                    Some('r') => result.push('\r'),
-                    Some('t') => result.push('\t'),
-                    Some(other) => {
-                        return Err(
-                            self.err_here(py, format!("Invalid escape sequence: \\{}", other))
-                        );
-                    }
-                    None => {
-                        return Err(self.err_here(py, "Unterminated escape sequence"));
-                    }
-                }
-            } else {
-                result.push(ch);
-            }
-        }
-
-        Ok(result)
+        unescape(s).map_err(|message| self.err_here(py, message))
     }
 
     fn get_depth(&self, line: &str) -> usize {
-        let leading_spaces = line.len() - line.trim_start().len();
+        let leading_spaces = self.get_indent_spaces(line);
         let indent_to_use = if let Some(explicit) = self.explicit_indent {
             explicit
         } else {
             self.indent_size
         };
-        if indent_to_use > 0 {
-            leading_spaces / indent_to_use
-        } else {
-            0
-        }
+        leading_spaces.checked_div(indent_to_use).unwrap_or(0)
     }
 
+    /// Width of `line`'s indentation beyond the base indentation, with a tab
+    /// counting as a whole level when `allow_tab_indent` is set
     fn get_indent_spaces(&self, line: &str) -> usize {
-        line.len() - line.trim_start().len()
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let width = if self.allow_tab_indent && indent.contains('\t') {
+            let level = self.explicit_indent.unwrap_or(self.indent_size);
+            indent
+                .chars()
+                .map(|c| if c == '\t' { level } else { 1 })
+                .sum()
+        } else {
+            indent.len()
+        };
+        width.saturating_sub(self.base_indent)
     }
 
-    fn is_tabular_row(&self, line: &str, delimiter: char) -> bool {
-        let mut in_quotes = false;
-        let mut escape_next = false;
-        let mut first_delim_pos = None;
-        let mut first_colon_pos = None;
-
-        for (i, ch) in line.char_indices() {
-            if escape_next {
-                escape_next = false;
-                continue;
-            }
-
-            if ch == '\\' {
-                escape_next = true;
-                continue;
-            }
-
-            if ch == '"' {
-                in_quotes = !in_quotes;
-            } else if !in_quotes {
-                if ch == delimiter && first_delim_pos.is_none() {
-                    first_delim_pos = Some(i);
-                }
-                if ch == ':' && first_colon_pos.is_none() {
-                    first_colon_pos = Some(i);
-                }
-            }
-        }
-
-        match (first_delim_pos, first_colon_pos) {
-            (None, None) => true,
-            (Some(_), None) => true,
-            (None, Some(_)) => false,
-            (Some(d), Some(c)) => d < c,
+    /// Split the values of an inline array or tabular row, dropping the empty
+    /// one after a trailing delimiter if `allow_trailing_delimiter` is set
+    fn split_values<'b>(&self, s: &'b str, delimiter: char) -> Vec<&'b str> {
+        let mut values = split_by_delimiter(s, delimiter);
+        if self.allow_trailing_delimiter && values.len() > 1 && values.last() == Some(&"") {
+            values.pop();
         }
+        values
     }
 
-    fn split_by_delimiter<'b>(&self, s: &'b str, delimiter: char) -> Vec<&'b str> {
-        let mut result = Vec::new();
-        let mut start = 0;
-        let mut in_quotes = false;
-        let mut prev_ch = '\0';
-
-        // Track byte positions while iterating through characters
-        for (byte_pos, ch) in s.char_indices() {
-            if ch == '"' && prev_ch != '\\' {
-                in_quotes = !in_quotes;
-            } else if ch == delimiter && !in_quotes {
-                let segment = &s[start..byte_pos];
-                result.push(segment.trim());
-                start = byte_pos + ch.len_utf8();
-            }
-            prev_ch = ch;
-        }
-
-        if start < s.len() {
-            result.push(s[start..].trim());
-        } else if start == s.len() && s.ends_with(delimiter) {
-            result.push("");
+    /// Split the `expected` values of an inline array or tabular row. Values
+    /// that only split right on another delimiter than the header's were
+    /// likely written with it: an error in strict mode, split on it with a
+    /// warning otherwise.
+    fn split_row<'b>(
+        &mut self,
+        py: Python,
+        s: &'b str,
+        delimiter: char,
+        expected: usize,
+        line_idx: usize,
+    ) -> PyResult<Vec<&'b str>> {
+        let values = self.split_values(s, delimiter);
+        if values.len() == expected || expected < 2 {
+            return Ok(values);
         }
-
-        result
-    }
-
-    fn find_unquoted_char(&self, s: &str, target: char) -> Option<usize> {
-        let mut in_quotes = false;
-        let mut escape_next = false;
-
-        for (i, ch) in s.char_indices() {
-            if escape_next {
-                escape_next = false;
-                continue;
-            }
-
-            if ch == '\\' {
-                escape_next = true;
-                continue;
-            }
-
-            if ch == '"' {
-                in_quotes = !in_quotes;
-            } else if ch == target && !in_quotes {
-                return Some(i);
-            }
+        let Some(actual) = [',', '|', '\t']
+            .into_iter()
+            .filter(|&d| d != delimiter)
+            .find(|&d| self.split_values(s, d).len() == expected)
+        else {
+            return Ok(values);
+        };
+        let msg = format!(
+            "Values are separated by {:?} but the header declares {:?}: {}",
+            actual, delimiter, s
+        );
+        if self.strict {
+            return Err(self.err_at(py, line_idx, msg));
         }
-
-        None
+        self.warn_at(line_idx, msg);
+        Ok(self.split_values(s, actual))
     }
 }
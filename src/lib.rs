@@ -1,6 +1,13 @@
+#[cfg(feature = "python")]
 mod deserialization;
+#[cfg(feature = "python")]
+mod schema;
+#[cfg(feature = "python")]
 mod serialization;
+mod text;
+pub mod value;
 
+#[cfg(feature = "python")]
 pyo3::create_exception!(
     toons,
     ToonDecodeError,
@@ -42,6 +49,7 @@ pyo3::create_exception!(
 /// # Custom indentation
 /// toon_str = toons.dumps(data, indent=4)
 /// ```
+#[cfg(feature = "python")]
 #[pyo3::pymodule]
 mod toons {
     use pyo3::prelude::*;
@@ -53,179 +61,1347 @@ mod toons {
     #[pymodule_export]
     use super::ToonDecodeError;
 
-    /// Deserialize a TOON formatted string to a Python object.
+    /// Shared by `with_decode_args` and `with_encode_args` below: append
+    /// the keywords to the signature and parameters of the function, and
+    /// bind them as one `$Args` struct at the start of its body
+    macro_rules! with_keyword_args {
+        (
+            $Args:ident [$($kw:ident: $kw_ty:ty = $default:tt),*]
+            $(#[$attr:meta])*
+            fn $name:ident $(<$lt:lifetime>)? (
+                $py:ident: $py_ty:ty $(, $param:ident: $param_ty:ty)*; $args:ident
+            ) -> $ret:ty $body:block
+        ) => {
+            $(#[$attr])*
+            #[pyo3(signature = ($($param,)* *, $($kw = $default),*))]
+            #[allow(clippy::too_many_arguments)]
+            fn $name $(<$lt>)? (
+                $py: $py_ty, $($param: $param_ty,)* $($kw: $kw_ty),*
+            ) -> $ret {
+                let $args = $Args { $($kw),* };
+                $body
+            }
+        };
+        (
+            $Args:ident [$($kw:ident: $kw_ty:ty = $default:tt),*]
+            $(#[$impl_attr:meta])*
+            impl $class:ident {
+                $(#[$attr:meta])*
+                fn new($($py:ident: $py_ty:ty)?; $args:ident) -> $ret:ty $body:block
+                $($methods:tt)*
+            }
+        ) => {
+            $(#[$impl_attr])*
+            impl $class {
+                $(#[$attr])*
+                #[pyo3(signature = (*, $($kw = $default),*))]
+                #[allow(clippy::too_many_arguments)]
+                fn new($($py: $py_ty,)? $($kw: $kw_ty),*) -> $ret {
+                    let $args = $Args { $($kw),* };
+                    $body
+                }
+                $($methods)*
+            }
+        };
+    }
+
+    /// Define a `#[pyfunction]`, or a `#[pymethods]` block whose first
+    /// method is the `#[new]`, taking the keyword-only arguments of `loads`
+    /// after its own parameters, gathered into the `DecodeArgs` named after
+    /// the `;`. The keywords, with their types and defaults, are listed only
+    /// here.
+    macro_rules! with_decode_args {
+        ($($item:tt)*) => {
+            with_keyword_args!(DecodeArgs [
+                strict: bool = true,
+                expand_paths: Option<&str> = None,
+                indent: Option<usize> = None,
+                collect_warnings: bool = false,
+                bare_hyphen: &str = "empty_object",
+                resolve_aliases: bool = false,
+                parse_datetime: bool = false,
+                max_depth: usize = 1000,
+                object_hook: Option<Py<PyAny>> = None,
+                object_pairs_hook: Option<Py<PyAny>> = None,
+                null_literal: &str = "null",
+                true_literal: &str = "true",
+                false_literal: &str = "false",
+                nan_token: Option<&str> = None,
+                inf_token: Option<&str> = None,
+                neg_inf_token: Option<&str> = None,
+                allow_trailing_delimiter: bool = false,
+                allow_tab_indent: bool = false,
+                leading_zero: &str = "string",
+                empty_cell: &str = "string",
+                schema: Option<&Bound<'_, PyAny>> = None
+            ] $($item)*);
+        };
+    }
+
+    /// Like `with_decode_args`, for the keyword-only arguments of `dumps`,
+    /// gathered into an `EncodeArgs`
+    macro_rules! with_encode_args {
+        ($($item:tt)*) => {
+            with_keyword_args!(EncodeArgs [
+                indent: usize = 2,
+                delimiter: &str = ",",
+                key_folding: Option<&str> = None,
+                flatten_depth: Option<usize> = None,
+                sort_keys: Option<&Bound<'_, PyAny>> = None,
+                bytes_encoding: &str = "base64",
+                enum_as: &str = "value",
+                float_precision: Option<usize> = None,
+                strip_trailing_zeros: bool = true,
+                skipkeys: bool = false,
+                share_by_identity: bool = false,
+                tabular: &str = "auto",
+                allow_exponent: bool = false,
+                preserve_float: bool = false,
+                allow_nan: bool = true,
+                typed_tabular: bool = false,
+                rich_numbers: bool = false,
+                quote_strings: bool = false,
+                null_literal: &str = "null",
+                true_literal: &str = "true",
+                false_literal: &str = "false",
+                inline_threshold: Option<usize> = None,
+                align: bool = false,
+                nan_token: Option<&str> = None,
+                inf_token: Option<&str> = None,
+                neg_inf_token: Option<&str> = None,
+                tabular_field_order: &str = "first",
+                compact: bool = false,
+                tabular_nested_arrays: bool = false,
+                timedelta_as: &str = "seconds",
+                indent_char: &str = " ",
+                line_separator: &str = "\n",
+                header_comment: Option<&str> = None,
+                tuple_key_sep: Option<&str> = None,
+                ensure_ascii: bool = false,
+                strict: bool = false,
+                max_line_length: Option<usize> = None,
+                sort_rows_by: Option<&str> = None
+            ] $($item)*);
+        };
+    }
+
+    /// `#[pymodule]` only sees functions written out in this module, not
+    /// those defined through `with_decode_args` and `with_encode_args`
+    #[pymodule_init]
+    fn register(module: &Bound<'_, PyModule>) -> PyResult<()> {
+        module.add_function(wrap_pyfunction!(loads, module)?)?;
+        module.add_function(wrap_pyfunction!(load, module)?)?;
+        module.add_function(wrap_pyfunction!(dumps, module)?)?;
+        module.add_function(wrap_pyfunction!(dumps_bytes, module)?)?;
+        module.add_function(wrap_pyfunction!(dump, module)?)?;
+        module.add_function(wrap_pyfunction!(estimate_size, module)?)?;
+        module.add_function(wrap_pyfunction!(check_roundtrip, module)?)?;
+        Ok(())
+    }
+
+    with_decode_args! {
+        /// Deserialize a TOON formatted string to a Python object.
+        ///
+        /// Parse a string containing TOON (Token-Oriented Object Notation) data
+        /// and return the corresponding Python object.
+        ///
+        /// Args:
+        ///     s: A string containing TOON formatted data, or bytes/bytearray
+        ///        holding it as UTF-8 (a leading byte order mark is ignored)
+        ///     strict: If True (default), enforce strict TOON v3.0 compliance.
+        ///             If False, allow some leniency (e.g. blank lines in arrays).
+        ///     collect_warnings: If True, return a `(value, warnings)` tuple where
+        ///             warnings is a list of `(line, message)` tuples describing
+        ///             deviations the parser tolerated (e.g. in lenient mode).
+        ///     bare_hyphen: How a bare `-` list item decodes: "empty_object"
+        ///             (default, what `dumps` writes for `{}`) or "null".
+        ///     resolve_aliases: Resolve unquoted `*/path` aliases written with
+        ///                  `share_by_identity=True` into shared references,
+        ///                  before any object hook runs.
+        ///     parse_datetime: Decode ISO-8601 date, time and datetime values into
+        ///                     `datetime` objects instead of strings (default: False).
+        ///     max_depth: Deepest nesting of objects and lists to accept; deeper
+        ///                input raises ToonDecodeError rather than exhausting the
+        ///                stack (default: 1000).
+        ///     object_hook: Called with each decoded dict, innermost first; its
+        ///                  return value is used in place of the dict.
+        ///     object_pairs_hook: Like object_hook, but called with the object's
+        ///                        `(key, value)` pairs as a list, in document
+        ///                        order and with repeated keys kept; takes
+        ///                        precedence over object_hook.
+        ///     null_literal, true_literal, false_literal: Extra tokens to read as
+        ///                        None, True and False, such as "None", as
+        ///                        written by `dumps` with the same options.
+        ///     nan_token, inf_token, neg_inf_token: Read quoted strings equal to
+        ///                        one of these as NaN, Infinity and -Infinity,
+        ///                        as written by `dumps` with the same options.
+        ///     allow_trailing_delimiter: Drop one empty value after a trailing
+        ///                     delimiter in inline arrays and tabular rows, as in
+        ///                     `1,2,`, instead of raising a length mismatch
+        ///                     (default: False).
+        ///     allow_tab_indent: Accept tabs in indentation, each counting as one
+        ///                     level, as written by `dumps(indent_char="\t")`
+        ///                     (default: False).
+        ///     leading_zero: How unquoted numbers with leading zeros such as
+        ///                     `007` decode: "string" (default) keeps them as the
+        ///                     string "007", "number" reads them as the number 7.
+        ///     empty_cell: How an empty value between delimiters, as in `a,,c`,
+        ///                     decodes in inline arrays and tabular rows:
+        ///                     "string" (default) reads "", "null" reads None.
+        ///     schema: Shape the decoded value must have: a type, a dict of key to
+        ///                     schema, `[item_schema]` for a list, a tuple of
+        ///                     alternatives or a TypedDict. Unambiguous numbers
+        ///                     and booleans are coerced to it (default: None).
+        ///
+        /// Returns:
+        ///     A Python object (dict, list, or primitive) decoded from the TOON string
+        ///
+        /// Raises:
+        ///     ToonDecodeError: If the input is malformed. Subclass of
+        ///         `ValueError`; carries `.line` (1-based) and `.source`
+        ///         (raw line) attributes for programmatic access.
+        ///         Also raised, naming the path, when the value does not match
+        ///         `schema`.
+        ///     ValueError: If indent is less than 2
+        ///
+        /// Example:
+        ///     >>> import toons
+        ///     >>> data = toons.loads("name: Alice\nage: 30")
+        ///     >>> print(data)
+        ///     {'name': 'Alice', 'age': 30}
+        #[pyfunction]
+        fn loads(py: Python, s: &Bound<'_, PyAny>; args) -> PyResult<Py<PyAny>> {
+            let options = args.build(py)?;
+            let text = crate::deserialization::input_text(s)?;
+            crate::deserialization::deserialize(py, &text, &options)
+        }
+    }
+
+    with_decode_args! {
+        /// Deserialize a TOON formatted file to a Python object.
+        ///
+        /// Read TOON data from a file-like object and return the corresponding
+        /// Python object.
+        ///
+        /// Args:
+        ///     fp: A file-like object with a read() method returning a string,
+        ///         or UTF-8 bytes when opened in binary mode; a stream or iterator
+        ///         without read() that yields the lines; or a path (str or
+        ///         os.PathLike) to a UTF-8 file
+        ///     strict: If True (default), enforce strict TOON v3.0 compliance.
+        ///             If False, allow some leniency (e.g. blank lines in arrays).
+        ///     collect_warnings: If True, return `(value, warnings)`. See `loads`.
+        ///     bare_hyphen: "empty_object" (default) or "null" for bare `-` items.
+        ///     resolve_aliases: Resolve `*/path` aliases. See `loads`.
+        ///     parse_datetime: Decode ISO-8601 values as datetimes. See `loads`.
+        ///     max_depth: Deepest nesting to accept (default: 1000). See `loads`.
+        ///     object_hook, object_pairs_hook: Replace decoded dicts. See `loads`.
+        ///     null_literal, true_literal, false_literal: Extra tokens for None,
+        ///                        True and False. See `loads`.
+        ///     nan_token, inf_token, neg_inf_token: Strings for special floats. See `loads`.
+        ///     allow_trailing_delimiter: Accept `1,2,` rows. See `loads`.
+        ///     allow_tab_indent: Accept tab indentation. See `loads`.
+        ///     leading_zero: "string" (default) or "number". See `loads`.
+        ///     empty_cell: "string" (default) or "null". See `loads`.
+        ///     schema: Shape to validate and coerce the value to. See `loads`.
+        ///
+        /// Returns:
+        ///     A Python object (dict, list, or primitive) decoded from the file
+        ///
+        /// Raises:
+        ///     ToonDecodeError: If the input is malformed. See `loads` for details.
+        ///
+        /// Example:
+        ///     >>> import toons
+        ///     >>> with open('data.toon', 'r') as f:
+        ///     ...     data = toons.load(f)
+        #[pyfunction]
+        fn load(py: Python, fp: &Bound<'_, PyAny>; args) -> PyResult<Py<PyAny>> {
+            let options = args.build(py)?;
+            let content = if fp.hasattr("read")? {
+                fp.call_method0("read")?
+            } else if is_line_reader(fp)? {
+                join_lines(fp.try_iter()?)?
+            } else {
+                let file = open_path(fp, "read", "rb", None)?;
+                let content = file.call_method0("read");
+                file.call_method0("close")?;
+                content?
+            };
+            let text = crate::deserialization::input_text(&content)?;
+            crate::deserialization::deserialize(py, &text, &options)
+        }
+    }
+
+    /// Iterate over the rows of a TOON file holding a root tabular array.
     ///
-    /// Parse a string containing TOON (Token-Oriented Object Notation) data
-    /// and return the corresponding Python object.
+    /// Rows are decoded a chunk of the file at a time and yielded as dicts,
+    /// so the whole list is never held in memory. The header is checked
+    /// when `iter_rows` is called; the declared `[N]` is checked against
+    /// the number of rows once they run out.
     ///
     /// Args:
-    ///     s: A string containing TOON formatted data
+    ///     fp: A file-like object with a read() method, as for `load`, or a
+    ///         path (str or os.PathLike) to a UTF-8 file
     ///     strict: If True (default), enforce strict TOON v3.0 compliance.
-    ///             If False, allow some leniency (e.g. blank lines in arrays).
     ///
     /// Returns:
-    ///     A Python object (dict, list, or primitive) decoded from the TOON string
+    ///     An iterator of dicts, one per row
     ///
     /// Raises:
-    ///     ToonDecodeError: If the input is malformed. Subclass of
-    ///         `ValueError`; carries `.line` (1-based) and `.source`
-    ///         (raw line) attributes for programmatic access.
+    ///     ToonDecodeError: If the document is not a root tabular array, or
+    ///         when the iteration reaches a malformed row or the row count
+    ///         does not match the header.
     ///
     /// Example:
     ///     >>> import toons
-    ///     >>> data = toons.loads("name: Alice\nage: 30")
-    ///     >>> print(data)
-    ///     {'name': 'Alice', 'age': 30}
+    ///     >>> for row in toons.iter_rows('users.toon'):
+    ///     ...     print(row['name'])
     #[pyfunction]
-    #[pyo3(signature = (s, *, strict=true, expand_paths=None, indent=None))]
-    fn loads(
+    #[pyo3(signature = (fp, *, strict=true))]
+    fn iter_rows(
         py: Python,
-        s: String,
+        fp: &Bound<'_, PyAny>,
         strict: bool,
-        expand_paths: Option<&str>,
-        indent: Option<usize>,
-    ) -> PyResult<Py<PyAny>> {
-        let expand_mode = expand_paths.unwrap_or("off");
-        crate::deserialization::deserialize(py, &s, strict, expand_mode, indent)
+    ) -> PyResult<crate::deserialization::RowIterator> {
+        let (file, owns_file) = if fp.hasattr("read")? {
+            (fp.clone(), false)
+        } else {
+            (open_path(fp, "read", "rb", None)?, true)
+        };
+        crate::deserialization::RowIterator::new(py, file.unbind(), owns_file, strict)
     }
 
-    /// Deserialize a TOON formatted file to a Python object.
+    /// Check that a TOON document is well-formed without decoding it.
     ///
-    /// Read TOON data from a file-like object and return the corresponding
-    /// Python object.
+    /// Runs the same parser as `loads`, including length and indentation
+    /// checks, but skips building Python values, so it is cheaper than
+    /// `loads` for large documents.
     ///
     /// Args:
-    ///     fp: A file-like object with a read() method returning a string
+    ///     s: A string (or UTF-8 bytes) containing TOON formatted data
     ///     strict: If True (default), enforce strict TOON v3.0 compliance.
-    ///             If False, allow some leniency (e.g. blank lines in arrays).
+    ///     indent: Optional indentation hint, as for `loads`.
+    ///     max_depth: Deepest nesting to accept, as for `loads`.
     ///
     /// Returns:
-    ///     A Python object (dict, list, or primitive) decoded from the file
+    ///     True if the document is well-formed
     ///
     /// Raises:
-    ///     ToonDecodeError: If the input is malformed. See `loads` for details.
+    ///     ToonDecodeError: If the input is malformed, with the same message,
+    ///         `.line` and `.source` that `loads` would report.
     ///
     /// Example:
     ///     >>> import toons
-    ///     >>> with open('data.toon', 'r') as f:
-    ///     ...     data = toons.load(f)
+    ///     >>> toons.validate("items[2]: a,b")
+    ///     True
     #[pyfunction]
-    #[pyo3(signature = (fp, *, strict=true, expand_paths=None, indent=None))]
-    fn load(
+    #[pyo3(signature = (s, *, strict=true, indent=None, max_depth=1000))]
+    fn validate(
         py: Python,
-        fp: &Bound<'_, PyAny>,
+        s: &Bound<'_, PyAny>,
         strict: bool,
-        expand_paths: Option<&str>,
         indent: Option<usize>,
-    ) -> PyResult<Py<PyAny>> {
-        let expand_mode = expand_paths.unwrap_or("off");
-        let read_method = fp.getattr("read")?;
-        let content = read_method.call0()?;
-        let content_str: String = content.extract()?;
-        crate::deserialization::deserialize(py, &content_str, strict, expand_mode, indent)
+        max_depth: usize,
+    ) -> PyResult<bool> {
+        let options = DecodeArgs {
+            strict,
+            indent,
+            max_depth,
+            ..Default::default()
+        }
+        .build(py)?;
+        let text = crate::deserialization::input_text(s)?;
+        crate::deserialization::validate(py, &text, &options)?;
+        Ok(true)
     }
 
-    /// Serialize a Python object to a TOON formatted string.
+    /// Convert a TOON document to a compact JSON string.
     ///
-    /// Convert a Python object (dict, list, or primitive) to its TOON
-    /// representation.
+    /// The document is decoded straight to JSON, without Python objects.
     ///
     /// Args:
-    ///     obj: A Python object to serialize (dict, list, str, int, float, bool, None)
-    ///     indent: Number of spaces per indentation level (default: 2, minimum: 2)
+    ///     s: A string (or UTF-8 bytes) containing TOON formatted data
+    ///     strict: If True (default), enforce strict TOON v3.0 compliance.
+    ///             Either way, over-indented lines are rejected rather than
+    ///             skipped as `loads` does.
     ///
     /// Returns:
-    ///     A string containing the TOON representation of the object
+    ///     The document as JSON, with object keys in document order
     ///
     /// Raises:
-    ///     ValueError: If indent is less than 2
+    ///     ToonDecodeError: If the input is malformed, as for `loads`.
     ///
     /// Example:
     ///     >>> import toons
-    ///     >>> data = {"name": "Alice", "tags": ["admin", "user"]}
-    ///     >>> toon_str = toons.dumps(data)
-    ///     >>> print(toon_str)
-    ///     name: Alice
-    ///     tags[2]: admin,user
-    ///
-    ///     >>> # Custom indentation
-    ///     >>> toon_str = toons.dumps(data, indent=4)
+    ///     >>> toons.toon_to_json("items[2]: a,b")
+    ///     '{"items":["a","b"]}'
     #[pyfunction]
-    #[pyo3(signature = (obj, *, indent=2, delimiter=",", key_folding=None, flatten_depth=None))]
-    fn dumps(
-        py: Python,
-        obj: &Bound<'_, PyAny>,
-        indent: usize,
-        delimiter: &str,
-        key_folding: Option<&str>,
-        flatten_depth: Option<usize>,
-    ) -> PyResult<String> {
-        if indent < 2 {
-            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
-                "indent must be >= 2",
-            ));
+    #[pyo3(signature = (s, *, strict=true))]
+    fn toon_to_json(py: Python, s: &Bound<'_, PyAny>, strict: bool) -> PyResult<String> {
+        let text = crate::deserialization::input_text(s)?;
+        // Decoded straight to JSON, without building Python objects
+        let options = crate::value::DecodeOptions {
+            strict,
+            ..Default::default()
+        };
+        match crate::value::decode_value(&text, &options) {
+            Ok(value) => Ok(value.to_string()),
+            Err(err) => {
+                let source = err.line.and_then(|line| text.lines().nth(line - 1));
+                let source = source.map(|line| line.trim_start_matches('\u{feff}'));
+                Err(crate::deserialization::make_decode_error(
+                    py,
+                    err.to_string(),
+                    err.line,
+                    source,
+                ))
+            }
         }
-        // key_folding: only enable when explicitly set to "safe", "on", or "always"
-        let enable_key_folding = matches!(key_folding, Some("safe") | Some("on") | Some("always"));
-        crate::serialization::serialize(
-            py,
-            obj,
-            delimiter.chars().next().unwrap(),
-            indent,
-            enable_key_folding,
-            flatten_depth,
-        )
     }
 
-    /// Serialize a Python object to a TOON formatted file.
-    ///
-    /// Convert a Python object to TOON format and write it to a file-like object.
+    /// Convert a JSON document to a TOON formatted string.
     ///
     /// Args:
-    ///     obj: A Python object to serialize (dict, list, str, int, float, bool, None)
-    ///     fp: A file-like object with a write() method
+    ///     s: A string containing JSON
     ///     indent: Number of spaces per indentation level (default: 2, minimum: 2)
     ///
+    /// Returns:
+    ///     The document as TOON, written as `dumps` would write it
+    ///
     /// Raises:
-    ///     ValueError: If indent is less than 2
+    ///     ValueError: If `s` is not valid JSON or indent is less than 2
     ///
     /// Example:
     ///     >>> import toons
-    ///     >>> data = {"name": "Alice", "age": 30}
-    ///     >>> with open('data.toon', 'w') as f:
-    ///     ...     toons.dump(data, f)
-    ///
-    ///     >>> # Custom indentation
-    ///     >>> with open('data.toon', 'w') as f:
-    ///     ...     toons.dump(data, f, indent=4)
+    ///     >>> print(toons.json_to_toon('{"items": ["a", "b"]}'))
+    ///     items[2]: a,b
     #[pyfunction]
-    #[pyo3(signature = (obj, fp, *, indent=2, delimiter=",", key_folding=None, flatten_depth=None))]
-    fn dump(
-        py: Python,
-        obj: &Bound<'_, PyAny>,
-        fp: &Bound<'_, PyAny>,
-        indent: usize,
-        delimiter: &str,
-        key_folding: Option<&str>,
-        flatten_depth: Option<usize>,
-    ) -> PyResult<()> {
+    #[pyo3(signature = (s, *, indent=2))]
+    fn json_to_toon(s: &str, indent: usize) -> PyResult<String> {
+        check_indent(indent)?;
+        let value: serde_json::Value = serde_json::from_str(s).map_err(|err| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid JSON: {}", err))
+        })?;
+        // What `dumps` writes with its defaults, apart from the indentation
+        let options = crate::value::EncodeOptions {
+            indent,
+            ..Default::default()
+        };
+        Ok(crate::value::encode_value(&value, &options))
+    }
+
+    /// Whether `fp` names a file (`str`, `bytes` or `os.PathLike`)
+    fn is_path(fp: &Bound<'_, PyAny>) -> PyResult<bool> {
+        Ok(fp.is_instance_of::<pyo3::types::PyString>()
+            || fp.is_instance_of::<pyo3::types::PyBytes>()
+            || fp.hasattr("__fspath__")?)
+    }
+
+    /// Whether `fp` is a reader without `read()` that yields its lines when
+    /// iterated: a stream with `readable()`, or an iterator such as a
+    /// generator. Plain containers like lists are not readers.
+    fn is_line_reader(fp: &Bound<'_, PyAny>) -> PyResult<bool> {
+        Ok(!is_path(fp)? && (fp.hasattr("readable")? || fp.hasattr("__next__")?))
+    }
+
+    /// Whether `fp` is a file object that takes `bytes`: an `io` binary
+    /// stream, or any object whose `mode` contains "b"
+    fn is_binary_writer(fp: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let io = fp.py().import("io")?;
+        if fp.is_instance(&io.getattr("RawIOBase")?)?
+            || fp.is_instance(&io.getattr("BufferedIOBase")?)?
+        {
+            return Ok(true);
+        }
+        Ok(match fp.getattr("mode") {
+            Ok(mode) => mode
+                .extract::<String>()
+                .is_ok_and(|mode| mode.contains('b')),
+            Err(_) => false,
+        })
+    }
+
+    /// Concatenate the lines of a reader without `read()`, all `str` or all
+    /// `bytes`
+    fn join_lines<'py>(lines: Bound<'py, pyo3::types::PyIterator>) -> PyResult<Bound<'py, PyAny>> {
+        let py = lines.py();
+        let lines = lines.collect::<PyResult<Vec<_>>>()?;
+        let empty = match lines.first() {
+            Some(line) if !line.is_instance_of::<pyo3::types::PyString>() => {
+                pyo3::types::PyBytes::new(py, b"").into_any()
+            }
+            _ => pyo3::types::PyString::new(py, "").into_any(),
+        };
+        empty.call_method1("join", (lines,))
+    }
+
+    /// Open `fp` with the builtin `open()` when it is a path (`str`, `bytes`
+    /// or `os.PathLike`) rather than a file object with a `method()` method
+    fn open_path<'py>(
+        fp: &Bound<'py, PyAny>,
+        method: &str,
+        mode: &str,
+        encoding: Option<&str>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if !is_path(fp)? {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                "fp must be a file object with a {}() method or a path, not {}",
+                method,
+                fp.get_type().name()?
+            )));
+        }
+        let open = fp.py().import("io")?.getattr("open")?;
+        let kwargs = pyo3::types::PyDict::new(fp.py());
+        if let Some(encoding) = encoding {
+            kwargs.set_item("encoding", encoding)?;
+            // Write "\n" as is, like dumps() returns it, on every platform
+            kwargs.set_item("newline", "")?;
+        }
+        open.call((fp, mode), Some(&kwargs))
+    }
+
+    /// Reject indentation widths below two spaces, which neither `dumps` nor
+    /// `loads` supports
+    fn check_indent(indent: usize) -> PyResult<()> {
         if indent < 2 {
             return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
                 "indent must be >= 2",
             ));
         }
-        // key_folding: only enable when explicitly set to "safe", "on", or "always"
-        let enable_key_folding = matches!(key_folding, Some("safe") | Some("on") | Some("always"));
-        let toon_str = crate::serialization::serialize(
-            py,
-            obj,
-            delimiter.chars().next().unwrap(),
-            indent,
-            enable_key_folding,
-            flatten_depth,
-        )?;
-        let write_method = fp.getattr("write")?;
-        write_method.call1((toon_str,))?;
         Ok(())
     }
+
+    /// The keyword arguments of `loads`, shared by `load` and `TOONDecoder`;
+    /// the defaults are those of `loads`
+    struct DecodeArgs<'a, 'py> {
+        strict: bool,
+        expand_paths: Option<&'a str>,
+        indent: Option<usize>,
+        collect_warnings: bool,
+        bare_hyphen: &'a str,
+        resolve_aliases: bool,
+        parse_datetime: bool,
+        max_depth: usize,
+        object_hook: Option<Py<PyAny>>,
+        object_pairs_hook: Option<Py<PyAny>>,
+        null_literal: &'a str,
+        true_literal: &'a str,
+        false_literal: &'a str,
+        nan_token: Option<&'a str>,
+        inf_token: Option<&'a str>,
+        neg_inf_token: Option<&'a str>,
+        allow_trailing_delimiter: bool,
+        allow_tab_indent: bool,
+        leading_zero: &'a str,
+        empty_cell: &'a str,
+        schema: Option<&'a Bound<'py, PyAny>>,
+    }
+
+    impl Default for DecodeArgs<'_, '_> {
+        fn default() -> Self {
+            DecodeArgs {
+                strict: true,
+                expand_paths: None,
+                indent: None,
+                collect_warnings: false,
+                bare_hyphen: "empty_object",
+                resolve_aliases: false,
+                parse_datetime: false,
+                max_depth: 1000,
+                object_hook: None,
+                object_pairs_hook: None,
+                null_literal: "null",
+                true_literal: "true",
+                false_literal: "false",
+                nan_token: None,
+                inf_token: None,
+                neg_inf_token: None,
+                allow_trailing_delimiter: false,
+                allow_tab_indent: false,
+                leading_zero: "string",
+                empty_cell: "string",
+                schema: None,
+            }
+        }
+    }
+
+    impl DecodeArgs<'_, '_> {
+        /// Validate the arguments and build the decoder options from them
+        fn build(&self, py: Python) -> PyResult<crate::deserialization::DecodeOptions> {
+            if let Some(indent) = self.indent {
+                check_indent(indent)?;
+            }
+            for (name, hook) in [
+                ("object_hook", &self.object_hook),
+                ("object_pairs_hook", &self.object_pairs_hook),
+            ] {
+                if let Some(hook) = hook
+                    && !hook.bind(py).is_callable()
+                {
+                    return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                        "{} must be callable",
+                        name
+                    )));
+                }
+            }
+            Ok(crate::deserialization::DecodeOptions {
+                strict: self.strict,
+                expand_paths: self.expand_paths.unwrap_or("off").to_string(),
+                indent: self.indent,
+                collect_warnings: self.collect_warnings,
+                bare_hyphen: crate::deserialization::BareHyphen::from_arg(self.bare_hyphen)?,
+                resolve_aliases: self.resolve_aliases,
+                parse_datetime: self.parse_datetime,
+                max_depth: self.max_depth,
+                object_hook: self.object_hook.as_ref().map(|hook| hook.clone_ref(py)),
+                object_pairs_hook: self
+                    .object_pairs_hook
+                    .as_ref()
+                    .map(|hook| hook.clone_ref(py)),
+                literals: crate::serialization::Literals::from_args(
+                    self.null_literal,
+                    self.true_literal,
+                    self.false_literal,
+                )?,
+                float_tokens: crate::serialization::FloatTokens::from_args(
+                    self.nan_token,
+                    self.inf_token,
+                    self.neg_inf_token,
+                )?,
+                allow_trailing_delimiter: self.allow_trailing_delimiter,
+                allow_tab_indent: self.allow_tab_indent,
+                leading_zero: crate::deserialization::LeadingZero::from_arg(self.leading_zero)?,
+                empty_cell: crate::deserialization::EmptyCell::from_arg(self.empty_cell)?,
+                schema: self
+                    .schema
+                    .map(crate::schema::Schema::from_spec)
+                    .transpose()?,
+            })
+        }
+    }
+
+    /// The keyword arguments of `dumps`, shared by the other encoding
+    /// functions and `TOONEncoder`; the defaults are those of `dumps`
+    struct EncodeArgs<'a, 'py> {
+        indent: usize,
+        delimiter: &'a str,
+        key_folding: Option<&'a str>,
+        flatten_depth: Option<usize>,
+        sort_keys: Option<&'a Bound<'py, PyAny>>,
+        bytes_encoding: &'a str,
+        enum_as: &'a str,
+        float_precision: Option<usize>,
+        strip_trailing_zeros: bool,
+        skipkeys: bool,
+        share_by_identity: bool,
+        tabular: &'a str,
+        allow_exponent: bool,
+        preserve_float: bool,
+        allow_nan: bool,
+        typed_tabular: bool,
+        rich_numbers: bool,
+        quote_strings: bool,
+        null_literal: &'a str,
+        true_literal: &'a str,
+        false_literal: &'a str,
+        inline_threshold: Option<usize>,
+        align: bool,
+        nan_token: Option<&'a str>,
+        inf_token: Option<&'a str>,
+        neg_inf_token: Option<&'a str>,
+        tabular_field_order: &'a str,
+        compact: bool,
+        tabular_nested_arrays: bool,
+        timedelta_as: &'a str,
+        indent_char: &'a str,
+        line_separator: &'a str,
+        header_comment: Option<&'a str>,
+        tuple_key_sep: Option<&'a str>,
+        ensure_ascii: bool,
+        strict: bool,
+        max_line_length: Option<usize>,
+        sort_rows_by: Option<&'a str>,
+    }
+
+    impl Default for EncodeArgs<'_, '_> {
+        fn default() -> Self {
+            EncodeArgs {
+                indent: 2,
+                delimiter: ",",
+                key_folding: None,
+                flatten_depth: None,
+                sort_keys: None,
+                bytes_encoding: "base64",
+                enum_as: "value",
+                float_precision: None,
+                strip_trailing_zeros: true,
+                skipkeys: false,
+                share_by_identity: false,
+                tabular: "auto",
+                allow_exponent: false,
+                preserve_float: false,
+                allow_nan: true,
+                typed_tabular: false,
+                rich_numbers: false,
+                quote_strings: false,
+                null_literal: "null",
+                true_literal: "true",
+                false_literal: "false",
+                inline_threshold: None,
+                align: false,
+                nan_token: None,
+                inf_token: None,
+                neg_inf_token: None,
+                tabular_field_order: "first",
+                compact: false,
+                tabular_nested_arrays: false,
+                timedelta_as: "seconds",
+                indent_char: " ",
+                line_separator: "\n",
+                header_comment: None,
+                tuple_key_sep: None,
+                ensure_ascii: false,
+                strict: false,
+                max_line_length: None,
+                sort_rows_by: None,
+            }
+        }
+    }
+
+    impl EncodeArgs<'_, '_> {
+        /// Validate the arguments and build the serialization context from them
+        fn build(&self) -> PyResult<crate::serialization::SerializationContext> {
+            check_indent(self.indent)?;
+            // key_folding: only enable when explicitly set to "safe", "on", or "always"
+            let key_folding = matches!(self.key_folding, Some("safe" | "on" | "always"));
+            if self.float_precision == Some(0) {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "float_precision must be >= 1",
+                ));
+            }
+            if key_folding && self.share_by_identity {
+                // Alias paths address the unfolded structure
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "share_by_identity cannot be combined with key_folding",
+                ));
+            }
+            if self.ensure_ascii
+                && !(self.null_literal.is_ascii()
+                    && self.true_literal.is_ascii()
+                    && self.false_literal.is_ascii())
+            {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "ensure_ascii requires ASCII null_literal, true_literal and false_literal",
+                ));
+            }
+            Ok(crate::serialization::SerializationContext {
+                indent: self.indent,
+                delimiter: crate::serialization::Delimiter::from_arg(self.delimiter)?,
+                key_folding,
+                flatten_depth: self.flatten_depth.unwrap_or(usize::MAX),
+                key_order: crate::serialization::KeyOrder::from_arg(self.sort_keys)?,
+                bytes_encoding: crate::serialization::BytesEncoding::from_arg(self.bytes_encoding)?,
+                enum_as: crate::serialization::EnumAs::from_arg(self.enum_as)?,
+                float_precision: self.float_precision,
+                strip_trailing_zeros: self.strip_trailing_zeros,
+                skipkeys: self.skipkeys,
+                share_by_identity: self.share_by_identity,
+                tabular: crate::serialization::TabularMode::from_arg(self.tabular)?,
+                allow_exponent: self.allow_exponent,
+                preserve_float: self.preserve_float,
+                allow_nan: self.allow_nan,
+                typed_tabular: self.typed_tabular,
+                rich_numbers: self.rich_numbers,
+                quote_strings: self.quote_strings,
+                literals: crate::serialization::Literals::from_args(
+                    self.null_literal,
+                    self.true_literal,
+                    self.false_literal,
+                )?,
+                inline_threshold: self.inline_threshold,
+                align: self.align,
+                float_tokens: crate::serialization::FloatTokens::from_args(
+                    self.nan_token,
+                    self.inf_token,
+                    self.neg_inf_token,
+                )?,
+                tabular_field_order: crate::serialization::FieldOrder::from_arg(
+                    self.tabular_field_order,
+                )?,
+                compact: self.compact,
+                tabular_nested_arrays: self.tabular_nested_arrays,
+                timedelta_as: crate::serialization::TimedeltaAs::from_arg(self.timedelta_as)?,
+                indent_char: crate::serialization::IndentChar::from_arg(self.indent_char)?,
+                line_separator: crate::serialization::LineSeparator::from_arg(self.line_separator)?,
+                header_comment: self.header_comment.map(str::to_string),
+                tuple_key_sep: self.tuple_key_sep.map(str::to_string),
+                ensure_ascii: self.ensure_ascii,
+                strict: self.strict,
+                max_line_length: self.max_line_length,
+                sort_rows_by: self.sort_rows_by.map(str::to_string),
+            })
+        }
+    }
+
+    with_encode_args! {
+        /// Serialize a Python object to a TOON formatted string.
+        ///
+        /// Convert a Python object (dict, list, or primitive) to its TOON
+        /// representation.
+        ///
+        /// Args:
+        ///     obj: A Python object to serialize (dict, list, str, int, float, bool, None)
+        ///     indent: Number of spaces per indentation level (default: 2, minimum: 2)
+        ///     sort_keys: Emit object keys and tabular fields sorted. True sorts
+        ///                lexicographically; "natural" orders digit runs numerically
+        ///                (item2 before item10). Default keeps insertion order.
+        ///     bytes_encoding: How bytes/bytearray values are written: "base64"
+        ///                     (default) or "hex". Decoding returns these as str.
+        ///     enum_as: How Enum members are written: "value" (default)
+        ///              emits `.value`, "name" emits the member name.
+        ///     float_precision: Round floats to this many significant digits
+        ///                      (default: shortest representation).
+        ///     strip_trailing_zeros: With float_precision, drop trailing zeros
+        ///                           (default) or keep them (False: 1.50 stays 1.50).
+        ///     skipkeys: Drop dict keys that are not str, int, float, bool or None
+        ///               instead of raising TypeError (default: False). Supported
+        ///               non-string keys are converted like json.dumps (True -> "true").
+        ///     share_by_identity: Emit repeats of the same container object (by id,
+        ///                        including cycles) as `*/path` aliases to its first
+        ///                        occurrence instead of writing it again. Decode with
+        ///                        `loads(..., resolve_aliases=True)`.
+        ///     tabular: "auto" (default) uses the tabular form when all rows share
+        ///              the same keys, "fill" when rows differ (union header, null
+        ///              for missing cells), "never" always writes expanded lists.
+        ///     allow_exponent: Write very small (< 1e-4) or large (>= 1e16) floats
+        ///                     in exponent form, e.g. 1e-10 or 1e+20 (default: False).
+        ///     preserve_float: Write integral floats as 1.0 instead of 1 so they
+        ///                     decode as float (default: False).
+        ///     allow_nan: Write NaN/Infinity as null; when False, raise ValueError
+        ///                for them instead (default: True).
+        ///     typed_tabular: Annotate tabular fields whose values are all int or
+        ///                    all float, e.g. `[2]{count:int,ratio:float}:`, so
+        ///                    `loads` restores each column's type (default: False).
+        ///     rich_numbers: Write fractions.Fraction values exactly, as strings
+        ///                   like 3/4, instead of as floats (default: False).
+        ///     quote_strings: Quote every string value, so a string such as "true"
+        ///                    never reads like a literal; keys are unaffected
+        ///                    (default: False).
+        ///     null_literal, true_literal, false_literal: Tokens written for None,
+        ///                    True and False (default: "null", "true", "false");
+        ///                    strings equal to one of them are quoted.
+        ///     inline_threshold: Write a list of primitives inline (`[3]: a,b,c`)
+        ///                       only while its items take fewer bytes than this,
+        ///                       and as a `- ` item list otherwise (default: always
+        ///                       inline).
+        ///     align: Pad tabular cells with spaces after each delimiter so the
+        ///            columns line up (default: False).
+        ///     nan_token, inf_token, neg_inf_token: Quoted strings written for
+        ///                    NaN, Infinity and -Infinity instead of null; a set
+        ///                    token takes precedence over `allow_nan`
+        ///                    (default: None).
+        ///     tabular_field_order: Order of tabular header fields: "first" follows
+        ///                          the first row, "sorted" sorts them, "union"
+        ///                          merges every row's key order under
+        ///                          `tabular="fill"`; `sort_keys` takes precedence
+        ///                          (default: "first").
+        ///     compact: Omit the space after the colon of fields and inline array
+        ///              headers, e.g. `name:Alice` and `tags[2]:a,b` (default: False).
+        ///     tabular_nested_arrays: Also write lists of objects tabular when some
+        ///                            values are lists of primitives, as inline
+        ///                            arrays inside the cell, e.g. `x,[2|]: a|b`
+        ///                            (default: False).
+        ///     timedelta_as: Write datetime.timedelta values as their total seconds
+        ///                   ("seconds") or as an ISO-8601 duration such as
+        ///                   `P1DT2H30M` ("iso") (default: "seconds").
+        ///     indent_char: Indent with `indent` spaces per level (" ") or with
+        ///                  one tab per level ("\t") (default: " ").
+        ///     line_separator: End lines with "\n" (default) or "\r\n".
+        ///     header_comment: Text written as `# ` comment lines before the
+        ///                     document, one per line of it; read it back with
+        ///                     `loads(..., strict=False)` (default: None).
+        ///     tuple_key_sep: Join the items of tuple dict keys with this string,
+        ///                    each converted like a key, so ("x", 1) becomes
+        ///                    `x.1` with "."; None (default) treats tuple keys
+        ///                    like other unsupported keys.
+        ///     ensure_ascii: Quote strings and keys with non-ASCII characters and
+        ///                   write those as `\uXXXX` escapes, like `json.dumps`,
+        ///                   which `loads` reads back (default: False).
+        ///     strict: Raise TypeError for objects of types TOON has no form for,
+        ///             once `__toon__()` and `default()` have been tried, instead
+        ///             of writing them as null (default: False).
+        ///     max_line_length: Write a list of primitives as a `- ` item list
+        ///                      when its inline line, indentation and key
+        ///                      included, would be longer than this many
+        ///                      characters (default: None, no limit).
+        ///     sort_rows_by: Write the rows of every tabular array with this
+        ///                   column sorted, stably, by it; other lists keep their
+        ///                   order. ValueError if a top-level table lacks the
+        ///                   column or its values cannot be compared
+        ///                   (default: None).
+        ///
+        /// Returns:
+        ///     A string containing the TOON representation of the object
+        ///
+        /// Raises:
+        ///     ValueError: If indent is less than 2
+        ///
+        /// Example:
+        ///     >>> import toons
+        ///     >>> data = {"name": "Alice", "tags": ["admin", "user"]}
+        ///     >>> toon_str = toons.dumps(data)
+        ///     >>> print(toon_str)
+        ///     name: Alice
+        ///     tags[2]: admin,user
+        ///     >>> # Custom indentation
+        ///     >>> toon_str = toons.dumps(data, indent=4)
+        #[pyfunction]
+        fn dumps(py: Python, obj: &Bound<'_, PyAny>; args) -> PyResult<String> {
+            let ctx = args.build()?;
+            crate::serialization::serialize(py, obj, &ctx, None)
+        }
+    }
+
+    with_encode_args! {
+        /// Serialize a Python object to TOON as UTF-8 bytes.
+        ///
+        /// Like `dumps`, but the result is returned as `bytes`, built straight
+        /// from the encoder's output without an intermediate `str`. Use it to
+        /// write to sockets or files opened in binary mode.
+        ///
+        /// Args:
+        ///     obj: A Python object to serialize
+        ///     **kwargs: The same keyword arguments as `dumps`
+        ///
+        /// Returns:
+        ///     The UTF-8 encoded TOON representation of the object
+        ///
+        /// Raises:
+        ///     ValueError: For the same invalid arguments or values as `dumps`
+        ///
+        /// Example:
+        ///     >>> import toons
+        ///     >>> toons.dumps_bytes({"name": "Zoë"})
+        ///     b'name: Zo\xc3\xab'
+        #[pyfunction]
+        fn dumps_bytes<'py>(
+            py: Python<'py>,
+            obj: &Bound<'_, PyAny>;
+            args
+        ) -> PyResult<Bound<'py, pyo3::types::PyBytes>> {
+            let ctx = args.build()?;
+            let toon_str = crate::serialization::serialize(py, obj, &ctx, None)?;
+            Ok(pyo3::types::PyBytes::new(py, toon_str.as_bytes()))
+        }
+    }
+
+    with_encode_args! {
+        /// Serialize a Python object to a TOON formatted file.
+        ///
+        /// Convert a Python object to TOON format and write it to a file-like object.
+        ///
+        /// Args:
+        ///     obj: A Python object to serialize (dict, list, str, int, float, bool, None)
+        ///     fp: A file-like object with a write() method, in text or binary
+        ///         mode (binary streams are written UTF-8 bytes), or a path (str
+        ///         or os.PathLike) to create or overwrite as UTF-8
+        ///     indent: Number of spaces per indentation level (default: 2, minimum: 2)
+        ///     sort_keys: Emit object keys sorted (True or "natural"). See `dumps`.
+        ///     bytes_encoding: "base64" (default) or "hex" for bytes values.
+        ///     enum_as: "value" (default) or "name" for Enum members.
+        ///     float_precision: Significant digits for floats. See `dumps`.
+        ///     strip_trailing_zeros: Keep or drop float_precision padding. See `dumps`.
+        ///     skipkeys: Drop keys of unsupported types. See `dumps`.
+        ///     share_by_identity: Alias repeated containers. See `dumps`.
+        ///     tabular: "auto" (default), "fill" or "never". See `dumps`.
+        ///     allow_exponent: Exponent form for very small/large floats. See `dumps`.
+        ///     preserve_float: Keep a trailing .0 on integral floats. See `dumps`.
+        ///     allow_nan: Reject NaN/Infinity when False. See `dumps`.
+        ///     typed_tabular: Annotate int/float tabular columns. See `dumps`.
+        ///     rich_numbers: Write Fraction values as 3/4 strings. See `dumps`.
+        ///     quote_strings: Quote every string value. See `dumps`.
+        ///     null_literal, true_literal, false_literal: Tokens for None, True
+        ///                    and False. See `dumps`.
+        ///     inline_threshold: Longest inline primitive list. See `dumps`.
+        ///     align: Line up tabular columns. See `dumps`.
+        ///     nan_token, inf_token, neg_inf_token: Strings for special floats. See `dumps`.
+        ///     tabular_field_order: Order of tabular header fields. See `dumps`.
+        ///     compact: Omit the space after colons. See `dumps`.
+        ///     tabular_nested_arrays: Allow primitive-list cells. See `dumps`.
+        ///     timedelta_as: How timedelta values are written. See `dumps`.
+        ///     indent_char: Space or tab indentation. See `dumps`.
+        ///     line_separator: "\n" or "\r\n" line endings. See `dumps`.
+        ///     header_comment: Leading `# ` comment lines. See `dumps`.
+        ///     tuple_key_sep: Joiner for tuple dict keys. See `dumps`.
+        ///     ensure_ascii: Escape non-ASCII characters. See `dumps`.
+        ///     strict: Raise TypeError for unsupported types. See `dumps`.
+        ///     max_line_length: Longest inline primitive list line. See `dumps`.
+        ///     sort_rows_by: Column to sort tabular rows by. See `dumps`.
+        ///
+        /// Raises:
+        ///     ValueError: If indent is less than 2
+        ///
+        /// Example:
+        ///     >>> import toons
+        ///     >>> data = {"name": "Alice", "age": 30}
+        ///     >>> with open('data.toon', 'w') as f:
+        ///     ...     toons.dump(data, f)
+        ///     >>> # Custom indentation
+        ///     >>> with open('data.toon', 'w') as f:
+        ///     ...     toons.dump(data, f, indent=4)
+        #[pyfunction]
+        fn dump(py: Python, obj: &Bound<'_, PyAny>, fp: &Bound<'_, PyAny>; args) -> PyResult<()> {
+            let ctx = args.build()?;
+            let toon_str = crate::serialization::serialize(py, obj, &ctx, None)?;
+            if fp.hasattr("write")? {
+                if is_binary_writer(fp)? {
+                    let bytes = pyo3::types::PyBytes::new(py, toon_str.as_bytes());
+                    fp.call_method1("write", (bytes,))?;
+                } else {
+                    fp.call_method1("write", (toon_str,))?;
+                }
+            } else {
+                let file = open_path(fp, "write", "w", Some("utf-8"))?;
+                let written = file.call_method1("write", (toon_str,));
+                file.call_method0("close")?;
+                written?;
+            }
+            Ok(())
+        }
+    }
+
+    with_encode_args! {
+        /// Compute the size of `dumps(obj, ...)` without building the string.
+        ///
+        /// Runs the encoder with a byte counter in place of the output buffer,
+        /// so the result is exact: it equals `len(toons.dumps(obj, ...).encode())`
+        /// for the same arguments. Useful to decide whether to stream, compress,
+        /// or reject a payload before allocating it.
+        ///
+        /// Args:
+        ///     obj: A Python object to serialize
+        ///     **kwargs: The same keyword arguments as `dumps`
+        ///
+        /// Returns:
+        ///     The UTF-8 byte length of the TOON representation
+        ///
+        /// Raises:
+        ///     ValueError: For the same invalid arguments or values as `dumps`
+        ///
+        /// Example:
+        ///     >>> import toons
+        ///     >>> toons.estimate_size({"name": "Alice"})
+        ///     11
+        #[pyfunction]
+        fn estimate_size(py: Python, obj: &Bound<'_, PyAny>; args) -> PyResult<usize> {
+            let ctx = args.build()?;
+            crate::serialization::estimate_size(py, obj, &ctx)
+        }
+    }
+
+    with_encode_args! {
+        /// Check that the TOON form of `obj` decodes back to the same data.
+        ///
+        /// Serializes `obj` as `dumps` would, parses the output with matching
+        /// `loads` options (path expansion for `key_folding`, alias resolution
+        /// for `share_by_identity`, the same literals and float tokens) and
+        /// compares the result with `obj`. A debugging aid for finding values
+        /// that do not survive the trip, such as integers too large for the
+        /// decoder or rows padded by `tabular="fill"`.
+        ///
+        /// Objects are compared by key set and numbers by value. Values the
+        /// encoder writes in another form (dates, UUIDs, enums, bytes, ...) are
+        /// not compared.
+        ///
+        /// The output is decoded strictly, except with `header_comment`,
+        /// whose comments only lenient decoding reads; anything else that
+        /// decoding then tolerates is reported as a difference.
+        ///
+        /// Args:
+        ///     obj: A Python object to serialize
+        ///     **kwargs: The same keyword arguments as `dumps`
+        ///
+        /// Returns:
+        ///     True if the decoded value matches `obj`
+        ///
+        /// Raises:
+        ///     ValueError: If they differ, listing the first differences by
+        ///         JSON-pointer path, or for the same invalid arguments or
+        ///         values as `dumps`
+        ///     ToonDecodeError: If the output cannot be parsed back
+        ///
+        /// Example:
+        ///     >>> import toons
+        ///     >>> toons.check_roundtrip({"name": "Alice", "tags": ["a", "b"]})
+        ///     True
+        #[pyfunction]
+        fn check_roundtrip(py: Python, obj: &Bound<'_, PyAny>; args) -> PyResult<bool> {
+            let ctx = args.build()?;
+            let toon = crate::serialization::serialize(py, obj, &ctx, None)?;
+            // Header comments are only read leniently; any other leniency the
+            // output needs is reported as a difference
+            let strict = args.header_comment.is_none();
+            let options = DecodeArgs {
+                strict,
+                expand_paths: ctx.key_folding.then_some("safe"),
+                collect_warnings: !strict,
+                resolve_aliases: args.share_by_identity,
+                max_depth: usize::MAX,
+                null_literal: args.null_literal,
+                true_literal: args.true_literal,
+                false_literal: args.false_literal,
+                nan_token: args.nan_token,
+                inf_token: args.inf_token,
+                neg_inf_token: args.neg_inf_token,
+                allow_tab_indent: args.indent_char == "\t",
+                ..Default::default()
+            }
+            .build(py)?;
+            let mut decoded = crate::deserialization::deserialize(py, &toon, &options)?;
+            let mut warnings = Vec::new();
+            if !strict {
+                let (value, tolerated): (Py<PyAny>, Vec<(usize, String)>) = decoded.extract(py)?;
+                decoded = value;
+                warnings = tolerated
+                    .into_iter()
+                    .map(|(line, warning)| format!("line {}: {}", line, warning))
+                    .collect();
+            }
+            let expected = crate::serialization::normalize(obj, &ctx)?;
+            let mut differences = warnings;
+            differences.extend(crate::serialization::roundtrip_differences(
+                &expected,
+                decoded.bind(py),
+                &ctx,
+            )?);
+            if differences.is_empty() {
+                return Ok(true);
+            }
+            const SHOWN: usize = 10;
+            let mut message = String::from("TOON output does not decode back to the same value:");
+            for difference in differences.iter().take(SHOWN) {
+                message.push_str("\n  ");
+                message.push_str(difference);
+            }
+            if differences.len() > SHOWN {
+                message.push_str(&format!("\n  ... and {} more", differences.len() - SHOWN));
+            }
+            Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(message))
+        }
+    }
+
+    /// Reusable TOON encoder holding the `dumps` options.
+    ///
+    /// Like `json.JSONEncoder`, subclass it and override `default()` to
+    /// encode objects of types TOON has no form for.
+    ///
+    /// Args:
+    ///     indent, delimiter, ...: The keyword arguments of `dumps`, with the
+    ///         same defaults; invalid values raise when the encoder is built.
+    ///
+    /// Example:
+    ///     >>> import toons
+    ///     >>> class Point:
+    ///     ...     def __init__(self, x, y):
+    ///     ...         self.x, self.y = x, y
+    ///     >>> class PointEncoder(toons.TOONEncoder):
+    ///     ...     def default(self, obj):
+    ///     ...         if isinstance(obj, Point):
+    ///     ...             return {"x": obj.x, "y": obj.y}
+    ///     ...         return super().default(obj)
+    ///     >>> PointEncoder().encode({"origin": Point(0, 0)})
+    ///     'origin:\n  x: 0\n  y: 0'
+    #[pyclass(frozen, subclass, module = "toons")]
+    struct TOONEncoder {
+        ctx: crate::serialization::SerializationContext,
+    }
+
+    with_encode_args! {
+        #[pymethods]
+        impl TOONEncoder {
+            #[new]
+            fn new(; args) -> PyResult<Self> {
+                let ctx = args.build()?;
+                Ok(TOONEncoder { ctx })
+            }
+
+            /// Accept and ignore the constructor's arguments, so that the
+            /// `__init__` of a subclass can call `super().__init__(**kwargs)`
+            #[pyo3(signature = (*_args, **_kwargs))]
+            fn __init__(
+                &self,
+                _args: &Bound<'_, pyo3::types::PyTuple>,
+                _kwargs: Option<&Bound<'_, pyo3::types::PyDict>>,
+            ) {
+            }
+
+            #[getter]
+            fn indent(&self) -> usize {
+                self.ctx.indent
+            }
+
+            #[getter]
+            fn delimiter(&self) -> String {
+                self.ctx.delimiter.as_arg()
+            }
+
+            /// Serialize `obj` to a TOON formatted string, as `dumps` would with
+            /// this encoder's options, calling `default()` for each object of an
+            /// unsupported type.
+            fn encode(slf: &Bound<'_, Self>, obj: &Bound<'_, PyAny>) -> PyResult<String> {
+                let encoder = slf.get();
+                let default = slf.getattr("default")?;
+                crate::serialization::serialize(slf.py(), obj, &encoder.ctx, Some(&default))
+            }
+
+            /// Return an encodable stand-in for `obj`, an object of a type TOON
+            /// has no form for; it is encoded in place of `obj`. Override in a
+            /// subclass. The base implementation returns None, so such objects
+            /// are written as null, as `dumps` does; with `strict=True` it raises
+            /// TypeError instead.
+            fn default(&self, py: Python, obj: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+                if self.ctx.strict {
+                    return Err(crate::serialization::not_serializable(obj));
+                }
+                Ok(py.None())
+            }
+        }
+    }
+
+    /// Reusable TOON decoder holding the `loads` options.
+    ///
+    /// Args:
+    ///     strict, expand_paths, ...: The keyword arguments of `loads`, with
+    ///         the same defaults; invalid values raise when the decoder is
+    ///         built.
+    ///
+    /// Example:
+    ///     >>> import toons
+    ///     >>> decoder = toons.TOONDecoder(strict=False)
+    ///     >>> decoder.decode("items[2]: a,b")
+    ///     {'items': ['a', 'b']}
+    #[pyclass(frozen, subclass, module = "toons")]
+    struct TOONDecoder {
+        options: crate::deserialization::DecodeOptions,
+        #[pyo3(get)]
+        expand_paths: Option<String>,
+        #[pyo3(get)]
+        bare_hyphen: String,
+        #[pyo3(get)]
+        null_literal: String,
+        #[pyo3(get)]
+        true_literal: String,
+        #[pyo3(get)]
+        false_literal: String,
+        #[pyo3(get)]
+        nan_token: Option<String>,
+        #[pyo3(get)]
+        inf_token: Option<String>,
+        #[pyo3(get)]
+        neg_inf_token: Option<String>,
+        #[pyo3(get)]
+        leading_zero: String,
+        #[pyo3(get)]
+        empty_cell: String,
+        #[pyo3(get)]
+        schema: Option<Py<PyAny>>,
+    }
+
+    with_decode_args! {
+        #[pymethods]
+        impl TOONDecoder {
+            #[new]
+            fn new(py: Python; args) -> PyResult<Self> {
+                let options = args.build(py)?;
+                Ok(TOONDecoder {
+                    options,
+                    expand_paths: args.expand_paths.map(str::to_string),
+                    bare_hyphen: args.bare_hyphen.to_string(),
+                    null_literal: args.null_literal.to_string(),
+                    true_literal: args.true_literal.to_string(),
+                    false_literal: args.false_literal.to_string(),
+                    nan_token: args.nan_token.map(str::to_string),
+                    inf_token: args.inf_token.map(str::to_string),
+                    neg_inf_token: args.neg_inf_token.map(str::to_string),
+                    leading_zero: args.leading_zero.to_string(),
+                    empty_cell: args.empty_cell.to_string(),
+                    schema: args.schema.map(|schema| schema.clone().unbind()),
+                })
+            }
+
+            /// Accept and ignore the constructor's arguments, so that the
+            /// `__init__` of a subclass can call `super().__init__(**kwargs)`
+            #[pyo3(signature = (*_args, **_kwargs))]
+            fn __init__(
+                &self,
+                _args: &Bound<'_, pyo3::types::PyTuple>,
+                _kwargs: Option<&Bound<'_, pyo3::types::PyDict>>,
+            ) {
+            }
+
+            #[getter]
+            fn strict(&self) -> bool {
+                self.options.strict
+            }
+
+            #[getter]
+            fn indent(&self) -> Option<usize> {
+                self.options.indent
+            }
+
+            #[getter]
+            fn collect_warnings(&self) -> bool {
+                self.options.collect_warnings
+            }
+
+            #[getter]
+            fn resolve_aliases(&self) -> bool {
+                self.options.resolve_aliases
+            }
+
+            #[getter]
+            fn parse_datetime(&self) -> bool {
+                self.options.parse_datetime
+            }
+
+            #[getter]
+            fn max_depth(&self) -> usize {
+                self.options.max_depth
+            }
+
+            #[getter]
+            fn object_hook(&self, py: Python) -> Option<Py<PyAny>> {
+                self.options
+                    .object_hook
+                    .as_ref()
+                    .map(|hook| hook.clone_ref(py))
+            }
+
+            #[getter]
+            fn object_pairs_hook(&self, py: Python) -> Option<Py<PyAny>> {
+                self.options
+                    .object_pairs_hook
+                    .as_ref()
+                    .map(|hook| hook.clone_ref(py))
+            }
+
+            #[getter]
+            fn allow_trailing_delimiter(&self) -> bool {
+                self.options.allow_trailing_delimiter
+            }
+
+            #[getter]
+            fn allow_tab_indent(&self) -> bool {
+                self.options.allow_tab_indent
+            }
+
+            /// Deserialize a TOON string (or UTF-8 bytes) to a Python object, as
+            /// `loads` would with this decoder's options.
+            fn decode(&self, py: Python, s: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+                let text = crate::deserialization::input_text(s)?;
+                crate::deserialization::deserialize(py, &text, &self.options)
+            }
+        }
+    }
 }
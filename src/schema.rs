@@ -0,0 +1,392 @@
+use crate::deserialization::{PendingAlias, make_decode_error};
+use crate::serialization::push_pointer_segment;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple, PyType};
+
+/// The shape `loads(schema=...)` checks a decoded value against, built once
+/// from the Python spec by `Schema::from_spec`
+pub enum Schema {
+    /// `object` or `typing.Any`: every value matches
+    Any,
+    /// `None` or `type(None)`
+    Null,
+    Bool,
+    Int,
+    Float,
+    Str,
+    /// Any other class, matched with `isinstance`
+    Instance(Py<PyType>),
+    /// A dict (or `TypedDict`) of key to schema; keys not listed are kept
+    /// unchecked
+    Object(Vec<Field>),
+    /// `[item]` or `list[item]`: a list whose items all match `item`
+    List(Box<Schema>),
+    /// A tuple of alternatives, or `typing.Union` / `Optional` / `X | Y`
+    Union(Vec<Schema>),
+}
+
+/// A key of an object schema
+pub struct Field {
+    name: String,
+    schema: Schema,
+    /// Only keys a `TypedDict` declares `NotRequired` (or `total=False`)
+    /// may be absent
+    required: bool,
+}
+
+impl Schema {
+    /// Interpret the `schema` argument, raising `TypeError` for anything that
+    /// is not a type, `None`, a dict, a one-item list, a tuple of these, a
+    /// `TypedDict` or a supported `typing` form
+    pub fn from_spec(spec: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let py = spec.py();
+        if spec.is_none() {
+            return Ok(Schema::Null);
+        }
+        if let Ok(dict) = spec.cast::<PyDict>() {
+            return dict
+                .iter()
+                .map(|(key, value)| {
+                    Ok(Field {
+                        name: schema_key(&key)?,
+                        schema: Schema::from_spec(&value)?,
+                        required: true,
+                    })
+                })
+                .collect::<PyResult<_>>()
+                .map(Schema::Object);
+        }
+        if let Ok(list) = spec.cast::<PyList>()
+            && list.len() == 1
+        {
+            return Ok(Schema::List(Box::new(Schema::from_spec(
+                &list.get_item(0)?,
+            )?)));
+        }
+        if let Ok(tuple) = spec.cast::<PyTuple>()
+            && !tuple.is_empty()
+        {
+            return union(tuple);
+        }
+        let typing = py.import("typing")?;
+        if spec.is(&typing.getattr("Any")?) {
+            return Ok(Schema::Any);
+        }
+        // `int | None` (Python 3.10+) has no `__origin__`, unlike `Optional[int]`
+        if spec.get_type().name()? == "UnionType" {
+            return union(&spec.getattr("__args__")?.cast_into::<PyTuple>()?);
+        }
+        if let Ok(origin) = spec.getattr("__origin__") {
+            let args = spec
+                .getattr("__args__")
+                .ok()
+                .and_then(|args| args.cast_into::<PyTuple>().ok());
+            if origin.is(&typing.getattr("Union")?) {
+                if let Some(args) = args {
+                    return union(&args);
+                }
+            } else if origin.is(py.get_type::<PyList>()) {
+                let item = match args {
+                    Some(args) if args.len() == 1 => Schema::from_spec(&args.get_item(0)?)?,
+                    _ => Schema::Any,
+                };
+                return Ok(Schema::List(Box::new(item)));
+            } else if let Ok(origin) = origin.cast_into::<PyType>() {
+                // Other generics such as `dict[str, int]` check the container only
+                return Ok(Schema::from_type(origin));
+            }
+        }
+        if let Ok(ty) = spec.cast::<PyType>() {
+            if ty.is_subclass_of::<PyDict>()? && ty.hasattr("__total__")? {
+                return typed_dict(ty);
+            }
+            return Ok(Schema::from_type(ty.clone()));
+        }
+        Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+            "schema must be a type, None, a dict, a one-item list or a tuple of \
+             these, not {}",
+            spec.repr()?
+        )))
+    }
+
+    fn from_type(ty: Bound<'_, PyType>) -> Self {
+        let py = ty.py();
+        if ty.is(py.get_type::<PyBool>()) {
+            Schema::Bool
+        } else if ty.is(py.get_type::<PyInt>()) {
+            Schema::Int
+        } else if ty.is(py.get_type::<PyFloat>()) {
+            Schema::Float
+        } else if ty.is(py.get_type::<PyString>()) {
+            Schema::Str
+        } else if ty.is(py.None().into_bound(py).get_type()) {
+            Schema::Null
+        } else if ty.is(py.get_type::<PyAny>()) {
+            Schema::Any
+        } else {
+            Schema::Instance(ty.unbind())
+        }
+    }
+
+    /// How the schema reads in an error message
+    fn describe(&self, py: Python) -> PyResult<String> {
+        Ok(match self {
+            Schema::Any => "any value".to_string(),
+            Schema::Null => "None".to_string(),
+            Schema::Bool => "bool".to_string(),
+            Schema::Int => "int".to_string(),
+            Schema::Float => "float".to_string(),
+            Schema::Str => "str".to_string(),
+            Schema::Instance(ty) => ty.bind(py).name()?.to_string(),
+            Schema::Object(_) => "dict".to_string(),
+            Schema::List(_) => "list".to_string(),
+            Schema::Union(alternatives) => alternatives
+                .iter()
+                .map(|schema| schema.describe(py))
+                .collect::<PyResult<Vec<_>>>()?
+                .join(" or "),
+        })
+    }
+}
+
+fn schema_key(key: &Bound<'_, PyAny>) -> PyResult<String> {
+    match key.cast::<PyString>() {
+        Ok(key) => Ok(key.to_cow()?.into_owned()),
+        Err(_) => Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+            "schema keys must be strings, not {}",
+            key.get_type().name()?
+        ))),
+    }
+}
+
+fn union(alternatives: &Bound<'_, PyTuple>) -> PyResult<Schema> {
+    alternatives
+        .iter()
+        .map(|spec| Schema::from_spec(&spec))
+        .collect::<PyResult<_>>()
+        .map(Schema::Union)
+}
+
+/// An object schema from a `TypedDict`'s resolved annotations
+fn typed_dict(ty: &Bound<'_, PyType>) -> PyResult<Schema> {
+    let py = ty.py();
+    let hints = py
+        .import("typing")?
+        .call_method1("get_type_hints", (ty,))?
+        .cast_into::<PyDict>()?;
+    // `__required_keys__` is new in Python 3.9; before that only `total`
+    // says whether keys may be absent
+    let required_keys = ty.getattr("__required_keys__").ok();
+    let total: bool = ty.getattr("__total__")?.extract()?;
+    hints
+        .iter()
+        .map(|(key, value)| {
+            let required = match &required_keys {
+                Some(keys) => keys.contains(&key)?,
+                None => total,
+            };
+            Ok(Field {
+                name: schema_key(&key)?,
+                schema: Schema::from_spec(&value)?,
+                required,
+            })
+        })
+        .collect::<PyResult<_>>()
+        .map(Schema::Object)
+}
+
+/// Check `value` against `schema`, returning it with numbers and booleans
+/// coerced where the intended value is unambiguous, or raise a
+/// `ToonDecodeError` naming the path of the first mismatch
+pub fn apply_schema(value: &Bound<'_, PyAny>, schema: &Schema) -> PyResult<Py<PyAny>> {
+    let py = value.py();
+    let mut path = String::new();
+    match check(value, schema, &mut path, true)? {
+        Ok(value) => Ok(value),
+        Err(mismatch) => {
+            let path = if path.is_empty() { "(root)" } else { &path };
+            Err(make_decode_error(
+                py,
+                format!("Schema mismatch at {}: {}", path, mismatch),
+                None,
+                None,
+            ))
+        }
+    }
+}
+
+/// The checked (and possibly coerced) value, or a description of the
+/// mismatch with `path` left pointing at it. Containers are rebuilt rather
+/// than changed in place, so a union alternative that fails halfway leaves
+/// the value untouched for the next one.
+fn check(
+    value: &Bound<'_, PyAny>,
+    schema: &Schema,
+    path: &mut String,
+    coerce: bool,
+) -> PyResult<Result<Py<PyAny>, String>> {
+    let py = value.py();
+    // Aliases are resolved after the check, to an object checked elsewhere
+    if value.cast::<PendingAlias>().is_ok() {
+        return Ok(Ok(value.clone().unbind()));
+    }
+    let matched = match schema {
+        Schema::Any => Some(value.clone().unbind()),
+        Schema::Null => value.is_none().then(|| value.clone().unbind()),
+        Schema::Bool => match value.cast::<PyBool>() {
+            Ok(_) => Some(value.clone().unbind()),
+            Err(_) if coerce => bool_from_str(value)?,
+            Err(_) => None,
+        },
+        Schema::Int => {
+            if is_int(value) {
+                Some(value.clone().unbind())
+            } else if coerce {
+                int_from(value)?
+            } else {
+                None
+            }
+        }
+        Schema::Float => {
+            if value.is_instance_of::<PyFloat>() {
+                Some(value.clone().unbind())
+            } else if coerce {
+                float_from(value)?
+            } else {
+                None
+            }
+        }
+        Schema::Str => value
+            .is_instance_of::<PyString>()
+            .then(|| value.clone().unbind()),
+        Schema::Instance(ty) => value
+            .is_instance(ty.bind(py))?
+            .then(|| value.clone().unbind()),
+        Schema::Object(fields) => {
+            let Ok(dict) = value.cast::<PyDict>() else {
+                return Ok(Err(expected(schema, value)?));
+            };
+            let checked = dict.copy()?;
+            let parent_len = path.len();
+            for field in fields {
+                path.push('/');
+                push_pointer_segment(path, &field.name);
+                match dict.get_item(&field.name)? {
+                    Some(item) => match check(&item, &field.schema, path, coerce)? {
+                        Ok(item) => checked.set_item(&field.name, item)?,
+                        Err(mismatch) => return Ok(Err(mismatch)),
+                    },
+                    None if field.required => {
+                        return Ok(Err("missing required key".to_string()));
+                    }
+                    None => {}
+                }
+                path.truncate(parent_len);
+            }
+            Some(checked.into_any().unbind())
+        }
+        Schema::List(item_schema) => {
+            let Ok(list) = value.cast::<PyList>() else {
+                return Ok(Err(expected(schema, value)?));
+            };
+            let parent_len = path.len();
+            let mut items = Vec::with_capacity(list.len());
+            for (i, item) in list.iter().enumerate() {
+                path.push('/');
+                path.push_str(&i.to_string());
+                match check(&item, item_schema, path, coerce)? {
+                    Ok(item) => items.push(item),
+                    Err(mismatch) => return Ok(Err(mismatch)),
+                }
+                path.truncate(parent_len);
+            }
+            Some(PyList::new(py, items)?.into_any().unbind())
+        }
+        Schema::Union(alternatives) => {
+            // An alternative the value matches as is wins over one it
+            // would have to be coerced to
+            let passes: &[bool] = if coerce { &[false, true] } else { &[false] };
+            let parent_len = path.len();
+            for &coerce in passes {
+                for alternative in alternatives {
+                    if let Ok(value) = check(value, alternative, path, coerce)? {
+                        return Ok(Ok(value));
+                    }
+                    path.truncate(parent_len);
+                }
+            }
+            None
+        }
+    };
+    match matched {
+        Some(value) => Ok(Ok(value)),
+        None => Ok(Err(expected(schema, value)?)),
+    }
+}
+
+fn expected(schema: &Schema, value: &Bound<'_, PyAny>) -> PyResult<String> {
+    let py = value.py();
+    let found = if value.is_instance_of::<PyDict>() || value.is_instance_of::<PyList>() {
+        value.get_type().name()?.to_string()
+    } else {
+        format!("{} {}", value.get_type().name()?, value.repr()?)
+    };
+    Ok(format!("expected {}, got {}", schema.describe(py)?, found))
+}
+
+/// An int that is not a bool
+fn is_int(value: &Bound<'_, PyAny>) -> bool {
+    value.is_instance_of::<PyInt>() && !value.is_instance_of::<PyBool>()
+}
+
+/// `True`/`False` for the strings `"true"`/`"false"`
+fn bool_from_str(value: &Bound<'_, PyAny>) -> PyResult<Option<Py<PyAny>>> {
+    let py = value.py();
+    let Ok(s) = value.cast::<PyString>() else {
+        return Ok(None);
+    };
+    Ok(match &*s.to_cow()? {
+        "true" => Some(PyBool::new(py, true).to_owned().into_any().unbind()),
+        "false" => Some(PyBool::new(py, false).to_owned().into_any().unbind()),
+        _ => None,
+    })
+}
+
+/// An int from a float with no fractional part or from a string of digits
+fn int_from(value: &Bound<'_, PyAny>) -> PyResult<Option<Py<PyAny>>> {
+    let py = value.py();
+    if let Ok(f) = value.cast::<PyFloat>() {
+        let f = f.value();
+        if f.is_finite() && f.fract() == 0.0 {
+            return Ok(Some(py.get_type::<PyInt>().call1((f,))?.unbind()));
+        }
+    } else if let Ok(s) = value.cast::<PyString>() {
+        let s = s.to_cow()?;
+        let digits = s.strip_prefix('-').unwrap_or(&s);
+        if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Ok(Some(py.get_type::<PyInt>().call1((&*s,))?.unbind()));
+        }
+    }
+    Ok(None)
+}
+
+/// A float from an int or from a string holding a finite decimal number
+fn float_from(value: &Bound<'_, PyAny>) -> PyResult<Option<Py<PyAny>>> {
+    let py = value.py();
+    if is_int(value) {
+        return Ok(value
+            .extract::<f64>()
+            .ok()
+            .map(|f| PyFloat::new(py, f).into_any().unbind()));
+    }
+    if let Ok(s) = value.cast::<PyString>() {
+        let s = s.to_cow()?;
+        let is_decimal = s.bytes().any(|b| b.is_ascii_digit())
+            && s.bytes()
+                .all(|b| b.is_ascii_digit() || matches!(b, b'-' | b'+' | b'.' | b'e' | b'E'));
+        if is_decimal && let Ok(f) = s.parse::<f64>() {
+            return Ok(Some(PyFloat::new(py, f).into_any().unbind()));
+        }
+    }
+    Ok(None)
+}
@@ -1,22 +1,736 @@
+use crate::text::{
+    Output, RESERVED_DELIMITERS, is_valid_delimiter, is_valid_unquoted_key, needs_quoting,
+    serialize_key, serialize_string, write_array_header, write_ascii_quoted_string,
+    write_quoted_string, write_shortest_float, write_unicode_escape,
+};
 use pyo3::prelude::*;
-use pyo3::types::{PyDate, PyDateTime, PyDict, PyList, PyTime};
-use std::collections::HashSet;
+use pyo3::sync::PyOnceLock;
+use pyo3::types::{
+    PyBool, PyByteArray, PyBytes, PyComplex, PyDate, PyDateTime, PyDelta, PyDict, PyDictItems,
+    PyFloat, PyInt, PyList, PyString, PyTime, PyTuple, PyType,
+};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write as FmtWrite;
 
-/// Serialization context for key folding options
+/// Order in which object keys (and tabular fields) are emitted
+#[derive(Clone, Copy, PartialEq)]
+pub enum KeyOrder {
+    /// Dict insertion order (default)
+    Insertion,
+    /// Lexicographic order by code point, like `json.dumps(sort_keys=True)`
+    Sorted,
+    /// Human order: digit runs compare numerically (`item2` < `item10`)
+    Natural,
+}
+
+impl KeyOrder {
+    /// Interpret the `sort_keys` argument: `None`/`False`, `True`, or `"natural"`
+    pub fn from_arg(arg: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        let Some(arg) = arg else {
+            return Ok(KeyOrder::Insertion);
+        };
+        if let Ok(flag) = arg.extract::<bool>() {
+            return Ok(if flag {
+                KeyOrder::Sorted
+            } else {
+                KeyOrder::Insertion
+            });
+        }
+        match arg.extract::<String>().as_deref() {
+            Ok("natural") => Ok(KeyOrder::Natural),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "sort_keys must be a bool or \"natural\"",
+            )),
+        }
+    }
+
+    /// Sort keys in place according to this order
+    fn sort<T>(self, items: &mut [T], key: impl Fn(&T) -> &str) {
+        match self {
+            KeyOrder::Insertion => {}
+            KeyOrder::Sorted => items.sort_by(|a, b| key(a).cmp(key(b))),
+            KeyOrder::Natural => items.sort_by(|a, b| natural_cmp(key(a), key(b))),
+        }
+    }
+}
+
+/// Compare two strings in natural order, treating runs of ASCII digits as numbers
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(ca), Some(cb)) if ca.is_ascii_digit() && cb.is_ascii_digit() => {
+                let mut run_a = String::new();
+                while let Some(c) = a_chars.next_if(|c| c.is_ascii_digit()) {
+                    run_a.push(c);
+                }
+                let mut run_b = String::new();
+                while let Some(c) = b_chars.next_if(|c| c.is_ascii_digit()) {
+                    run_b.push(c);
+                }
+                // Compare numerically without overflow: strip leading zeros,
+                // then a longer run is larger, equal lengths compare digit-wise
+                let num_a = run_a.trim_start_matches('0');
+                let num_b = run_b.trim_start_matches('0');
+                let ord = num_a.len().cmp(&num_b.len()).then_with(|| num_a.cmp(num_b));
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            (Some(ca), Some(cb)) => {
+                if ca != cb {
+                    return ca.cmp(&cb);
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+/// Text encoding used for `bytes`/`bytearray` values
+#[derive(Clone, Copy, PartialEq)]
+pub enum BytesEncoding {
+    Base64,
+    Hex,
+}
+
+impl BytesEncoding {
+    /// Interpret the `bytes_encoding` argument: `"base64"` or `"hex"`
+    pub fn from_arg(arg: &str) -> PyResult<Self> {
+        match arg {
+            "base64" => Ok(BytesEncoding::Base64),
+            "hex" => Ok(BytesEncoding::Hex),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "bytes_encoding must be \"base64\" or \"hex\"",
+            )),
+        }
+    }
+
+    /// Encode raw bytes as text
+    fn encode(self, data: &[u8]) -> String {
+        match self {
+            BytesEncoding::Base64 => encode_base64(data),
+            BytesEncoding::Hex => data.iter().fold(String::new(), |mut out, b| {
+                write!(out, "{:02x}", b).unwrap();
+                out
+            }),
+        }
+    }
+}
+
+/// Counts the UTF-8 bytes written instead of storing them, and the
+/// characters of the last line
+#[derive(Default)]
+struct ByteCounter {
+    bytes: usize,
+    column: usize,
+}
+
+impl FmtWrite for ByteCounter {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.bytes += s.len();
+        match s.rfind('\n') {
+            Some(i) => self.column = s[i + 1..].chars().count(),
+            None => self.column += s.chars().count(),
+        }
+        Ok(())
+    }
+}
+
+impl Output for ByteCounter {
+    fn column(&self) -> usize {
+        self.column
+    }
+}
+
+/// When a list of objects is written in the tabular `[N]{fields}:` form
+#[derive(Clone, Copy, PartialEq)]
+pub enum TabularMode {
+    /// Only when every row has the same keys
+    Auto,
+    /// Never; always use expanded lists
+    Never,
+    /// Also when keys differ: the header is the union, missing cells are `null`
+    Fill,
+}
+
+impl TabularMode {
+    /// Interpret the `tabular` argument: `"auto"`, `"never"` or `"fill"`
+    pub fn from_arg(arg: &str) -> PyResult<Self> {
+        match arg {
+            "auto" => Ok(TabularMode::Auto),
+            "never" => Ok(TabularMode::Never),
+            "fill" => Ok(TabularMode::Fill),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "tabular must be \"auto\", \"never\" or \"fill\"",
+            )),
+        }
+    }
+}
+
+/// Order of the fields in a tabular header, unless `sort_keys` sets one
+#[derive(Clone, Copy, PartialEq)]
+pub enum FieldOrder {
+    /// The first row's order; keys only later rows have follow as first seen
+    First,
+    /// Lexicographic order by code point
+    Sorted,
+    /// Every row's order merged: a new key goes right after the key that
+    /// precedes it in the row where it first appears
+    Union,
+}
+
+impl FieldOrder {
+    /// Interpret the `tabular_field_order` argument: `"first"`, `"sorted"`
+    /// or `"union"`
+    pub fn from_arg(arg: &str) -> PyResult<Self> {
+        match arg {
+            "first" => Ok(FieldOrder::First),
+            "sorted" => Ok(FieldOrder::Sorted),
+            "union" => Ok(FieldOrder::Union),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "tabular_field_order must be \"first\", \"sorted\" or \"union\"",
+            )),
+        }
+    }
+
+    /// Put a table's fields in this order, or in the `sort_keys` order if
+    /// one is set
+    fn apply(self, fields: &mut [String], key_order: KeyOrder) {
+        if key_order != KeyOrder::Insertion {
+            key_order.sort(fields, |key| key);
+        } else if self == FieldOrder::Sorted {
+            KeyOrder::Sorted.sort(fields, |key| key);
+        }
+    }
+}
+
+/// Type annotation on a tabular field, as in `[N]{count:int,ratio:float}:`,
+/// that makes every cell of the column decode to the same Python type
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColumnType {
+    Int,
+    Float,
+    Bool,
+    Str,
+    Null,
+}
+
+impl ColumnType {
+    /// Interpret the annotation after a field name's `:`
+    pub fn from_annotation(annotation: &str) -> Option<Self> {
+        match annotation {
+            "int" => Some(ColumnType::Int),
+            "float" => Some(ColumnType::Float),
+            "bool" => Some(ColumnType::Bool),
+            "str" => Some(ColumnType::Str),
+            "null" => Some(ColumnType::Null),
+            _ => None,
+        }
+    }
+
+    pub fn annotation(self) -> &'static str {
+        match self {
+            ColumnType::Int => "int",
+            ColumnType::Float => "float",
+            ColumnType::Bool => "bool",
+            ColumnType::Str => "str",
+            ColumnType::Null => "null",
+        }
+    }
+
+    /// The type of a cell holding exactly an `int` or a `float`
+    fn of(value: &Bound<'_, PyAny>) -> Option<Self> {
+        if value.is_exact_instance_of::<PyInt>() {
+            Some(ColumnType::Int)
+        } else if value.is_exact_instance_of::<PyFloat>() {
+            Some(ColumnType::Float)
+        } else {
+            None
+        }
+    }
+}
+
+/// The `delimiter` argument: a fixed character, or chosen for each document
+#[derive(Clone, Copy, PartialEq)]
+pub enum Delimiter {
+    Fixed(char),
+    /// The `AUTO_DELIMITERS` entry the fewest strings need quoting for
+    Auto,
+}
+
+/// Candidates for `delimiter="auto"`, in order of preference on ties: the
+/// default comma, then the pipe, which stays visible, then tab
+const AUTO_DELIMITERS: [char; 3] = [',', '|', '\t'];
+
+impl Delimiter {
+    /// Interpret the `delimiter` argument: a single character accepted by
+    /// `is_valid_delimiter` (`","`, `"\t"`, `"|"`, `";"`, ...) or `"auto"`
+    pub fn from_arg(arg: &str) -> PyResult<Self> {
+        if arg == "auto" {
+            return Ok(Delimiter::Auto);
+        }
+        let mut chars = arg.chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) if is_valid_delimiter(ch) => Ok(Delimiter::Fixed(ch)),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "delimiter must be \"auto\", a tab or an ASCII punctuation character \
+                 other than {}, not {:?}",
+                RESERVED_DELIMITERS
+                    .chars()
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                arg
+            ))),
+        }
+    }
+
+    /// The argument this was parsed from
+    pub fn as_arg(self) -> String {
+        match self {
+            Delimiter::Fixed(delimiter) => delimiter.to_string(),
+            Delimiter::Auto => "auto".to_string(),
+        }
+    }
+
+    /// The delimiter to write the normalized `obj` with
+    fn resolve(self, obj: &Bound<'_, PyAny>) -> PyResult<char> {
+        match self {
+            Delimiter::Fixed(delimiter) => Ok(delimiter),
+            Delimiter::Auto => {
+                let mut quoted = [0usize; AUTO_DELIMITERS.len()];
+                count_delimiter_quoting(obj, &mut quoted)?;
+                // `min_by_key` keeps the first of equal counts
+                let best = (0..AUTO_DELIMITERS.len())
+                    .min_by_key(|&i| quoted[i])
+                    .unwrap();
+                Ok(AUTO_DELIMITERS[best])
+            }
+        }
+    }
+}
+
+/// For each of `AUTO_DELIMITERS`, count the strings under `obj` (dict values
+/// and list items, recursively) that would be quoted with it as delimiter.
+/// Strings containing neither `,` nor `|` quote the same under every
+/// candidate (tabs are always escaped), so they are skipped.
+fn count_delimiter_quoting(
+    obj: &Bound<'_, PyAny>,
+    quoted: &mut [usize; AUTO_DELIMITERS.len()],
+) -> PyResult<()> {
+    if let Ok(s) = obj.cast::<PyString>() {
+        let s = s.to_cow()?;
+        if s.contains([',', '|']) {
+            for (count, &delimiter) in quoted.iter_mut().zip(&AUTO_DELIMITERS) {
+                if needs_quoting(&s, delimiter) {
+                    *count += 1;
+                }
+            }
+        }
+    } else if let Ok(dict) = obj.cast::<PyDict>() {
+        for value in dict.values() {
+            count_delimiter_quoting(&value, quoted)?;
+        }
+    } else if let Ok(list) = obj.cast::<PyList>() {
+        for item in list {
+            count_delimiter_quoting(&item, quoted)?;
+        }
+    }
+    Ok(())
+}
+
+/// What an `enum.Enum` member is written as
+#[derive(Clone, Copy, PartialEq)]
+pub enum EnumAs {
+    Value,
+    Name,
+}
+
+impl EnumAs {
+    /// Interpret the `enum_as` argument: `"value"` or `"name"`
+    pub fn from_arg(arg: &str) -> PyResult<Self> {
+        match arg {
+            "value" => Ok(EnumAs::Value),
+            "name" => Ok(EnumAs::Name),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "enum_as must be \"value\" or \"name\"",
+            )),
+        }
+    }
+
+    fn attr(self) -> &'static str {
+        match self {
+            EnumAs::Value => "value",
+            EnumAs::Name => "name",
+        }
+    }
+}
+
+/// What a `datetime.timedelta` is written as
+#[derive(Clone, Copy, PartialEq)]
+pub enum TimedeltaAs {
+    /// `total_seconds()`, as a number
+    Seconds,
+    /// An ISO-8601 duration string such as `P1DT2H30M`
+    Iso,
+}
+
+impl TimedeltaAs {
+    /// Interpret the `timedelta_as` argument: `"seconds"` or `"iso"`
+    pub fn from_arg(arg: &str) -> PyResult<Self> {
+        match arg {
+            "seconds" => Ok(TimedeltaAs::Seconds),
+            "iso" => Ok(TimedeltaAs::Iso),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "timedelta_as must be \"seconds\" or \"iso\"",
+            )),
+        }
+    }
+}
+
+/// What each indentation level is made of
+#[derive(Clone, Copy, PartialEq)]
+pub enum IndentChar {
+    /// `indent` spaces
+    Space,
+    /// One tab, whatever `indent` is
+    Tab,
+}
+
+impl IndentChar {
+    /// Interpret the `indent_char` argument: `" "` or `"\t"`
+    pub fn from_arg(arg: &str) -> PyResult<Self> {
+        match arg {
+            " " => Ok(IndentChar::Space),
+            "\t" => Ok(IndentChar::Tab),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "indent_char must be \" \" or \"\\t\"",
+            )),
+        }
+    }
+}
+
+/// What ends each line but the last
+#[derive(Clone, Copy, PartialEq)]
+pub enum LineSeparator {
+    Lf,
+    CrLf,
+}
+
+impl LineSeparator {
+    /// Interpret the `line_separator` argument: `"\n"` or `"\r\n"`
+    pub fn from_arg(arg: &str) -> PyResult<Self> {
+        match arg {
+            "\n" => Ok(LineSeparator::Lf),
+            "\r\n" => Ok(LineSeparator::CrLf),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "line_separator must be \"\\n\" or \"\\r\\n\"",
+            )),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineSeparator::Lf => "\n",
+            LineSeparator::CrLf => "\r\n",
+        }
+    }
+}
+
+/// The tokens written (and, on decode, also read) for `None`, `True` and
+/// `False`
+#[derive(Clone)]
+pub struct Literals {
+    pub null: String,
+    pub true_: String,
+    pub false_: String,
+}
+
+impl Default for Literals {
+    fn default() -> Self {
+        Self {
+            null: "null".to_string(),
+            true_: "true".to_string(),
+            false_: "false".to_string(),
+        }
+    }
+}
+
+impl Literals {
+    /// Interpret the `null_literal`, `true_literal` and `false_literal`
+    /// arguments. Each must be a distinct word (letters, digits and `_`)
+    /// that does not read as a number, so it can appear bare in any value
+    /// position and table cell.
+    pub fn from_args(null: &str, true_: &str, false_: &str) -> PyResult<Self> {
+        for (name, literal, standard) in [
+            ("null_literal", null, "null"),
+            ("true_literal", true_, "true"),
+            ("false_literal", false_, "false"),
+        ] {
+            let is_word = !literal.is_empty()
+                && literal.chars().all(|c| c.is_alphanumeric() || c == '_')
+                && !literal.starts_with(|c: char| c.is_ascii_digit());
+            if !is_word {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "{} must be a word of letters, digits and underscores not starting with a digit, got {:?}",
+                    name, literal
+                )));
+            }
+            if literal != standard && matches!(literal, "null" | "true" | "false") {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "{} cannot be {:?}, the standard literal of another value",
+                    name, literal
+                )));
+            }
+        }
+        if null == true_ || null == false_ || true_ == false_ {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "null_literal, true_literal and false_literal must differ",
+            ));
+        }
+        Ok(Self {
+            null: null.to_string(),
+            true_: true_.to_string(),
+            false_: false_.to_string(),
+        })
+    }
+
+    pub fn bool(&self, b: bool) -> &str {
+        if b { &self.true_ } else { &self.false_ }
+    }
+
+    /// Whether `s` is one of the standard literals or one of these, and so
+    /// must be quoted when it is a string value
+    fn collides(&self, s: &str) -> bool {
+        s == self.null || s == self.true_ || s == self.false_
+    }
+
+    /// The standard literal that `token` stands for, if it is one of these
+    pub fn standard<'a>(&self, token: &'a str) -> &'a str {
+        if token == self.null {
+            "null"
+        } else if token == self.true_ {
+            "true"
+        } else if token == self.false_ {
+            "false"
+        } else {
+            token
+        }
+    }
+}
+
+/// Quoted strings standing for NaN, Infinity and -Infinity, written by
+/// `dumps` instead of null and read back as floats by `loads`
+#[derive(Clone, Default)]
+pub struct FloatTokens {
+    pub nan: Option<String>,
+    pub inf: Option<String>,
+    pub neg_inf: Option<String>,
+}
+
+impl FloatTokens {
+    /// Interpret the `nan_token`, `inf_token` and `neg_inf_token` arguments.
+    /// Each must be non-empty and need no escaping, so it reads back as
+    /// written, and the set ones must differ.
+    pub fn from_args(
+        nan: Option<&str>,
+        inf: Option<&str>,
+        neg_inf: Option<&str>,
+    ) -> PyResult<Self> {
+        let named = [
+            ("nan_token", nan),
+            ("inf_token", inf),
+            ("neg_inf_token", neg_inf),
+        ];
+        for (name, token) in named {
+            if let Some(token) = token
+                && (token.is_empty()
+                    || token
+                        .chars()
+                        .any(|c| matches!(c, '"' | '\\') || c.is_control()))
+            {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "{} must be non-empty and free of quotes, backslashes and control characters, got {:?}",
+                    name, token
+                )));
+            }
+        }
+        let set: Vec<&str> = named.iter().filter_map(|(_, token)| *token).collect();
+        if (1..set.len()).any(|i| set[..i].contains(&set[i])) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "nan_token, inf_token and neg_inf_token must differ",
+            ));
+        }
+        Ok(Self {
+            nan: nan.map(str::to_string),
+            inf: inf.map(str::to_string),
+            neg_inf: neg_inf.map(str::to_string),
+        })
+    }
+
+    /// The token for the non-finite float `f`, if one is set
+    pub fn token(&self, f: f64) -> Option<&str> {
+        if f.is_nan() {
+            self.nan.as_deref()
+        } else if f > 0.0 {
+            self.inf.as_deref()
+        } else {
+            self.neg_inf.as_deref()
+        }
+    }
+
+    /// The float that the (unquoted) string `s` stands for, if it is a token
+    pub fn value(&self, s: &str) -> Option<f64> {
+        if self.nan.as_deref() == Some(s) {
+            Some(f64::NAN)
+        } else if self.inf.as_deref() == Some(s) {
+            Some(f64::INFINITY)
+        } else if self.neg_inf.as_deref() == Some(s) {
+            Some(f64::NEG_INFINITY)
+        } else {
+            None
+        }
+    }
+}
+
+/// Standard (RFC 4648) base64 with padding
+fn encode_base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+static UUID_TYPE: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static ENUM_TYPE: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static DATACLASS_FIELDS: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static MAPPING_TYPE: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static FRACTION_TYPE: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static PATHLIKE_TYPE: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static IP_ADDRESS_BASE: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static FSDECODE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+/// `isinstance(obj, module.name)`, importing the class once and caching it in `cell`
+fn is_instance_of_class(
+    obj: &Bound<'_, PyAny>,
+    cell: &'static PyOnceLock<Py<PyType>>,
+    module: &str,
+    name: &str,
+) -> PyResult<bool> {
+    obj.is_instance(cell.import(obj.py(), module, name)?)
+}
+
+/// Whether `obj` is an `ipaddress` address, interface or network. They share
+/// the private base class `_IPAddressBase` (and no public one).
+fn is_ip_address(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    is_instance_of_class(obj, &IP_ADDRESS_BASE, "ipaddress", "_IPAddressBase")
+}
+
+/// Serialization context for key folding, key ordering and type conversion options
 #[derive(Clone)]
 pub struct SerializationContext {
+    /// Spaces per indentation level
+    pub indent: usize,
+    pub delimiter: Delimiter,
     pub key_folding: bool,
     pub flatten_depth: usize,
+    pub key_order: KeyOrder,
+    pub bytes_encoding: BytesEncoding,
+    pub enum_as: EnumAs,
+    pub float_precision: Option<usize>,
+    pub strip_trailing_zeros: bool,
+    pub skipkeys: bool,
+    pub share_by_identity: bool,
+    pub tabular: TabularMode,
+    pub allow_exponent: bool,
+    pub preserve_float: bool,
+    pub allow_nan: bool,
+    pub typed_tabular: bool,
+    pub rich_numbers: bool,
+    pub quote_strings: bool,
+    pub literals: Literals,
+    pub inline_threshold: Option<usize>,
+    pub align: bool,
+    pub float_tokens: FloatTokens,
+    pub tabular_field_order: FieldOrder,
+    pub compact: bool,
+    pub tabular_nested_arrays: bool,
+    pub timedelta_as: TimedeltaAs,
+    pub indent_char: IndentChar,
+    pub line_separator: LineSeparator,
+    pub header_comment: Option<String>,
+    pub tuple_key_sep: Option<String>,
+    pub ensure_ascii: bool,
+    pub strict: bool,
+    pub max_line_length: Option<usize>,
+    pub sort_rows_by: Option<String>,
 }
 
 impl SerializationContext {
-    pub fn new(key_folding: bool, flatten_depth: Option<usize>) -> Self {
-        Self {
-            key_folding,
-            flatten_depth: flatten_depth.unwrap_or(usize::MAX),
+    /// Write a string value, quoted when TOON requires it or when
+    /// `ensure_ascii` has non-ASCII characters to escape
+    fn write_string(&self, s: &str, output: &mut impl Output, delimiter: char) {
+        if self.ensure_ascii && !s.is_ascii() {
+            write_ascii_quoted_string(s, output);
+        } else {
+            serialize_string(s, output, delimiter);
+        }
+    }
+
+    /// Write `s` quoted, escaping non-ASCII characters under `ensure_ascii`
+    fn write_quoted(&self, s: &str, output: &mut impl Output) {
+        if self.ensure_ascii {
+            write_ascii_quoted_string(s, output);
+        } else {
+            write_quoted_string(s, output);
+        }
+    }
+
+    /// Write an object key or tabular field name, quoted like a string under
+    /// `ensure_ascii` when it has non-ASCII characters
+    fn write_key(&self, key: &str, output: &mut impl Output) {
+        if self.ensure_ascii && !key.is_ascii() {
+            write_ascii_quoted_string(key, output);
+        } else {
+            serialize_key(key, output);
         }
     }
+
+    /// Collect a dict's `(key, value)` pairs in the configured key order
+    fn ordered_items<'py>(
+        &self,
+        dict: &Bound<'py, PyDict>,
+    ) -> PyResult<Vec<(String, Bound<'py, PyAny>)>> {
+        let mut items = dict
+            .items()
+            .iter()
+            .map(|item| item.extract::<(String, Bound<'py, PyAny>)>())
+            .collect::<PyResult<Vec<_>>>()?;
+        self.key_order.sort(&mut items, |(key, _)| key);
+        Ok(items)
+    }
 }
 
 /// Serialize a Python object to TOON format string.
@@ -25,10 +739,7 @@ impl SerializationContext {
 ///
 /// * `py` - Python interpreter handle
 /// * `obj` - Python object to serialize (dict, list, or primitive)
-/// * `delimiter` - Delimiter character for arrays/tables (',' | '\t' | '|')
-/// * `indent_size` - Number of spaces per indentation level
-/// * `key_folding` - Enable key folding (e.g., `a.b: value` for `a: {b: value}`)
-/// * `flatten_depth` - Maximum depth for key folding (None for unlimited)
+/// * `ctx` - Delimiter, indentation, key folding and the other `dumps` options
 ///
 /// # Returns
 ///
@@ -36,174 +747,1081 @@ impl SerializationContext {
 pub fn serialize(
     py: Python,
     obj: &Bound<'_, PyAny>,
-    delimiter: char,
-    indent_size: usize,
-    key_folding: bool,
-    flatten_depth: Option<usize>,
+    ctx: &SerializationContext,
+    default: Option<&Bound<'_, PyAny>>,
 ) -> PyResult<String> {
-    let mut output = String::new();
-    let ctx = SerializationContext::new(key_folding, flatten_depth);
-    serialize_value(py, obj, &mut output, 0, delimiter, true, indent_size, &ctx)?;
+    let mut output = String::with_capacity(capacity_hint(obj, 0));
+    encode(py, obj, &mut output, ctx, default)?;
     Ok(output)
 }
 
+/// Rough byte size of the TOON form of `obj`, used to size the output up
+/// front: a guess per scalar and per separator, with lists extrapolated from
+/// their first item. Only the top levels are looked at, so this stays cheap
+/// next to encoding; an estimate that is off just costs a reallocation.
+fn capacity_hint(obj: &Bound<'_, PyAny>, level: usize) -> usize {
+    const SCALAR: usize = 6;
+    const MAX_LEVEL: usize = 3;
+    if level > MAX_LEVEL {
+        return SCALAR;
+    }
+    if let Ok(dict) = obj.cast::<PyDict>() {
+        dict.iter()
+            .map(|(_, value)| 2 + capacity_hint(&value, level + 1))
+            .sum()
+    } else if let Ok(list) = obj.cast::<PyList>() {
+        match list.get_item(0) {
+            Ok(first) => list.len() * (1 + capacity_hint(&first, level + 1)),
+            Err(_) => SCALAR,
+        }
+    } else if let Ok(s) = obj.cast::<PyString>() {
+        s.len().unwrap_or(SCALAR)
+    } else {
+        SCALAR
+    }
+}
+
+/// Compute the UTF-8 byte length of `serialize(...)` for the same arguments
+/// without building the output string.
+pub fn estimate_size(
+    py: Python,
+    obj: &Bound<'_, PyAny>,
+    ctx: &SerializationContext,
+) -> PyResult<usize> {
+    let mut counter = ByteCounter::default();
+    encode(py, obj, &mut counter, ctx, None)?;
+    Ok(counter.bytes)
+}
+
+/// Normalize `obj` the way `serialize` does before writing it, so the
+/// result can be compared with what decoding the TOON output returns
+pub fn normalize<'py>(
+    obj: &Bound<'py, PyAny>,
+    ctx: &SerializationContext,
+) -> PyResult<Bound<'py, PyAny>> {
+    Ok(Normalizer::new(ctx, None)
+        .normalize(obj)?
+        .unwrap_or_else(|| obj.clone()))
+}
+
+/// List where `decoded` differs from `expected`, a value returned by
+/// `normalize`, as `path: expected X, got Y` lines in document order.
+///
+/// Objects are compared by key set, so `sort_keys` is not a difference, and
+/// numbers by value, so `1.0` read back as `1` is not either. Scalars the
+/// encoder writes in another form (dates, UUIDs, enums, bytes, ...) and
+/// `share_by_identity` aliases are not compared. With `float_precision`,
+/// floats may differ by up to the last digit kept.
+pub fn roundtrip_differences(
+    expected: &Bound<'_, PyAny>,
+    decoded: &Bound<'_, PyAny>,
+    ctx: &SerializationContext,
+) -> PyResult<Vec<String>> {
+    let mut differences = Vec::new();
+    diff_value(expected, decoded, &mut String::new(), &mut differences, ctx)?;
+    Ok(differences)
+}
+
+fn diff_value(
+    expected: &Bound<'_, PyAny>,
+    decoded: &Bound<'_, PyAny>,
+    path: &mut String,
+    differences: &mut Vec<String>,
+    ctx: &SerializationContext,
+) -> PyResult<()> {
+    if expected.is_instance_of::<AliasRef>() {
+        return Ok(());
+    }
+    if let Ok(dict) = expected.cast::<PyDict>() {
+        let Ok(other) = decoded.cast::<PyDict>() else {
+            let what = format!("expected an object, got {}", describe(decoded)?);
+            push_difference(differences, path, what);
+            return Ok(());
+        };
+        let parent_len = path.len();
+        for (key, value) in dict.iter() {
+            path.push('/');
+            push_pointer_segment(path, &key.str()?.to_cow()?);
+            match other.get_item(&key)? {
+                Some(other_value) => diff_value(&value, &other_value, path, differences, ctx)?,
+                None => push_difference(differences, path, "missing after decoding".to_string()),
+            }
+            path.truncate(parent_len);
+        }
+        for key in other.keys() {
+            if !dict.contains(&key)? {
+                path.push('/');
+                push_pointer_segment(path, &key.str()?.to_cow()?);
+                push_difference(differences, path, "not in the original".to_string());
+                path.truncate(parent_len);
+            }
+        }
+        return Ok(());
+    }
+    if let Ok(list) = expected.cast::<PyList>() {
+        // Tables were written in `sort_rows_by` order
+        let sorted;
+        let list = match (&ctx.sort_rows_by, detect_tabular(list, ctx)?) {
+            (Some(column), Some(table)) => {
+                match sort_table_rows(list, &table.fields, column, false)? {
+                    Some((rows, _)) => {
+                        sorted = rows;
+                        &sorted
+                    }
+                    None => list,
+                }
+            }
+            _ => list,
+        };
+        let Ok(other) = decoded.cast::<PyList>() else {
+            let what = format!("expected a list, got {}", describe(decoded)?);
+            push_difference(differences, path, what);
+            return Ok(());
+        };
+        if list.len() != other.len() {
+            let what = format!("expected {} items, got {}", list.len(), other.len());
+            push_difference(differences, path, what);
+            return Ok(());
+        }
+        let parent_len = path.len();
+        for (i, (item, other_item)) in list.iter().zip(other.iter()).enumerate() {
+            write!(path, "/{}", i).unwrap();
+            diff_value(&item, &other_item, path, differences, ctx)?;
+            path.truncate(parent_len);
+        }
+        return Ok(());
+    }
+    if !scalars_match(expected, decoded, ctx)? {
+        let what = format!(
+            "expected {}, got {}",
+            describe(expected)?,
+            describe(decoded)?
+        );
+        push_difference(differences, path, what);
+    }
+    Ok(())
+}
+
+fn push_difference(differences: &mut Vec<String>, path: &str, what: String) {
+    let path = if path.is_empty() { "(root)" } else { path };
+    differences.push(format!("{}: {}", path, what));
+}
+
+/// Whether the scalar `decoded` reads back as `expected`; always true for
+/// scalars the encoder converts to another type
+fn scalars_match(
+    expected: &Bound<'_, PyAny>,
+    decoded: &Bound<'_, PyAny>,
+    ctx: &SerializationContext,
+) -> PyResult<bool> {
+    let is_number = |obj: &Bound<'_, PyAny>| {
+        obj.is_exact_instance_of::<PyInt>() || obj.is_exact_instance_of::<PyFloat>()
+    };
+    if expected.is_none() {
+        Ok(decoded.is_none())
+    } else if expected.is_exact_instance_of::<PyBool>() {
+        Ok(decoded.is_exact_instance_of::<PyBool>() && expected.eq(decoded)?)
+    } else if expected.is_exact_instance_of::<PyString>() {
+        Ok(decoded.is_exact_instance_of::<PyString>() && expected.eq(decoded)?)
+    } else if expected.is_exact_instance_of::<PyInt>() {
+        Ok(is_number(decoded) && expected.eq(decoded)?)
+    } else if let Ok(value) = expected.cast_exact::<PyFloat>() {
+        if !is_number(decoded) {
+            return Ok(false);
+        }
+        let value = value.value();
+        let other: f64 = decoded.extract()?;
+        Ok(match ctx.float_precision {
+            _ if value.is_nan() => other.is_nan(),
+            Some(digits) if value.is_finite() => {
+                (value - other).abs() <= 10f64.powi(-(digits as i32))
+            }
+            _ => expected.eq(decoded)?,
+        })
+    } else {
+        Ok(true)
+    }
+}
+
+/// `repr()` of a scalar, or what kind of container `obj` is
+fn describe(obj: &Bound<'_, PyAny>) -> PyResult<String> {
+    Ok(if obj.is_instance_of::<PyDict>() {
+        "an object".to_string()
+    } else if let Ok(list) = obj.cast::<PyList>() {
+        format!("a list of {} items", list.len())
+    } else {
+        obj.repr()?.to_string()
+    })
+}
+
+/// Normalize `obj` and write its TOON form to `output`. `default`, when
+/// given, is called with each object of an unsupported type and its result
+/// is encoded in the object's place.
+fn encode(
+    py: Python,
+    obj: &Bound<'_, PyAny>,
+    output: &mut impl Output,
+    ctx: &SerializationContext,
+    default: Option<&Bound<'_, PyAny>>,
+) -> PyResult<()> {
+    let normalized = Normalizer::new(ctx, default).normalize(obj)?;
+    let obj = normalized.as_ref().unwrap_or(obj);
+    let delimiter = ctx.delimiter.resolve(obj)?;
+    if let Some(comment) = &ctx.header_comment {
+        write_header_comment(output, comment, ctx);
+    }
+    serialize_value(py, obj, output, 0, delimiter, true, ctx)
+}
+
+/// Write `comment` as `# ` lines ahead of the document, one per line of it,
+/// with `\uXXXX` escapes for non-ASCII characters under `ensure_ascii`
+fn write_header_comment(output: &mut impl Output, comment: &str, ctx: &SerializationContext) {
+    for line in comment.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        output.push('#');
+        if !line.is_empty() {
+            output.push(' ');
+            if ctx.ensure_ascii {
+                line.chars().for_each(|ch| match ch {
+                    _ if ch.is_ascii() => output.push(ch),
+                    _ => write_unicode_escape(ch, output),
+                });
+            } else {
+                output.push_str(line);
+            }
+        }
+        output.push_str(ctx.line_separator.as_str());
+    }
+}
+
+/// Placeholder for a repeated object in `share_by_identity` mode; written as
+/// `*` followed by the JSON-pointer path of the object's first occurrence
+#[pyclass(frozen)]
+struct AliasRef {
+    path: String,
+}
+
+/// Pre-pass that rewrites container-like objects the encoder does not walk
+/// directly into plain dicts and lists.
+///
+/// Dict views are materialized: `keys()` and `values()` become lists and
+/// `items()` becomes a list of `[key, value]` pairs. Dataclass and attrs
+/// instances become dicts of their fields in declaration order, pydantic
+/// models the dict their `model_dump()` returns. Any other
+/// `collections.abc.Mapping` becomes a dict of its `items()`, and remaining
+/// iterables (tuples, sets, ranges, generators, ...) become lists; strings,
+/// bytes and classes are never iterated. Non-string dict keys are converted
+/// the way `json.dumps` does (see `normalize_key`). numpy scalars become the
+/// native `int`, `float` or `bool` their `item()` returns, numpy arrays the
+/// lists (or, for structured arrays, dicts) of `numpy_array_value`, and
+/// pandas DataFrames a list of row dicts.
+///
+/// The walk also tracks container identity: a container reached again while
+/// it is still being walked is a cycle and raises `ValueError`, unless
+/// `share_by_identity` is set, in which case every repeat (cyclic or not)
+/// becomes an `AliasRef` to the first occurrence.
+///
+/// Objects with a `__toon__()` method are replaced by what it returns,
+/// before any of the above. With a `default` hook, objects the encoder has
+/// no form for (which it would otherwise write as `null`) are replaced by
+/// what the hook returns; without one, `strict` raises `TypeError` for them.
+struct Normalizer<'c> {
+    ctx: &'c SerializationContext,
+    default: Option<Py<PyAny>>,
+    /// Containers on the current path (by `id()`)
+    active: HashSet<usize>,
+    /// `share_by_identity`: path of every container already seen (by `id()`)
+    anchors: HashMap<usize, String>,
+    /// JSON-pointer path of the value being normalized (`""` is the root)
+    path: String,
+    /// `__toon__()` and `default()` replacements on the current path
+    replacements: usize,
+}
+
+/// Most `__toon__()` and `default()` replacements nested inside each other;
+/// a method returning a new object to replace each time never ends otherwise
+const MAX_REPLACEMENTS: usize = 200;
+
+impl<'c> Normalizer<'c> {
+    fn new(ctx: &'c SerializationContext, default: Option<&Bound<'_, PyAny>>) -> Self {
+        Self {
+            ctx,
+            default: default.map(|default| default.clone().unbind()),
+            active: HashSet::new(),
+            anchors: HashMap::new(),
+            path: String::new(),
+            replacements: 0,
+        }
+    }
+
+    /// Normalize `obj`, returning `None` when nothing under it needed
+    /// rewriting, so untouched subtrees are not copied.
+    fn normalize<'py>(&mut self, obj: &Bound<'py, PyAny>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        if has_toon_method(obj)? {
+            let value = obj.call_method0(pyo3::intern!(obj.py(), "__toon__"))?;
+            return self.normalize_replacement(obj, &value);
+        }
+        if let Some(item) = numpy_scalar_item(obj)? {
+            return Ok(Some(self.normalize(&item)?.unwrap_or(item)));
+        }
+        if obj.is_instance_of::<PyString>()
+            || obj.is_instance_of::<PyInt>()
+            || obj.is_instance_of::<PyFloat>()
+            || obj.is_none()
+        {
+            return Ok(None);
+        }
+        if !is_container(obj)? {
+            if is_supported_scalar(obj)? {
+                return Ok(None);
+            }
+            return match &self.default {
+                Some(default) => {
+                    let default = default.bind(obj.py()).clone();
+                    self.normalize_default(obj, &default)
+                }
+                None if self.ctx.strict => Err(not_serializable(obj)),
+                None => Ok(None),
+            };
+        }
+
+        let id = obj.as_ptr() as usize;
+        if self.ctx.share_by_identity {
+            if let Some(path) = self.anchors.get(&id) {
+                let alias = AliasRef { path: path.clone() };
+                return Ok(Some(Bound::new(obj.py(), alias)?.into_any()));
+            }
+            self.anchors.insert(id, self.path.clone());
+        } else if !self.active.insert(id) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Circular reference detected",
+            ));
+        }
+        let result = self.normalize_container(obj);
+        self.active.remove(&id);
+        result
+    }
+
+    /// Replace `obj`, of a type the encoder does not support, with the
+    /// normalized result of `default(obj)`
+    fn normalize_default<'py>(
+        &mut self,
+        obj: &Bound<'py, PyAny>,
+        default: &Bound<'py, PyAny>,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let value = default.call1((obj,))?;
+        if !is_container(&value)? && !is_supported_scalar(&value)? && !has_toon_method(&value)? {
+            // Calling the hook again could recurse without end; one wrapped
+            // in a container is caught by the `MAX_REPLACEMENTS` limit
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                "default() returned an unsupported object of type {}",
+                value.get_type().name()?
+            )));
+        }
+        self.normalize_replacement(obj, &value)
+    }
+
+    /// Normalize `value`, returned by `obj.__toon__()` or `default(obj)` to
+    /// be encoded in place of `obj`
+    fn normalize_replacement<'py>(
+        &mut self,
+        obj: &Bound<'py, PyAny>,
+        value: &Bound<'py, PyAny>,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        // `obj` showing up again inside its own replacement is a cycle
+        let id = obj.as_ptr() as usize;
+        if !self.active.insert(id) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Circular reference detected",
+            ));
+        }
+        if self.replacements == MAX_REPLACEMENTS {
+            self.active.remove(&id);
+            return Err(PyErr::new::<pyo3::exceptions::PyRecursionError, _>(
+                format!(
+                    "More than {} nested __toon__() or default() replacements",
+                    MAX_REPLACEMENTS
+                ),
+            ));
+        }
+        self.replacements += 1;
+        let result = self.normalize(value);
+        self.replacements -= 1;
+        self.active.remove(&id);
+        Ok(Some(result?.unwrap_or_else(|| value.clone())))
+    }
+
+    /// Normalize `value` found at `segment` below the current path
+    fn normalize_child<'py>(
+        &mut self,
+        segment: impl std::fmt::Display,
+        value: &Bound<'py, PyAny>,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        if !self.ctx.share_by_identity {
+            // The path only names alias targets; skip formatting segments
+            return self.normalize(value);
+        }
+        let parent_len = self.path.len();
+        self.path.push('/');
+        push_pointer_segment(&mut self.path, &segment.to_string());
+        let result = self.normalize(value);
+        self.path.truncate(parent_len);
+        result
+    }
+
+    fn normalize_container<'py>(
+        &mut self,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let py = obj.py();
+        if let Ok(dict) = obj.cast::<PyDict>() {
+            let mut rewritten: Option<Bound<'py, PyDict>> = None;
+            for (i, (key, value)) in dict.iter().enumerate() {
+                let new_key = normalize_key(&key, self.ctx)?;
+                let new_value = match &new_key {
+                    Some(None) => None,
+                    Some(Some(new_key)) => self.normalize_child(new_key, &value)?,
+                    None => self.normalize_child(&key, &value)?,
+                };
+                if rewritten.is_none() && (new_key.is_some() || new_value.is_some()) {
+                    // Rebuild from scratch so converted keys keep their position
+                    let target = PyDict::new(py);
+                    for (key, value) in dict.iter().take(i) {
+                        target.set_item(key, value)?;
+                    }
+                    rewritten = Some(target);
+                }
+                if let Some(target) = &rewritten {
+                    let value = new_value.unwrap_or(value);
+                    match new_key {
+                        Some(None) => {}
+                        Some(Some(new_key)) => target.set_item(new_key, value)?,
+                        None => target.set_item(key, value)?,
+                    }
+                }
+            }
+            return Ok(rewritten.map(Bound::into_any));
+        }
+        if let Ok(list) = obj.cast::<PyList>() {
+            let mut rewritten: Option<Bound<'py, PyList>> = None;
+            for (i, item) in list.iter().enumerate() {
+                if let Some(new_item) = self.normalize_child(i, &item)? {
+                    let target = match &rewritten {
+                        Some(target) => target,
+                        None => rewritten.insert(PyList::new(py, list.iter())?),
+                    };
+                    target.set_item(i, new_item)?;
+                }
+            }
+            return Ok(rewritten.map(Bound::into_any));
+        }
+        if let Some(fields) = declared_fields(obj)? {
+            let dict = PyDict::new(py);
+            for field in fields.try_iter()? {
+                let name = field?.getattr("name")?;
+                let name = name.cast::<PyString>()?;
+                let value = obj.getattr(name)?;
+                let value = self
+                    .normalize_child(name.to_str()?, &value)?
+                    .unwrap_or(value);
+                dict.set_item(name, value)?;
+            }
+            return Ok(Some(dict.into_any()));
+        }
+        if is_pydantic_model(obj)? {
+            let dict = obj.call_method0(pyo3::intern!(py, "model_dump"))?;
+            return Ok(Some(self.normalize(&dict)?.unwrap_or(dict)));
+        }
+        if let Some(records) = dataframe_records(obj)? {
+            return Ok(Some(self.normalize(&records)?.unwrap_or(records)));
+        }
+        if let Some(value) = numpy_array_value(obj)? {
+            // Fresh plain lists and dicts: nothing else refers to them
+            return Ok(Some(self.normalize(&value)?.unwrap_or(value)));
+        }
+        if obj.is_instance_of::<PyDictItems>() {
+            let pairs = PyList::empty(py);
+            for (i, item) in obj.try_iter()?.enumerate() {
+                let (key, value): (Bound<'py, PyAny>, Bound<'py, PyAny>) = item?.extract()?;
+                let value = self
+                    .normalize_child(format_args!("{}/1", i), &value)?
+                    .unwrap_or(value);
+                pairs.append(PyList::new(py, [key, value])?)?;
+            }
+            return Ok(Some(pairs.into_any()));
+        }
+        if is_instance_of_class(obj, &MAPPING_TYPE, "collections.abc", "Mapping")? {
+            let dict = PyDict::new(py);
+            for item in obj.call_method0("items")?.try_iter()? {
+                let (key, value): (Bound<'py, PyAny>, Bound<'py, PyAny>) = item?.extract()?;
+                let key = match normalize_key(&key, self.ctx)? {
+                    Some(None) => continue,
+                    Some(Some(new_key)) => new_key,
+                    None => key,
+                };
+                let value = self.normalize_child(&key, &value)?.unwrap_or(value);
+                dict.set_item(key, value)?;
+            }
+            return Ok(Some(dict.into_any()));
+        }
+        // Dict keys/values views and any other iterable
+        let list = PyList::empty(py);
+        for (i, item) in obj.try_iter()?.enumerate() {
+            let item = item?;
+            list.append(self.normalize_child(i, &item)?.unwrap_or(item))?;
+        }
+        Ok(Some(list.into_any()))
+    }
+}
+
+/// Whether the normalizer walks `obj`: dicts, lists, dataclass, attrs and
+/// pydantic instances, mappings and other iterables, but not strings, bytes
+/// or classes
+fn is_container(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    if obj.is_instance_of::<PyDict>() || obj.is_instance_of::<PyList>() {
+        return Ok(true);
+    }
+    // Networks iterate over their addresses but are written as one string
+    if obj.is_instance_of::<PyBytes>()
+        || obj.is_instance_of::<PyByteArray>()
+        || obj.is_instance_of::<PyType>()
+        || is_ip_address(obj)?
+    {
+        return Ok(false);
+    }
+    Ok(is_dataclass_instance(obj)?
+        || is_attrs_instance(obj)?
+        || is_pydantic_model(obj)?
+        || is_instance_of_class(obj, &MAPPING_TYPE, "collections.abc", "Mapping")?
+        || obj.try_iter().is_ok())
+}
+
+/// Whether `obj` chooses its own encoding with a `__toon__()` method, whose
+/// result is encoded in its place. Checked before any built-in handling.
+fn has_toon_method(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    if obj.is_exact_instance_of::<PyString>()
+        || obj.is_exact_instance_of::<PyInt>()
+        || obj.is_exact_instance_of::<PyFloat>()
+        || obj.is_instance_of::<PyBool>()
+        || obj.is_exact_instance_of::<PyDict>()
+        || obj.is_exact_instance_of::<PyList>()
+        || obj.is_none()
+        || obj.is_instance_of::<PyType>()
+    {
+        return Ok(false);
+    }
+    obj.get_type().hasattr(pyo3::intern!(obj.py(), "__toon__"))
+}
+
+/// The native Python value of a numpy scalar (`np.int64`, `np.float32`,
+/// `np.bool_`, ...), from its `item()`. numpy is never imported: any
+/// zero-dimensional object with a `dtype` and an `item()` method qualifies.
+fn numpy_scalar_item<'py>(obj: &Bound<'py, PyAny>) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let py = obj.py();
+    if obj.is_exact_instance_of::<PyString>()
+        || obj.is_exact_instance_of::<PyInt>()
+        || obj.is_exact_instance_of::<PyFloat>()
+        || obj.is_instance_of::<PyBool>()
+        || obj.is_instance_of::<PyDict>()
+        || obj.is_instance_of::<PyList>()
+        || obj.is_none()
+        || !obj.hasattr(pyo3::intern!(py, "dtype"))?
+        || !obj.hasattr(pyo3::intern!(py, "item"))?
+    {
+        return Ok(None);
+    }
+    let is_scalar = obj
+        .getattr(pyo3::intern!(py, "ndim"))
+        .and_then(|ndim| ndim.extract::<usize>())
+        .is_ok_and(|ndim| ndim == 0);
+    if !is_scalar {
+        return Ok(None);
+    }
+    let item = obj.call_method0(pyo3::intern!(py, "item"))?;
+    // An `item()` returning its own type would be converted forever
+    if item.get_type().is(obj.get_type()) {
+        return Ok(None);
+    }
+    Ok(Some(item))
+}
+
+/// The rows of a pandas DataFrame as a list of dicts in column order, from
+/// `to_dict(orient="records")`, so the frame encodes as a table. pandas is
+/// never imported: anything with `columns` and `to_dict()` qualifies.
+fn dataframe_records<'py>(obj: &Bound<'py, PyAny>) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let py = obj.py();
+    if !obj.hasattr(pyo3::intern!(py, "columns"))? || !obj.hasattr(pyo3::intern!(py, "to_dict"))? {
+        return Ok(None);
+    }
+    let kwargs = PyDict::new(py);
+    kwargs.set_item(pyo3::intern!(py, "orient"), pyo3::intern!(py, "records"))?;
+    let records = obj.call_method(pyo3::intern!(py, "to_dict"), (), Some(&kwargs))?;
+    Ok(Some(records))
+}
+
+/// The contents of a numpy array as plain Python values: nested lists from
+/// `tolist()`, or for a 1-D structured array one dict per record, keyed by
+/// the field names, so it encodes as a table. Like `numpy_scalar_item`, this
+/// duck-types on `__array__`, `dtype` and `tolist()` instead of importing numpy.
+fn numpy_array_value<'py>(obj: &Bound<'py, PyAny>) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let py = obj.py();
+    if !obj.hasattr(pyo3::intern!(py, "__array__"))?
+        || !obj.hasattr(pyo3::intern!(py, "dtype"))?
+        || !obj.hasattr(pyo3::intern!(py, "tolist"))?
+    {
+        return Ok(None);
+    }
+    let value = obj.call_method0(pyo3::intern!(py, "tolist"))?;
+    let names = obj
+        .getattr(pyo3::intern!(py, "dtype"))?
+        .getattr(pyo3::intern!(py, "names"))
+        .ok()
+        .filter(|names| !names.is_none());
+    let (Some(names), Ok(records)) = (names, value.cast::<PyList>()) else {
+        return Ok(Some(value));
+    };
+    let names = names.extract::<Vec<Bound<'py, PyString>>>()?;
+    let rows = PyList::empty(py);
+    for record in records.iter() {
+        let Ok(record) = record.cast::<PyTuple>() else {
+            // Multi-dimensional structured arrays keep their nesting
+            return Ok(Some(value));
+        };
+        let row = PyDict::new(py);
+        for (name, field) in names.iter().zip(record.iter()) {
+            row.set_item(name, field)?;
+        }
+        rows.append(row)?;
+    }
+    Ok(Some(rows.into_any()))
+}
+
+/// The `TypeError` of `strict` mode for `obj`, of a type the encoder has no
+/// form for
+pub fn not_serializable(obj: &Bound<'_, PyAny>) -> PyErr {
+    let name = obj
+        .get_type()
+        .name()
+        .map_or_else(|_| "?".to_string(), |name| name.to_string());
+    PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+        "Object of type {} is not TOON serializable",
+        name
+    ))
+}
+
+/// Whether `serialize_value` has a form for the non-container `obj`, rather
+/// than writing it as `null` for lack of one
+fn is_supported_scalar(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    Ok(obj.is_none()
+        || obj.extract::<bool>().is_ok()
+        || obj.extract::<i64>().is_ok()
+        || obj.extract::<f64>().is_ok()
+        || obj.extract::<String>().is_ok()
+        || obj.is_instance_of::<PyBytes>()
+        || obj.is_instance_of::<PyByteArray>()
+        || obj.is_instance_of::<PyDate>()
+        || obj.is_instance_of::<PyTime>()
+        || obj.is_instance_of::<PyDelta>()
+        || is_instance_of_class(obj, &ENUM_TYPE, "enum", "Enum")?
+        || is_instance_of_class(obj, &UUID_TYPE, "uuid", "UUID")?
+        || is_instance_of_class(obj, &PATHLIKE_TYPE, "os", "PathLike")?
+        || is_ip_address(obj)?
+        || obj.is_instance_of::<PyComplex>())
+}
+
+/// Append a JSON-pointer path segment, percent-encoding everything but
+/// letters, digits, `_`, `.` and `-` so an alias never needs quoting
+pub fn push_pointer_segment(path: &mut String, segment: &str) {
+    for ch in segment.chars() {
+        if ch.is_alphanumeric() || matches!(ch, '_' | '.' | '-') {
+            path.push(ch);
+        } else {
+            let mut buf = [0u8; 4];
+            for byte in ch.encode_utf8(&mut buf).bytes() {
+                write!(path, "%{:02X}", byte).unwrap();
+            }
+        }
+    }
+}
+
+/// `dataclasses.is_dataclass(obj)` restricted to instances (not the class itself)
+fn is_dataclass_instance(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    Ok(!obj.is_instance_of::<PyType>() && obj.get_type().hasattr("__dataclass_fields__")?)
+}
+
+/// Instance of an attrs class (`@attrs.define`, `@attr.s`, ...); the class
+/// itself is not
+fn is_attrs_instance(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    Ok(!obj.is_instance_of::<PyType>() && obj.get_type().hasattr("__attrs_attrs__")?)
+}
+
+/// Instance of a pydantic v2 model, recognized by `model_dump()` so that
+/// pydantic is never imported
+fn is_pydantic_model(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    Ok(!obj.is_instance_of::<PyType>() && obj.get_type().hasattr("model_dump")?)
+}
+
+/// The fields (each with a `.name`) of a dataclass or attrs instance, in
+/// declaration order
+fn declared_fields<'py>(obj: &Bound<'py, PyAny>) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let py = obj.py();
+    if is_dataclass_instance(obj)? {
+        let fields = DATACLASS_FIELDS.import(py, "dataclasses", "fields")?;
+        return Ok(Some(fields.call1((obj,))?));
+    }
+    if is_attrs_instance(obj)? {
+        let fields = obj
+            .get_type()
+            .getattr(pyo3::intern!(py, "__attrs_attrs__"))?;
+        return Ok(Some(fields));
+    }
+    Ok(None)
+}
+
+/// Convert a non-string dict key to its string form, as `json.dumps` does:
+/// `True` → `"true"`, `None` → `"null"`, `1` → `"1"`, `1.5` → `"1.5"`.
+/// With `tuple_key_sep`, tuple keys join their converted items with it.
+///
+/// Returns `None` for string keys (nothing to do) and `Some(None)` for keys
+/// of other types that `skipkeys` drops; without `skipkeys` those raise
+/// `TypeError`.
+fn normalize_key<'py>(
+    key: &Bound<'py, PyAny>,
+    ctx: &SerializationContext,
+) -> PyResult<Option<Option<Bound<'py, PyAny>>>> {
+    let py = key.py();
+    if key.is_instance_of::<PyString>() {
+        return Ok(None);
+    }
+    let text = if let Some(text) = scalar_key_text(key)? {
+        text
+    } else if let (Ok(tuple), Some(sep)) = (key.cast::<PyTuple>(), &ctx.tuple_key_sep) {
+        // Items of other types, such as nested tuples, use str()
+        tuple
+            .iter()
+            .map(|item| match scalar_key_text(&item)? {
+                Some(text) => Ok(text),
+                None => Ok(item.str()?.to_string()),
+            })
+            .collect::<PyResult<Vec<_>>>()?
+            .join(sep)
+    } else if ctx.skipkeys {
+        return Ok(Some(None));
+    } else {
+        return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+            "keys must be str, int, float, bool or None, not {}",
+            key.get_type().name()?
+        )));
+    };
+    Ok(Some(Some(PyString::new(py, &text).into_any())))
+}
+
+/// String form of a str, int, float, bool or None key, or `None` for other
+/// types
+fn scalar_key_text(key: &Bound<'_, PyAny>) -> PyResult<Option<String>> {
+    let py = key.py();
+    let text = if let Ok(s) = key.cast::<PyString>() {
+        s.to_string()
+    } else if key.is_none() {
+        "null".to_string()
+    } else if let Ok(b) = key.cast::<PyBool>() {
+        (if b.is_true() { "true" } else { "false" }).to_string()
+    } else if key.is_instance_of::<PyInt>() {
+        // int subclasses (IntEnum) use the plain integer, like json.dumps
+        py.get_type::<PyInt>().call1((key,))?.str()?.to_string()
+    } else if let Ok(float) = key.cast::<PyFloat>() {
+        let f = float.value();
+        if f.is_nan() {
+            "NaN".to_string()
+        } else if f.is_infinite() {
+            (if f > 0.0 { "Infinity" } else { "-Infinity" }).to_string()
+        } else {
+            PyFloat::new(py, f).repr()?.to_string()
+        }
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(text))
+}
+
 /// Serialize a value at a given depth with specified delimiter context
 pub fn serialize_value(
     py: Python,
     obj: &Bound<'_, PyAny>,
-    output: &mut String,
+    output: &mut impl Output,
     depth: usize,
     delimiter: char,
     is_root: bool,
-    indent_size: usize,
     ctx: &SerializationContext,
 ) -> PyResult<()> {
     if obj.is_none() {
-        output.push_str("null");
+        output.push_str(&ctx.literals.null);
+    } else if let Ok(alias) = obj.cast::<AliasRef>() {
+        output.push('*');
+        output.push_str(&alias.get().path);
+    } else if is_instance_of_class(obj, &ENUM_TYPE, "enum", "Enum")? {
+        // Checked before the primitives so IntEnum/StrEnum honor `enum_as` too
+        let member = obj.getattr(ctx.enum_as.attr())?;
+        serialize_value(py, &member, output, depth, delimiter, is_root, ctx)?;
+    } else if ctx.rich_numbers
+        && is_instance_of_class(obj, &FRACTION_TYPE, "fractions", "Fraction")?
+    {
+        // Checked before `f64`, which a Fraction converts to inexactly
+        ctx.write_string(obj.str()?.to_str()?, output, delimiter);
     } else if let Ok(b) = obj.extract::<bool>() {
-        output.push_str(if b { "true" } else { "false" });
+        output.push_str(ctx.literals.bool(b));
     } else if let Ok(i) = obj.extract::<i64>() {
-        write!(output, "{}", i).unwrap();
+        output.push_str(itoa::Buffer::new().format(i));
     } else if let Ok(f) = obj.extract::<f64>() {
-        // TOON v3.0: normalize -0 to 0, no exponential notation
-        if f == 0.0 {
-            output.push('0');
-        } else if f.is_finite() {
-            // Format without exponential notation
-            write!(output, "{}", f).unwrap();
-        } else {
-            // NaN, Infinity → null (per spec Section 3)
-            output.push_str("null");
+        // Ints too large for i64 land here as well; they stay plain integers
+        let is_float = !obj.is_instance_of::<PyInt>();
+        if !write_float(output, f, is_float, ctx) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Out of range float value: {}",
+                obj.repr()?
+            )));
         }
     } else if let Ok(s) = obj.extract::<String>() {
-        serialize_string(&s, output, delimiter);
-    } else if let Ok(list) = obj.cast::<PyList>() {
-        serialize_array(
-            py,
-            &list,
+        write_string_value(&s, output, delimiter, ctx);
+    } else if let Ok(bytes) = obj.cast::<PyBytes>() {
+        // Binary data is emitted as text; decoding does not reverse this
+        ctx.write_string(
+            &ctx.bytes_encoding.encode(bytes.as_bytes()),
             output,
-            depth,
             delimiter,
-            is_root,
-            indent_size,
-            ctx,
-        )?;
-    } else if let Ok(dict) = obj.cast::<PyDict>() {
-        serialize_object(
-            py,
-            &dict,
+        );
+    } else if let Ok(bytes) = obj.cast::<PyByteArray>() {
+        ctx.write_string(
+            &ctx.bytes_encoding.encode(&bytes.to_vec()),
             output,
-            depth,
             delimiter,
-            is_root,
-            indent_size,
-            ctx,
-        )?;
+        );
+    } else if let Ok(list) = obj.cast::<PyList>() {
+        serialize_array(py, list, output, depth, delimiter, is_root, ctx)?;
+    } else if let Ok(dict) = obj.cast::<PyDict>() {
+        serialize_object(dict, output, depth, delimiter, is_root, ctx)?;
     } else if let Ok(dt) = obj.cast::<PyDateTime>() {
         let iso_str: String = dt.call_method0("isoformat")?.extract()?;
-        serialize_string(&iso_str, output, delimiter);
+        ctx.write_string(&iso_str, output, delimiter);
     } else if let Ok(date) = obj.cast::<PyDate>() {
         let iso_str: String = date.call_method0("isoformat")?.extract()?;
-        serialize_string(&iso_str, output, delimiter);
+        ctx.write_string(&iso_str, output, delimiter);
     } else if let Ok(time) = obj.cast::<PyTime>() {
         let iso_str: String = time.call_method0("isoformat")?.extract()?;
-        serialize_string(&iso_str, output, delimiter);
+        ctx.write_string(&iso_str, output, delimiter);
+    } else if obj.is_instance_of::<PyDelta>() {
+        match ctx.timedelta_as {
+            TimedeltaAs::Seconds => {
+                let seconds: f64 = obj.call_method0("total_seconds")?.extract()?;
+                write_float(output, seconds, true, ctx);
+            }
+            TimedeltaAs::Iso => {
+                let duration = iso_duration(
+                    obj.getattr("days")?.extract()?,
+                    obj.getattr("seconds")?.extract()?,
+                    obj.getattr("microseconds")?.extract()?,
+                );
+                ctx.write_string(&duration, output, delimiter);
+            }
+        }
+    } else if is_instance_of_class(obj, &UUID_TYPE, "uuid", "UUID")? {
+        // Canonical hyphenated form; only a leading hyphen would force quoting
+        let uuid_str: String = obj.str()?.extract()?;
+        ctx.write_string(&uuid_str, output, delimiter);
+    } else if is_instance_of_class(obj, &PATHLIKE_TYPE, "os", "PathLike")? {
+        // `os.fsdecode` applies `os.fspath` and decodes bytes paths, so a
+        // Windows path keeps its backslashes (escaped when quoted)
+        let fsdecode = FSDECODE.import(obj.py(), "os", "fsdecode")?;
+        let path: String = fsdecode.call1((obj,))?.extract()?;
+        write_string_value(&path, output, delimiter, ctx);
+    } else if is_ip_address(obj)? {
+        // `192.168.0.1`, `10.0.0.0/8`; IPv6 forms contain `:` and get quoted
+        let text: String = obj.str()?.extract()?;
+        write_string_value(&text, output, delimiter, ctx);
+    } else if obj.is_instance_of::<PyComplex>() {
+        // No TOON number form; `str()` gives `(1+2j)`, which `complex()` parses
+        ctx.write_string(obj.str()?.to_str()?, output, delimiter);
     } else {
         // Unknown type → null (per spec Section 3)
-        output.push_str("null");
+        output.push_str(&ctx.literals.null);
     }
     Ok(())
 }
 
-/// Serialize a string with proper quoting and escaping per TOON v3.0 Section 7
-pub fn serialize_string(s: &str, output: &mut String, delimiter: char) {
-    if needs_quoting(s, delimiter) {
-        output.push('"');
-        for ch in s.chars() {
-            match ch {
-                '\\' => output.push_str("\\\\"),
-                '"' => output.push_str("\\\""),
-                '\n' => output.push_str("\\n"),
-                '\r' => output.push_str("\\r"),
-                '\t' => output.push_str("\\t"),
-                _ => output.push(ch),
+/// The ISO-8601 duration (`P1DT2H30M`, `PT0.5S`, `-PT1S`) of a timedelta
+/// with the given normalized fields
+fn iso_duration(days: i64, seconds: i64, microseconds: i64) -> String {
+    let total = (days as i128 * 86_400 + seconds as i128) * 1_000_000 + microseconds as i128;
+    let mut duration = String::from(if total < 0 { "-P" } else { "P" });
+    let total = total.unsigned_abs();
+    let (days, rest) = (total / 86_400_000_000, total % 86_400_000_000);
+    let (hours, rest) = (rest / 3_600_000_000, rest % 3_600_000_000);
+    let (minutes, rest) = (rest / 60_000_000, rest % 60_000_000);
+    let (seconds, micros) = (rest / 1_000_000, rest % 1_000_000);
+    if days > 0 {
+        write!(duration, "{}D", days).unwrap();
+    }
+    if rest > 0 || hours > 0 || minutes > 0 || days == 0 {
+        duration.push('T');
+        if hours > 0 {
+            write!(duration, "{}H", hours).unwrap();
+        }
+        if minutes > 0 {
+            write!(duration, "{}M", minutes).unwrap();
+        }
+        if rest > 0 || (hours == 0 && minutes == 0) {
+            write!(duration, "{}", seconds).unwrap();
+            if micros > 0 {
+                let fraction = format!("{:06}", micros);
+                write!(duration, ".{}", fraction.trim_end_matches('0')).unwrap();
             }
+            duration.push('S');
         }
-        output.push('"');
-    } else {
-        output.push_str(s);
     }
+    duration
 }
 
-/// Check if a string needs quoting per TOON v3.0 Section 7.2
-fn needs_quoting(s: &str, delimiter: char) -> bool {
-    if s.is_empty() {
-        return true;
+/// Write a float value per the context's float options. Returns `false`,
+/// writing nothing, for NaN/Infinity without a token when `allow_nan` is off.
+fn write_float(
+    output: &mut impl Output,
+    f: f64,
+    is_float: bool,
+    ctx: &SerializationContext,
+) -> bool {
+    let force_point = ctx.preserve_float && is_float;
+    // TOON v3.0: normalize -0 to 0, no exponential notation unless
+    // allow_exponent opts in
+    if f == 0.0 {
+        output.push_str(if force_point { "0.0" } else { "0" });
+    } else if ctx.allow_exponent && is_float && f.is_finite() && !(1e-4..1e16).contains(&f.abs()) {
+        write_float_exponent(output, f, ctx.float_precision, ctx.strip_trailing_zeros);
+    } else if f.is_finite() {
+        match ctx.float_precision {
+            Some(digits) => {
+                write_float_with_precision(output, f, digits, ctx.strip_trailing_zeros, force_point)
+            }
+            None => write_shortest_float(output, f, force_point),
+        }
+    } else if let Some(token) = ctx.float_tokens.token(f) {
+        ctx.write_quoted(token, output);
+    } else if ctx.allow_nan {
+        // NaN, Infinity → null (per spec Section 3)
+        output.push_str(&ctx.literals.null);
+    } else {
+        return false;
     }
+    true
+}
 
-    // Check for leading/trailing whitespace
-    if s.starts_with(|c: char| c.is_whitespace()) || s.ends_with(|c: char| c.is_whitespace()) {
-        return true;
+/// Write a `str` value, quoted when needed
+fn write_string_value(
+    s: &str,
+    output: &mut impl Output,
+    delimiter: char,
+    ctx: &SerializationContext,
+) {
+    // With share_by_identity, keep literal strings distinguishable from aliases
+    if ctx.quote_strings
+        || ctx.literals.collides(s)
+        || (ctx.share_by_identity && s.starts_with('*'))
+    {
+        ctx.write_quoted(s, output);
+    } else {
+        ctx.write_string(s, output, delimiter);
     }
+}
 
-    // Check for reserved keywords
-    if s == "true" || s == "false" || s == "null" {
-        return true;
+/// Write a finite, non-zero float in exponent form (`1e-10`, `1.5e+20`), with
+/// the shortest round-trip mantissa or `digits` significant digits
+fn write_float_exponent(
+    output: &mut impl Output,
+    f: f64,
+    digits: Option<usize>,
+    strip_trailing_zeros: bool,
+) {
+    let sci = match digits {
+        Some(digits) => format!("{:.*e}", digits - 1, f),
+        None => format!("{:e}", f),
+    };
+    let (mantissa, exponent) = sci.split_once('e').unwrap();
+    let mantissa = if strip_trailing_zeros && mantissa.contains('.') {
+        mantissa.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        mantissa
+    };
+    output.push_str(mantissa);
+    output.push('e');
+    if !exponent.starts_with('-') {
+        output.push('+');
     }
+    output.push_str(exponent);
+}
 
-    // Check if numeric-like
-    if is_numeric_like(s) {
-        return true;
+/// Write a finite, non-zero float rounded to `digits` significant digits in
+/// plain decimal notation. Trailing fractional zeros only exist because of the
+/// rounding, so `strip_trailing_zeros` is meaningless without a precision.
+/// `force_point` appends `.0` when the result has no fractional part.
+fn write_float_with_precision(
+    output: &mut impl Output,
+    f: f64,
+    digits: usize,
+    strip_trailing_zeros: bool,
+    force_point: bool,
+) {
+    // `{:e}` rounds correctly; rebuild the decimal form from its mantissa digits
+    let sci = format!("{:.*e}", digits.saturating_sub(1), f.abs());
+    let (mantissa, exponent) = sci.split_once('e').unwrap();
+    let exponent: i64 = exponent.parse().unwrap();
+    let mantissa_digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let point = exponent + 1;
+
+    let mut number = String::new();
+    if point <= 0 {
+        number.push_str("0.");
+        number.extend(std::iter::repeat_n('0', point.unsigned_abs() as usize));
+        number.push_str(&mantissa_digits);
+    } else if point as usize >= mantissa_digits.len() {
+        number.push_str(&mantissa_digits);
+        number.extend(std::iter::repeat_n(
+            '0',
+            point as usize - mantissa_digits.len(),
+        ));
+    } else {
+        let (int_part, frac_part) = mantissa_digits.split_at(point as usize);
+        number.push_str(int_part);
+        number.push('.');
+        number.push_str(frac_part);
     }
-
-    // Check for special characters
-    for ch in s.chars() {
-        match ch {
-            ':' | '"' | '\\' | '[' | ']' | '{' | '}' | '\n' | '\r' | '\t' => return true,
-            _ if ch == delimiter => return true,
-            _ => {}
-        }
+    if strip_trailing_zeros && number.contains('.') {
+        number.truncate(number.trim_end_matches('0').trim_end_matches('.').len());
     }
-
-    // Check if starts with hyphen
-    if s.starts_with('-') {
-        return true;
+    // Rounding just below f64::MAX can round up past it; keep the value finite
+    if exponent >= 308 && !number.parse::<f64>().is_ok_and(f64::is_finite) {
+        number = format!("{}", f.abs());
     }
-
-    false
-}
-
-/// Check if string looks numeric per TOON v3.0 Section 7.2
-fn is_numeric_like(s: &str) -> bool {
-    // Matches: -?\d+(\.\d+)?(e[+-]?\d+)? or 0\d+
-    if s.chars().next().unwrap_or(' ').is_ascii_digit() {
-        // Check for leading zero with more digits (e.g., "05")
-        if s.starts_with('0') && s.len() > 1 && s.chars().nth(1).unwrap().is_ascii_digit() {
-            return true;
-        }
+    if force_point && !number.contains('.') {
+        number.push_str(".0");
     }
 
-    // Try to parse as number
-    s.parse::<f64>().is_ok()
-}
-
-/// Write array header with delimiter per TOON v3.0 Section 6
-pub fn write_array_header(output: &mut String, len: usize, delimiter: char, inline: bool) {
-    write!(output, "[{}", len).unwrap();
-    // Only include delimiter in header if it's not comma (default)
-    if delimiter != ',' {
-        output.push(delimiter);
-    }
-    output.push_str("]:");
-    // Add space for inline arrays with elements
-    if inline && len > 0 {
-        output.push(' ');
+    if f.is_sign_negative() {
+        output.push('-');
     }
+    output.push_str(&number);
 }
 
-/// Write tabular array header with delimiter per TOON v3.0 Section 9.3
-pub fn write_tabular_header(output: &mut String, len: usize, delimiter: char, fields: &[String]) {
-    write!(output, "[{}", len).unwrap();
+/// Write tabular array header with delimiter per TOON v3.0 Section 9.3.
+/// With `typed_tabular`, fields whose values share a `ColumnType` are
+/// annotated with it, read from the extracted `cells` when there are any.
+fn write_tabular_header(
+    output: &mut impl Output,
+    list: &Bound<'_, PyList>,
+    delimiter: char,
+    fields: &[String],
+    cells: Option<&[Cell]>,
+    ctx: &SerializationContext,
+) -> PyResult<()> {
+    write!(output, "[{}", list.len()).unwrap();
     // Only include delimiter in header if it's not comma (default)
     if delimiter != ',' {
         output.push(delimiter);
@@ -213,228 +1831,255 @@ pub fn write_tabular_header(output: &mut String, len: usize, delimiter: char, fi
         if i > 0 {
             output.push(delimiter);
         }
-        serialize_key(field, output);
+        ctx.write_key(field, output);
+        let column_type = match (ctx.typed_tabular, cells) {
+            (false, _) => None,
+            (true, Some(cells)) => cell_column_type(cells, fields.len(), i),
+            (true, None) => column_type(list, field)?,
+        };
+        if let Some(column_type) = column_type {
+            output.push(':');
+            output.push_str(column_type.annotation());
+        }
     }
     output.push_str("}:");
+    Ok(())
+}
+
+/// The `ColumnType` shared by every non-null value of `field`, if any;
+/// null cells (and cells missing under `tabular="fill"`) fit any type
+fn column_type(list: &Bound<'_, PyList>, field: &str) -> PyResult<Option<ColumnType>> {
+    let mut shared = None;
+    for item in list.iter() {
+        let dict = item.cast::<PyDict>()?;
+        let Some(value) = dict.get_item(field)? else {
+            continue;
+        };
+        if value.is_none() {
+            continue;
+        }
+        match (ColumnType::of(&value), shared) {
+            (None, _) => return Ok(None),
+            (Some(column_type), Some(seen)) if column_type != seen => return Ok(None),
+            (column_type, _) => shared = column_type,
+        }
+    }
+    Ok(shared)
+}
+
+/// `column_type` over column `index` of extracted cells, `width` per row
+fn cell_column_type(cells: &[Cell], width: usize, index: usize) -> Option<ColumnType> {
+    let mut shared = None;
+    for cell in cells.iter().skip(index).step_by(width) {
+        let column_type = match cell {
+            Cell::Null => continue,
+            Cell::Int(_) | Cell::Float(_, false) => ColumnType::Int,
+            Cell::Float(_, true) => ColumnType::Float,
+            Cell::Bool(_) | Cell::Str(_) => return None,
+        };
+        match shared {
+            Some(seen) if seen != column_type => return None,
+            _ => shared = Some(column_type),
+        }
+    }
+    shared
+}
+
+/// Where an object sits below the nearest object that does not hang off a
+/// key (the root, or a list item), for key folding collision checks: a
+/// folded key expands to the same path as a dotted literal key of an
+/// enclosing object
+#[derive(Default)]
+struct FoldScope {
+    /// Dot-joined keys leading to the object
+    path: String,
+    /// Full paths of the dotted literal keys of enclosing objects
+    dotted_keys: HashSet<String>,
+}
+
+impl FoldScope {
+    /// `key` joined onto this scope's path
+    fn join(&self, key: &str) -> String {
+        if self.path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", self.path, key)
+        }
+    }
+
+    /// The scope of the object under `key`, among `sibling_keys`
+    fn child(&self, key: &str, sibling_keys: &HashSet<String>) -> Self {
+        let mut dotted_keys = self.dotted_keys.clone();
+        dotted_keys.extend(
+            sibling_keys
+                .iter()
+                .filter(|sibling| sibling.contains('.'))
+                .map(|sibling| self.join(sibling)),
+        );
+        Self {
+            path: self.join(key),
+            dotted_keys,
+        }
+    }
 }
 
 /// Serialize an object (dict) per TOON v3.0 Section 8
 pub fn serialize_object(
-    py: Python,
     dict: &Bound<'_, PyDict>,
-    output: &mut String,
+    output: &mut impl Output,
     depth: usize,
     delimiter: char,
     is_root: bool,
-    indent_size: usize,
     ctx: &SerializationContext,
 ) -> PyResult<()> {
-    let items: Vec<_> = dict.items().iter().collect();
+    serialize_object_in(
+        dict,
+        output,
+        depth,
+        delimiter,
+        is_root,
+        ctx,
+        &FoldScope::default(),
+    )
+}
+
+/// Serialize an object found at `scope`
+fn serialize_object_in(
+    dict: &Bound<'_, PyDict>,
+    output: &mut impl Output,
+    depth: usize,
+    delimiter: char,
+    is_root: bool,
+    ctx: &SerializationContext,
+    scope: &FoldScope,
+) -> PyResult<()> {
+    let py = dict.py();
+    let items = ctx.ordered_items(dict)?;
 
     if items.is_empty() {
         // Empty object: no output at root, empty line with key elsewhere
         return Ok(());
     }
 
-    // Collect all top-level keys for collision detection
-    let all_keys: HashSet<String> = items
-        .iter()
-        .map(|item| item.extract::<(String, Bound<'_, PyAny>)>().unwrap().0)
-        .collect();
-
-    for (i, item) in items.iter().enumerate() {
-        let (key, value) = item.extract::<(String, Bound<'_, PyAny>)>()?;
+    // Collect all keys at this level for collision detection
+    let all_keys: HashSet<String> = items.iter().map(|(key, _)| key.clone()).collect();
+    let collides = |folded_key: &str| {
+        all_keys.contains(folded_key) || scope.dotted_keys.contains(&scope.join(folded_key))
+    };
 
+    for (i, (key, value)) in items.into_iter().enumerate() {
         // Add newline and indentation before each field (except first at root)
         if i > 0 || !is_root {
-            output.push('\n');
-            write_indent(output, depth, indent_size);
+            output.push_str(ctx.line_separator.as_str());
+            write_indent(output, depth, ctx);
         }
 
         // Check if value is an array - need to write key with array header inline
         if value.is_instance_of::<PyList>() {
             if let Ok(list) = value.cast::<PyList>() {
-                serialize_array_with_key(
-                    py,
-                    &key,
-                    &list,
-                    output,
-                    depth,
-                    delimiter,
-                    indent_size,
-                    ctx,
-                )?;
+                serialize_array_with_key(py, &key, list, output, depth, delimiter, ctx)?;
             }
         } else {
-            // Try key folding if enabled (only at root level to avoid collisions)
-            if ctx.key_folding && depth == 0 && value.is_instance_of::<PyDict>() {
-                if let Ok(nested_dict) = value.cast::<PyDict>() {
-                    if let Some((folded_key, final_value)) = try_fold_key_chain(
-                        py,
-                        &key,
-                        &nested_dict,
-                        depth,
-                        ctx.flatten_depth,
-                        &all_keys,
-                    )? {
-                        // Successfully folded - emit folded key
-                        serialize_key(&folded_key, output);
-
-                        if final_value.is_instance_of::<PyList>() {
-                            // Folded to array - write array inline (no colon yet, array header will add it)
-                            if let Ok(list) = final_value.cast::<PyList>() {
-                                write_array_inline(
-                                    py,
-                                    &list,
-                                    output,
-                                    depth,
-                                    delimiter,
-                                    indent_size,
-                                    ctx,
-                                )?;
-                            }
-                        } else if final_value.is_instance_of::<PyDict>() {
-                            // Folded to object - serialize nested without further folding
-                            output.push(':');
-                            if let Ok(dict) = final_value.cast::<PyDict>() {
-                                // Create a context with folding disabled for nested serialization
-                                let no_fold_ctx = SerializationContext {
-                                    key_folding: false,
-                                    flatten_depth: 0,
-                                };
-                                serialize_object(
-                                    py,
-                                    &dict,
-                                    output,
-                                    depth + 1,
-                                    delimiter,
-                                    false,
-                                    indent_size,
-                                    &no_fold_ctx,
-                                )?;
-                            }
-                        } else {
-                            // Folded to primitive
-                            output.push(':');
-                            output.push(' ');
-                            serialize_value(
-                                py,
-                                &final_value,
-                                output,
-                                depth,
-                                delimiter,
-                                false,
-                                indent_size,
-                                ctx,
-                            )?;
-                        }
-                        continue;
+            // Try key folding if enabled, at any depth; the folded key must
+            // not collide with a sibling or an enclosing dotted key
+            if ctx.key_folding
+                && value.is_instance_of::<PyDict>()
+                && let Ok(nested_dict) = value.cast::<PyDict>()
+                && let Some((folded_key, final_value)) =
+                    try_fold_key_chain(py, &key, nested_dict, depth, ctx, &collides)?
+            {
+                // Successfully folded - emit folded key
+                ctx.write_key(&folded_key, output);
+
+                if final_value.is_instance_of::<PyList>() {
+                    // Folded to array - write array inline (no colon yet, array header will add it)
+                    if let Ok(list) = final_value.cast::<PyList>() {
+                        write_array_inline(py, list, output, depth, delimiter, ctx)?;
+                    }
+                } else if final_value.is_instance_of::<PyDict>() {
+                    // Folded to object - serialize nested without further folding
+                    output.push(':');
+                    if let Ok(dict) = final_value.cast::<PyDict>() {
+                        // Create a context with folding disabled for nested serialization
+                        let no_fold_ctx = SerializationContext {
+                            key_folding: false,
+                            flatten_depth: 0,
+                            ..ctx.clone()
+                        };
+                        serialize_object(dict, output, depth + 1, delimiter, false, &no_fold_ctx)?;
+                    }
+                } else {
+                    // Folded to primitive
+                    output.push(':');
+                    if !ctx.compact {
+                        output.push(' ');
                     }
+                    serialize_value(py, &final_value, output, depth, delimiter, false, ctx)?;
                 }
+                continue;
             }
 
             // Standard serialization (no folding)
             // Encode key per Section 7.3
-            serialize_key(&key, output);
+            ctx.write_key(&key, output);
             output.push(':');
 
             // Check if value needs nesting
             if value.is_instance_of::<PyDict>() {
-                // Nested object
+                // Nested object; its keys may fold too
                 if let Ok(nested_dict) = value.cast::<PyDict>() {
-                    serialize_object(
-                        py,
-                        &nested_dict,
+                    let nested_scope = if ctx.key_folding {
+                        scope.child(&key, &all_keys)
+                    } else {
+                        FoldScope::default()
+                    };
+                    serialize_object_in(
+                        nested_dict,
                         output,
                         depth + 1,
                         delimiter, // Use document delimiter per Section 11.1
                         false,
-                        indent_size,
                         ctx,
+                        &nested_scope,
                     )?;
                 }
             } else {
-                // Primitive: space after colon
-                output.push(' ');
+                // Primitive: space after colon, unless compact
+                if !ctx.compact {
+                    output.push(' ');
+                }
                 // Use document delimiter per Section 11.1
-                serialize_value(
-                    py,
-                    &value,
-                    output,
-                    depth,
-                    delimiter,
-                    false,
-                    indent_size,
-                    ctx,
-                )?;
-            }
-        }
-    }
-
-    Ok(())
-}
-
-/// Serialize object key per TOON v3.0 Section 7.3
-pub fn serialize_key(key: &str, output: &mut String) {
-    // Key can be unquoted if matches: ^[A-Za-z_][\w.]*$
-    if is_valid_unquoted_key(key) {
-        output.push_str(key);
-    } else {
-        // Quote and escape
-        output.push('"');
-        for ch in key.chars() {
-            match ch {
-                '\\' => output.push_str("\\\\"),
-                '"' => output.push_str("\\\""),
-                '\n' => output.push_str("\\n"),
-                '\r' => output.push_str("\\r"),
-                '\t' => output.push_str("\\t"),
-                _ => output.push(ch),
+                serialize_value(py, &value, output, depth, delimiter, false, ctx)?;
             }
         }
-        output.push('"');
-    }
-}
-
-/// Check if key can be unquoted
-pub fn is_valid_unquoted_key(key: &str) -> bool {
-    if key.is_empty() {
-        return false;
-    }
-
-    let mut chars = key.chars();
-    let first = chars.next().unwrap();
-
-    if !first.is_ascii_alphabetic() && first != '_' {
-        return false;
-    }
-
-    for ch in chars {
-        if !ch.is_ascii_alphanumeric() && ch != '_' && ch != '.' {
-            return false;
-        }
     }
 
-    true
+    Ok(())
 }
 
-/// Try to fold a chain of single-key objects into a dot-notation key
+/// Try to fold a chain of single-key objects into a dot-notation key; the
+/// `flatten_depth` limit counts keys from `start_key`, wherever it is
 /// Returns Some((folded_key, final_value)) if folding is possible, None otherwise
 fn try_fold_key_chain<'py>(
     _py: Python<'py>,
     start_key: &str,
     start_dict: &Bound<'py, PyDict>,
     _depth: usize,
-    max_depth: usize,
-    sibling_keys: &HashSet<String>,
+    ctx: &SerializationContext,
+    collides: &impl Fn(&str) -> bool,
 ) -> PyResult<Option<(String, Bound<'py, PyAny>)>> {
+    let max_depth = ctx.flatten_depth;
     // If max_depth is 0 or 1, no folding is possible (need at least 2 keys to fold)
     if max_depth < 2 {
         return Ok(None);
     }
 
+    // Keys written quoted (including non-ASCII ones under `ensure_ascii`)
+    // cannot be part of a folded chain
+    let foldable = |key: &str| is_valid_unquoted_key(key) && (!ctx.ensure_ascii || key.is_ascii());
+
     // If the start_key requires quotes, it cannot be part of a folded chain
-    if !is_valid_unquoted_key(start_key) {
+    if !foldable(start_key) {
         return Ok(None);
     }
 
@@ -452,7 +2097,7 @@ fn try_fold_key_chain<'py>(
         let (next_key, next_value) = items[0].extract::<(String, Bound<'_, PyAny>)>()?;
 
         // Check if the key can be represented unquoted (safe for folding)
-        if !is_valid_unquoted_key(&next_key) {
+        if !foldable(&next_key) {
             break;
         }
 
@@ -462,7 +2107,7 @@ fn try_fold_key_chain<'py>(
         if key_chain.len() >= max_depth {
             // Reached depth limit - return what we have folded so far
             let folded_key = key_chain.join(".");
-            if sibling_keys.contains(&folded_key) {
+            if collides(&folded_key) {
                 return Ok(None);
             }
             return Ok(Some((folded_key, next_value)));
@@ -474,7 +2119,7 @@ fn try_fold_key_chain<'py>(
                 if dict.is_empty() {
                     // Empty dict - treat as terminal value
                     let folded_key = key_chain.join(".");
-                    if sibling_keys.contains(&folded_key) {
+                    if collides(&folded_key) {
                         return Ok(None);
                     }
                     return Ok(Some((folded_key, next_value)));
@@ -486,7 +2131,7 @@ fn try_fold_key_chain<'py>(
             // Reached a non-object value (primitive or array)
             // Check for collision with literal keys at current level
             let folded_key = key_chain.join(".");
-            if sibling_keys.contains(&folded_key) {
+            if collides(&folded_key) {
                 // Collision detected - cannot fold
                 return Ok(None);
             }
@@ -504,69 +2149,37 @@ fn try_fold_key_chain<'py>(
 fn write_array_inline(
     py: Python,
     list: &Bound<'_, PyList>,
-    output: &mut String,
+    output: &mut impl Output,
     depth: usize,
     delimiter: char,
-    indent_size: usize,
     ctx: &SerializationContext,
 ) -> PyResult<()> {
     let len = list.len();
-    let all_primitives = list.iter().all(|item| is_primitive(&item));
 
-    if all_primitives {
+    if is_inline_array(py, list, depth, delimiter, ctx, || output.column())? {
         // Inline primitive array
-        write_array_header(output, len, delimiter, true);
+        write_array_header(output, len, delimiter, !ctx.compact);
         if len > 0 {
             for (i, item) in list.iter().enumerate() {
                 if i > 0 {
                     output.push(delimiter);
                 }
-                serialize_value(py, &item, output, depth, delimiter, false, indent_size, ctx)?;
+                serialize_value(py, &item, output, depth, delimiter, false, ctx)?;
             }
         }
     } else {
         // Check for tabular format
-        if let Some(fields) = detect_tabular(list)? {
+        if let Some(table) = detect_tabular(list, ctx)? {
             // Tabular array
-            write_tabular_header(output, len, delimiter, &fields);
-            for item in list.iter() {
-                output.push('\n');
-                write_indent(output, depth + 1, indent_size);
-                let dict = item.cast::<PyDict>()?;
-                for (i, field) in fields.iter().enumerate() {
-                    if i > 0 {
-                        output.push(delimiter);
-                    }
-                    let value = dict.get_item(field)?.unwrap();
-                    serialize_value(
-                        py,
-                        &value,
-                        output,
-                        depth + 1,
-                        delimiter,
-                        false,
-                        indent_size,
-                        ctx,
-                    )?;
-                }
-            }
+            write_table(list, table, output, depth + 1, delimiter, ctx)?;
         } else {
             // Expanded array format
             write_array_header(output, len, delimiter, false);
             for item in list.iter() {
-                output.push('\n');
-                write_indent(output, depth + 1, indent_size);
+                output.push_str(ctx.line_separator.as_str());
+                write_indent(output, depth + 1, ctx);
                 output.push_str("- ");
-                serialize_value(
-                    py,
-                    &item,
-                    output,
-                    depth + 1,
-                    delimiter,
-                    false,
-                    indent_size,
-                    ctx,
-                )?;
+                serialize_value(py, &item, output, depth + 1, delimiter, false, ctx)?;
             }
         }
     }
@@ -578,56 +2191,35 @@ fn serialize_array_with_key(
     py: Python,
     key: &str,
     list: &Bound<'_, PyList>,
-    output: &mut String,
+    output: &mut impl Output,
     depth: usize,
     delimiter: char,
-    indent_size: usize,
     ctx: &SerializationContext,
 ) -> PyResult<()> {
     let len = list.len();
 
-    // Check if all elements are primitives
-    let all_primitives = list.iter().all(|item| is_primitive(&item));
-
-    if all_primitives {
+    if is_inline_array(py, list, depth, delimiter, ctx, || {
+        output.column() + key_width(key, ctx)
+    })? {
         // Inline primitive array: key[N]: v1,v2,v3
-        serialize_key(key, output);
-        write_array_header(output, len, delimiter, true);
+        ctx.write_key(key, output);
+        write_array_header(output, len, delimiter, !ctx.compact);
 
         if len > 0 {
             for (i, item) in list.iter().enumerate() {
                 if i > 0 {
                     output.push(delimiter);
                 }
-                serialize_value(py, &item, output, depth, delimiter, false, indent_size, ctx)?;
+                serialize_value(py, &item, output, depth, delimiter, false, ctx)?;
             }
         }
     } else {
         // Check for tabular format (Section 9.3)
-        if let Some(fields) = detect_tabular(list)? {
-            serialize_tabular_with_key(
-                py,
-                key,
-                list,
-                output,
-                depth,
-                delimiter,
-                &fields,
-                indent_size,
-                ctx,
-            )?;
+        if let Some(table) = detect_tabular(list, ctx)? {
+            serialize_tabular_with_key(key, list, output, depth, delimiter, table, ctx)?;
         } else {
             // Expanded list format
-            serialize_expanded_list_with_key(
-                py,
-                key,
-                list,
-                output,
-                depth,
-                delimiter,
-                indent_size,
-                ctx,
-            )?;
+            serialize_expanded_list_with_key(py, key, list, output, depth, delimiter, ctx)?;
         }
     }
 
@@ -638,74 +2230,177 @@ fn serialize_array_with_key(
 pub fn serialize_array(
     py: Python,
     list: &Bound<'_, PyList>,
-    output: &mut String,
+    output: &mut impl Output,
     depth: usize,
     delimiter: char,
     is_root: bool,
-    indent_size: usize,
     ctx: &SerializationContext,
 ) -> PyResult<()> {
     let len = list.len();
 
-    // Check if all elements are primitives
-    let all_primitives = list.iter().all(|item| is_primitive(&item));
-
-    if all_primitives {
+    let header_column = || {
+        if is_root {
+            return output.column();
+        }
+        let mut indent = ByteCounter::default();
+        write_indent(&mut indent, depth, ctx);
+        indent.column
+    };
+    if is_inline_array(py, list, depth, delimiter, ctx, header_column)? {
         // Inline primitive array: [N]: v1,v2,v3
         if !is_root {
-            output.push('\n');
-            write_indent(output, depth, indent_size);
+            output.push_str(ctx.line_separator.as_str());
+            write_indent(output, depth, ctx);
         }
-        write_array_header(output, len, delimiter, true);
+        write_array_header(output, len, delimiter, !ctx.compact);
 
         if len > 0 {
             for (i, item) in list.iter().enumerate() {
                 if i > 0 {
                     output.push(delimiter);
                 }
-                serialize_value(py, &item, output, depth, delimiter, false, indent_size, ctx)?;
+                serialize_value(py, &item, output, depth, delimiter, false, ctx)?;
             }
         }
     } else {
         // Check for tabular format (Section 9.3)
-        if let Some(fields) = detect_tabular(list)? {
-            serialize_tabular(
-                py,
-                list,
-                output,
-                depth,
-                delimiter,
-                &fields,
-                is_root,
-                indent_size,
-                ctx,
-            )?;
+        if let Some(table) = detect_tabular(list, ctx)? {
+            serialize_tabular(list, output, depth, delimiter, table, is_root, ctx)?;
         } else {
             // Expanded list format
-            serialize_expanded_list(
-                py,
-                list,
-                output,
-                depth,
-                delimiter,
-                is_root,
-                indent_size,
-                ctx,
-            )?;
+            serialize_expanded_list(py, list, output, depth, delimiter, is_root, ctx)?;
         }
     }
 
     Ok(())
 }
 
+/// Whether `list` takes the inline `[N]: v1,v2` form: every item is a
+/// primitive and, with `inline_threshold`, the items as written after the
+/// colon take fewer bytes than the threshold. With `max_line_length`, the
+/// whole line must also fit, its `[N]:` header starting at the column
+/// `header_column` returns. Other lists are tabular or expanded.
+fn is_inline_array(
+    py: Python,
+    list: &Bound<'_, PyList>,
+    depth: usize,
+    delimiter: char,
+    ctx: &SerializationContext,
+    header_column: impl FnOnce() -> usize,
+) -> PyResult<bool> {
+    if !list.iter().all(|item| is_primitive(&item)) {
+        return Ok(false);
+    }
+    if list.is_empty() || (ctx.inline_threshold.is_none() && ctx.max_line_length.is_none()) {
+        return Ok(true);
+    }
+    let line_start = match ctx.max_line_length {
+        Some(_) => {
+            let mut header = ByteCounter::default();
+            write_array_header(&mut header, list.len(), delimiter, !ctx.compact);
+            header_column() + header.column
+        }
+        None => 0,
+    };
+    let mut counter = ByteCounter::default();
+    for (i, item) in list.iter().enumerate() {
+        if i > 0 {
+            counter.push(delimiter);
+        }
+        serialize_value(py, &item, &mut counter, depth, delimiter, false, ctx)?;
+        if ctx
+            .inline_threshold
+            .is_some_and(|threshold| counter.bytes >= threshold)
+            || ctx
+                .max_line_length
+                .is_some_and(|max| line_start + counter.column > max)
+        {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Characters `key` takes when written as an object key
+fn key_width(key: &str, ctx: &SerializationContext) -> usize {
+    let mut counter = ByteCounter::default();
+    ctx.write_key(key, &mut counter);
+    counter.column
+}
+
 /// Check if value is a primitive (not dict or list)
 fn is_primitive(obj: &Bound<'_, PyAny>) -> bool {
     !obj.is_instance_of::<PyDict>() && !obj.is_instance_of::<PyList>()
 }
 
-/// Detect if list qualifies for tabular format per Section 9.3
-fn detect_tabular(list: &Bound<'_, PyList>) -> PyResult<Option<Vec<String>>> {
-    if list.is_empty() {
+/// Check if value fits a tabular cell: a primitive or, with
+/// `tabular_nested_arrays`, a list of primitives
+fn is_cell_value(obj: &Bound<'_, PyAny>, ctx: &SerializationContext) -> bool {
+    match obj.cast::<PyList>() {
+        Ok(list) => ctx.tabular_nested_arrays && list.iter().all(|item| is_primitive(&item)),
+        Err(_) => is_primitive(obj),
+    }
+}
+
+/// Write one tabular cell that `Cell` could not copy out. A list (only
+/// allowed with `tabular_nested_arrays`) becomes an inline array whose items
+/// are split by `|`, or by `,` when the row delimiter is not a comma, so they
+/// cannot be mistaken for cells.
+fn write_cell_value(
+    py: Python,
+    value: &Bound<'_, PyAny>,
+    output: &mut impl Output,
+    row_depth: usize,
+    delimiter: char,
+    ctx: &SerializationContext,
+) -> PyResult<()> {
+    let Ok(list) = value.cast::<PyList>() else {
+        return serialize_value(py, value, output, row_depth, delimiter, false, ctx);
+    };
+    let inner = if delimiter == ',' { '|' } else { ',' };
+    write_array_header(output, list.len(), inner, !ctx.compact);
+    for (i, item) in list.iter().enumerate() {
+        if i > 0 {
+            output.push(inner);
+        }
+        match item.cast_exact::<PyString>() {
+            // Strings holding the row delimiter are quoted so rows split
+            // cleanly
+            Ok(s) => {
+                let text = s.to_cow()?;
+                if text.contains(delimiter) {
+                    ctx.write_quoted(&text, output);
+                } else {
+                    write_string_value(&text, output, inner, ctx);
+                }
+            }
+            Err(_) => serialize_value(py, &item, output, row_depth, inner, false, ctx)?,
+        }
+    }
+    Ok(())
+}
+
+/// A list detected as tabular: its fields and, when detection could copy
+/// them out on the way, its cells
+struct Table {
+    fields: Vec<String>,
+    cells: Cells,
+}
+
+enum Cells {
+    /// Every cell, row by row
+    Extracted(Vec<Cell>),
+    /// Some cell is not a plain primitive; rows go through `serialize_value`
+    Mixed,
+    /// Not looked at yet
+    Pending,
+}
+
+/// Detect if list qualifies for tabular format per Section 9.3. The rows are
+/// checked and their cells extracted in the same pass, so emitting the table
+/// does not have to look the values up again.
+fn detect_tabular(list: &Bound<'_, PyList>, ctx: &SerializationContext) -> PyResult<Option<Table>> {
+    if list.is_empty() || ctx.tabular == TabularMode::Never {
         return Ok(None);
     }
 
@@ -722,20 +2417,32 @@ fn detect_tabular(list: &Bound<'_, PyList>) -> PyResult<Option<Vec<String>>> {
         return Ok(None);
     }
 
+    if ctx.tabular == TabularMode::Fill {
+        return detect_tabular_union(list, ctx);
+    }
+
     // Get keys from first dict
     let first_item = list.get_item(0)?;
     let first_dict = first_item.cast::<PyDict>()?;
-    let first_keys: Vec<String> = first_dict
+    let mut first_keys: Vec<String> = first_dict
         .keys()
         .iter()
         .map(|k| k.extract::<String>())
         .collect::<Result<Vec<_>, _>>()?;
+    ctx.tabular_field_order
+        .apply(&mut first_keys, ctx.key_order);
 
     if first_keys.is_empty() {
         return Ok(None);
     }
 
-    // Check all dicts have same keys and all values are primitives
+    // Check all dicts have same keys and all values are primitives. Keys are
+    // looked up by name, so rows may list them in any order; the header and
+    // every row follow the first dict's order (or the configured one).
+    let mut cells = Some(Vec::with_capacity(list.len() * first_keys.len()));
+    let mut pending = false;
+    let index = FieldIndex::new(list, &first_keys)?;
+    let mut values = Vec::with_capacity(first_keys.len());
     for item in list.iter() {
         let dict = item.cast::<PyDict>()?;
 
@@ -745,114 +2452,511 @@ fn detect_tabular(list: &Bound<'_, PyList>) -> PyResult<Option<Vec<String>>> {
         }
 
         // Check all keys present and values are primitives
-        for key in &first_keys {
-            if let Ok(value) = dict.get_item(key) {
-                if let Some(v) = value {
-                    if !is_primitive(&v) {
-                        return Ok(None);
+        index.row_values(dict, &mut values)?;
+        for v in values.drain(..) {
+            let Some(v) = v else {
+                return Ok(None);
+            };
+            if !is_cell_value(&v, ctx) {
+                return Ok(None);
+            }
+            if let Some(extracted) = &mut cells {
+                match Cell::extract(&v, ctx) {
+                    Ok(Some(cell)) => extracted.push(cell),
+                    Ok(None) => cells = None,
+                    // Leave the error to emission, once the list is known
+                    // to be tabular
+                    Err(_) => {
+                        cells = None;
+                        pending = true;
                     }
-                } else {
-                    return Ok(None);
                 }
-            } else {
-                return Ok(None);
             }
         }
     }
 
-    Ok(Some(first_keys))
+    let cells = match cells {
+        Some(cells) => Cells::Extracted(cells),
+        None if pending => Cells::Pending,
+        None => Cells::Mixed,
+    };
+    Ok(Some(Table {
+        fields: first_keys,
+        cells,
+    }))
+}
+
+/// `tabular="fill"`: fields are the union of all rows' keys in first-seen
+/// order (or the configured field order); values must still be primitives
+fn detect_tabular_union(
+    list: &Bound<'_, PyList>,
+    ctx: &SerializationContext,
+) -> PyResult<Option<Table>> {
+    let mut fields: Vec<String> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    for item in list.iter() {
+        let dict = item.cast::<PyDict>()?;
+        // Where the next new key of this row goes under FieldOrder::Union:
+        // right after the last key of this row already placed
+        let mut insert_at = 0;
+        for (key, value) in dict.iter() {
+            if !is_cell_value(&value, ctx) {
+                return Ok(None);
+            }
+            let key: String = key.extract()?;
+            if seen.insert(key.clone()) {
+                if ctx.tabular_field_order == FieldOrder::Union {
+                    fields.insert(insert_at, key);
+                    insert_at += 1;
+                } else {
+                    fields.push(key);
+                }
+            } else if ctx.tabular_field_order == FieldOrder::Union {
+                insert_at = fields.iter().position(|field| *field == key).unwrap_or(0) + 1;
+            }
+        }
+    }
+    if fields.is_empty() {
+        return Ok(None);
+    }
+    ctx.tabular_field_order.apply(&mut fields, ctx.key_order);
+    Ok(Some(Table {
+        fields,
+        cells: Cells::Pending,
+    }))
 }
 
 /// Serialize array in tabular format per Section 9.3
 fn serialize_tabular(
-    py: Python,
     list: &Bound<'_, PyList>,
-    output: &mut String,
+    output: &mut impl Output,
     depth: usize,
     delimiter: char,
-    fields: &[String],
+    table: Table,
     is_root: bool,
-    indent_size: usize,
     ctx: &SerializationContext,
 ) -> PyResult<()> {
-    let len = list.len();
-
     // Header: [N]{f1,f2,f3}:
     if !is_root {
-        output.push('\n');
-        write_indent(output, depth, indent_size);
+        output.push_str(ctx.line_separator.as_str());
+        write_indent(output, depth, ctx);
+    }
+    write_table(list, table, output, depth + 1, delimiter, ctx)
+}
+
+/// Serialize array in tabular format with key (for object values)
+fn serialize_tabular_with_key(
+    key: &str,
+    list: &Bound<'_, PyList>,
+    output: &mut impl Output,
+    depth: usize,
+    delimiter: char,
+    table: Table,
+    ctx: &SerializationContext,
+) -> PyResult<()> {
+    // Header: key[N]{f1,f2,f3}:
+    ctx.write_key(key, output);
+    write_table(list, table, output, depth + 1, delimiter, ctx)
+}
+
+/// Write a detected table's header and then its rows, one line per dict at
+/// `row_depth`
+fn write_table(
+    list: &Bound<'_, PyList>,
+    table: Table,
+    output: &mut impl Output,
+    row_depth: usize,
+    delimiter: char,
+    ctx: &SerializationContext,
+) -> PyResult<()> {
+    let Table { fields, cells } = table;
+    let cells = match cells {
+        Cells::Extracted(cells) => Some(cells),
+        Cells::Mixed => None,
+        Cells::Pending => extract_cells(list, &fields, ctx)?,
+    };
+    let sorted;
+    let order = match &ctx.sort_rows_by {
+        // Rows at depth 1 belong to the root table or one under a root key
+        Some(column) => sort_table_rows(list, &fields, column, row_depth == 1)?,
+        None => None,
+    };
+    let (list, cells) = match order {
+        Some((rows, order)) => {
+            sorted = rows;
+            (&sorted, cells.map(|cells| permute_rows(cells, &order)))
+        }
+        None => (list, cells),
+    };
+    write_tabular_header(output, list, delimiter, &fields, cells.as_deref(), ctx)?;
+    write_tabular_rows(list, output, row_depth, delimiter, &fields, cells, ctx)
+}
+
+/// `sort_rows_by`: the rows of a table sorted stably by the value of
+/// `column`, as a new list, and the original index of each. A table without
+/// the column raises if `required` (a top-level table, which the option
+/// names the column of) and otherwise gives `None`, keeping its order like
+/// any other list. Rows of a `tabular="fill"` table that lack the value come
+/// last.
+fn sort_table_rows<'py>(
+    list: &Bound<'py, PyList>,
+    fields: &[String],
+    column: &str,
+    required: bool,
+) -> PyResult<Option<(Bound<'py, PyList>, Vec<usize>)>> {
+    let py = list.py();
+    if !fields.iter().any(|field| field == column) {
+        if required {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Cannot sort rows by {:?}: the table has no such column (it has {})",
+                column,
+                fields.join(", ")
+            )));
+        }
+        return Ok(None);
+    }
+    // `(false, value)`, or `(true, None)` for a missing value; the flag
+    // decides before the values are ever compared
+    let keys = PyList::empty(py);
+    for row in list.iter() {
+        let key = match row.cast::<PyDict>()?.get_item(column)? {
+            Some(value) => (false, value.unbind()),
+            None => (true, py.None()),
+        };
+        keys.append(key)?;
+    }
+    // Sort the indices with `sorted()`, so values compare as in Python
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("key", keys.getattr("__getitem__")?)?;
+    let order: Vec<usize> = py
+        .import("builtins")?
+        .getattr("sorted")?
+        .call(((0..list.len()).collect::<Vec<_>>(),), Some(&kwargs))
+        .map_err(|err| {
+            if err.is_instance_of::<pyo3::exceptions::PyTypeError>(py) {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Cannot sort rows by {:?}: {}",
+                    column,
+                    err.value(py)
+                ))
+            } else {
+                err
+            }
+        })?
+        .extract()?;
+    let rows = order
+        .iter()
+        .map(|&i| list.get_item(i))
+        .collect::<PyResult<Vec<_>>>()?;
+    let rows = PyList::new(py, rows)?;
+    Ok(Some((rows, order)))
+}
+
+/// Reorder `cells`, row by row, so row `i` is the one at `order[i]`
+fn permute_rows(cells: Vec<Cell>, order: &[usize]) -> Vec<Cell> {
+    let width = cells.len() / order.len().max(1);
+    let mut cells = cells.into_iter();
+    let mut rows: Vec<Option<Vec<Cell>>> = order
+        .iter()
+        .map(|_| Some(cells.by_ref().take(width).collect()))
+        .collect();
+    order
+        .iter()
+        .flat_map(|&i| rows[i].take().unwrap_or_default())
+        .collect()
+}
+
+/// Tables with at least this many cells are formatted with the GIL released
+const DETACH_MIN_CELLS: usize = 4096;
+
+/// A tabular cell holding a plain primitive, copied out of Python so rows can
+/// be formatted without the GIL
+enum Cell {
+    Null,
+    Bool(bool),
+    Int(i64),
+    /// The value, and whether it is a `float` rather than an oversized `int`
+    Float(f64, bool),
+    Str(String),
+}
+
+impl Cell {
+    /// Copy `obj` out if it is exactly `None`, `bool`, `int`, `float` or
+    /// `str`. Subclasses (such as enum members) and floats that must raise
+    /// under `allow_nan=False` go through `serialize_value` instead.
+    fn extract(obj: &Bound<'_, PyAny>, ctx: &SerializationContext) -> PyResult<Option<Self>> {
+        let cell = if obj.is_none() {
+            Cell::Null
+        } else if let Ok(b) = obj.cast_exact::<PyBool>() {
+            Cell::Bool(b.is_true())
+        } else if obj.is_exact_instance_of::<PyInt>() {
+            match obj.extract::<i64>() {
+                Ok(i) => Cell::Int(i),
+                Err(_) => match obj.extract::<f64>() {
+                    Ok(f) => Cell::Float(f, false),
+                    Err(_) => return Ok(None),
+                },
+            }
+        } else if let Ok(float) = obj.cast_exact::<PyFloat>() {
+            let f = float.value();
+            if !f.is_finite() && !ctx.allow_nan && ctx.float_tokens.token(f).is_none() {
+                return Ok(None);
+            }
+            Cell::Float(f, true)
+        } else if let Ok(s) = obj.cast_exact::<PyString>() {
+            Cell::Str(s.to_cow()?.into_owned())
+        } else {
+            return Ok(None);
+        };
+        Ok(Some(cell))
+    }
+
+    fn write(&self, output: &mut impl Output, delimiter: char, ctx: &SerializationContext) {
+        match self {
+            Cell::Null => output.push_str(&ctx.literals.null),
+            Cell::Bool(b) => output.push_str(ctx.literals.bool(*b)),
+            Cell::Int(i) => output.push_str(itoa::Buffer::new().format(*i)),
+            Cell::Float(f, is_float) => {
+                // Non-finite values only get here when they can be written
+                write_float(output, *f, *is_float, ctx);
+            }
+            Cell::Str(s) => write_string_value(s, output, delimiter, ctx),
+        }
     }
-    write_tabular_header(output, len, delimiter, fields);
+}
+
+/// The fields of a table as Python strings, to pick each row's values out in
+/// field order. The names are the first row's own key objects where it has
+/// them: rows from one dict literal or one `json.loads` call share their key
+/// objects and usually their order, so one pass over a row's items matches
+/// them by identity, without comparing or hashing; other rows fall back to a
+/// lookup per field.
+struct FieldIndex<'py> {
+    names: Vec<Bound<'py, PyString>>,
+}
+
+impl<'py> FieldIndex<'py> {
+    fn new(list: &Bound<'py, PyList>, fields: &[String]) -> PyResult<Self> {
+        let py = list.py();
+        let mut keys = HashMap::new();
+        if let Ok(first) = list.get_item(0)
+            && let Ok(first) = first.cast_into::<PyDict>()
+        {
+            for key in first.keys() {
+                if let Ok(key) = key.cast_into_exact::<PyString>() {
+                    keys.insert(key.to_cow()?.into_owned(), key);
+                }
+            }
+        }
+        Ok(Self {
+            names: fields
+                .iter()
+                .map(|field| {
+                    keys.remove(field)
+                        .unwrap_or_else(|| PyString::new(py, field))
+                })
+                .collect(),
+        })
+    }
+
+    /// Replace `values` with the values of `dict` in field order, `None` for
+    /// a missing field
+    fn row_values(
+        &self,
+        dict: &Bound<'py, PyDict>,
+        values: &mut Vec<Option<Bound<'py, PyAny>>>,
+    ) -> PyResult<()> {
+        values.clear();
+        if dict.len() == self.names.len() {
+            for ((key, value), name) in dict.iter().zip(&self.names) {
+                if !(key.is(name) || key.eq(name)?) {
+                    break;
+                }
+                values.push(Some(value));
+            }
+            if values.len() == self.names.len() {
+                return Ok(());
+            }
+            values.clear();
+        }
+        for name in &self.names {
+            values.push(dict.get_item(name)?);
+        }
+        Ok(())
+    }
+}
 
-    // Rows: one per object
+/// Copy every cell of a table out of Python, row by row, or return `None` if
+/// any cell is not a plain primitive
+fn extract_cells(
+    list: &Bound<'_, PyList>,
+    fields: &[String],
+    ctx: &SerializationContext,
+) -> PyResult<Option<Vec<Cell>>> {
+    if fields.is_empty() {
+        return Ok(None);
+    }
+    let mut cells = Vec::with_capacity(list.len() * fields.len());
+    let index = FieldIndex::new(list, fields)?;
+    let mut values = Vec::with_capacity(fields.len());
     for item in list.iter() {
-        output.push('\n');
-        write_indent(output, depth + 1, indent_size);
+        index.row_values(item.cast::<PyDict>()?, &mut values)?;
+        for value in values.drain(..) {
+            let cell = match value {
+                Some(value) => Cell::extract(&value, ctx)?,
+                None => Some(Cell::Null),
+            };
+            match cell {
+                Some(cell) => cells.push(cell),
+                None => return Ok(None),
+            }
+        }
+    }
+    Ok(Some(cells))
+}
 
-        let dict = item.cast::<PyDict>()?;
-        for (i, field) in fields.iter().enumerate() {
+/// Write extracted cells, `width` per row, one row per line at `row_depth`
+fn write_cell_rows(
+    output: &mut impl Output,
+    cells: &[Cell],
+    width: usize,
+    row_depth: usize,
+    delimiter: char,
+    ctx: &SerializationContext,
+) {
+    for row in cells.chunks(width) {
+        output.push_str(ctx.line_separator.as_str());
+        write_indent(output, row_depth, ctx);
+        for (i, cell) in row.iter().enumerate() {
             if i > 0 {
                 output.push(delimiter);
             }
-            let value = dict.get_item(field)?.unwrap();
-            serialize_value(
-                py,
-                &value,
-                output,
-                depth + 1,
-                delimiter,
-                false,
-                indent_size,
-                ctx,
-            )?;
+            cell.write(output, delimiter, ctx);
         }
     }
-
-    Ok(())
 }
 
-/// Serialize array in tabular format with key (for object values)
-fn serialize_tabular_with_key(
+/// Each cell of a tabular array as it is written, row by row
+fn render_cells(
     py: Python,
-    key: &str,
     list: &Bound<'_, PyList>,
-    output: &mut String,
-    depth: usize,
+    fields: &[String],
+    cells: Option<&[Cell]>,
+    row_depth: usize,
+    delimiter: char,
+    ctx: &SerializationContext,
+) -> PyResult<Vec<String>> {
+    if let Some(cells) = cells {
+        return Ok(cells
+            .iter()
+            .map(|cell| {
+                let mut text = String::new();
+                cell.write(&mut text, delimiter, ctx);
+                text
+            })
+            .collect());
+    }
+    let mut rendered = Vec::with_capacity(list.len() * fields.len());
+    let index = FieldIndex::new(list, fields)?;
+    let mut values = Vec::with_capacity(fields.len());
+    for item in list.iter() {
+        index.row_values(item.cast::<PyDict>()?, &mut values)?;
+        for value in values.drain(..) {
+            let value = value.unwrap_or_else(|| py.None().into_bound(py));
+            let mut text = String::new();
+            write_cell_value(py, &value, &mut text, row_depth, delimiter, ctx)?;
+            rendered.push(text);
+        }
+    }
+    Ok(rendered)
+}
+
+/// Write rendered tabular cells with each column padded, after its
+/// delimiter, to the widest cell before it, so columns start at the same
+/// position. The last column is not padded.
+fn write_aligned_rows(
+    output: &mut impl Output,
+    rendered: &[String],
+    width: usize,
+    row_depth: usize,
+    delimiter: char,
+    ctx: &SerializationContext,
+) {
+    let mut widths = vec![0; width];
+    for row in rendered.chunks(width) {
+        for (column, text) in row.iter().enumerate() {
+            widths[column] = widths[column].max(text.chars().count());
+        }
+    }
+    for row in rendered.chunks(width) {
+        output.push_str(ctx.line_separator.as_str());
+        write_indent(output, row_depth, ctx);
+        for (column, text) in row.iter().enumerate() {
+            if column > 0 {
+                output.push(delimiter);
+                let padding = widths[column - 1] - row[column - 1].chars().count();
+                for _ in 0..padding {
+                    output.push(' ');
+                }
+            }
+            output.push_str(text);
+        }
+    }
+}
+
+/// Write the rows of a tabular array, one line per dict at `row_depth`.
+/// Tables of plain primitives arrive as extracted `cells` and, when large,
+/// are formatted with the GIL released so other Python threads can run
+/// meanwhile.
+fn write_tabular_rows(
+    list: &Bound<'_, PyList>,
+    output: &mut impl Output,
+    row_depth: usize,
     delimiter: char,
     fields: &[String],
-    indent_size: usize,
+    cells: Option<Vec<Cell>>,
     ctx: &SerializationContext,
 ) -> PyResult<()> {
-    let len = list.len();
-
-    // Header: key[N]{f1,f2,f3}:
-    serialize_key(key, output);
-    write_tabular_header(output, len, delimiter, fields);
+    let py = list.py();
+    if ctx.align {
+        let rendered = render_cells(
+            py,
+            list,
+            fields,
+            cells.as_deref(),
+            row_depth,
+            delimiter,
+            ctx,
+        )?;
+        write_aligned_rows(output, &rendered, fields.len(), row_depth, delimiter, ctx);
+        return Ok(());
+    }
+    if let Some(cells) = cells {
+        let write = |output: &mut _| {
+            write_cell_rows(output, &cells, fields.len(), row_depth, delimiter, ctx)
+        };
+        if cells.len() >= DETACH_MIN_CELLS {
+            py.detach(|| write(output));
+        } else {
+            write(output);
+        }
+        return Ok(());
+    }
 
-    // Rows: one per object
+    let index = FieldIndex::new(list, fields)?;
+    let mut values = Vec::with_capacity(fields.len());
     for item in list.iter() {
-        output.push('\n');
-        write_indent(output, depth + 1, indent_size);
-
-        let dict = item.cast::<PyDict>()?;
-        for (i, field) in fields.iter().enumerate() {
+        output.push_str(ctx.line_separator.as_str());
+        write_indent(output, row_depth, ctx);
+        index.row_values(item.cast::<PyDict>()?, &mut values)?;
+        for (i, value) in values.drain(..).enumerate() {
             if i > 0 {
                 output.push(delimiter);
             }
-            let value = dict.get_item(field)?.unwrap();
-            serialize_value(
-                py,
-                &value,
-                output,
-                depth + 1,
-                delimiter,
-                false,
-                indent_size,
-                ctx,
-            )?;
+            let value = value.unwrap_or_else(|| py.None().into_bound(py));
+            write_cell_value(py, &value, output, row_depth, delimiter, ctx)?;
         }
     }
-
     Ok(())
 }
 
@@ -861,86 +2965,95 @@ fn serialize_expanded_list_with_key(
     py: Python,
     key: &str,
     list: &Bound<'_, PyList>,
-    output: &mut String,
+    output: &mut impl Output,
     depth: usize,
     delimiter: char,
-    indent_size: usize,
     ctx: &SerializationContext,
 ) -> PyResult<()> {
     let len = list.len();
 
     // Header: key[N]:
-    serialize_key(key, output);
+    ctx.write_key(key, output);
     write_array_header(output, len, delimiter, false);
 
-    // List items with "- " prefix
     for item in list.iter() {
-        output.push('\n');
-        write_indent(output, depth + 1, indent_size);
+        write_list_item(py, &item, output, depth + 1, delimiter, ctx)?;
+    }
 
-        // Check if item is empty dict - encode as bare hyphen without space
-        if let Ok(dict) = item.cast::<PyDict>() {
-            if dict.is_empty() {
-                output.push('-');
-                continue;
-            }
-        }
+    Ok(())
+}
 
-        output.push_str("- ");
-
-        // Check if item itself is a primitive array
-        if let Ok(inner_list) = item.cast::<PyList>() {
-            if inner_list.iter().all(|x| is_primitive(&x)) {
-                // Inline inner array
-                let inner_len = inner_list.len();
-                write_array_header(output, inner_len, delimiter, true);
-                if inner_len > 0 {
-                    for (i, inner_item) in inner_list.iter().enumerate() {
-                        if i > 0 {
-                            output.push(delimiter);
-                        }
-                        serialize_value(
-                            py,
-                            &inner_item,
-                            output,
-                            depth + 1,
-                            delimiter,
-                            false,
-                            indent_size,
-                            ctx,
-                        )?;
-                    }
+/// Write `item` as an entry of an expanded list: a `- ` line at
+/// `item_depth`, or a bare `-` for an empty object
+fn write_list_item(
+    py: Python,
+    item: &Bound<'_, PyAny>,
+    output: &mut impl Output,
+    item_depth: usize,
+    delimiter: char,
+    ctx: &SerializationContext,
+) -> PyResult<()> {
+    output.push_str(ctx.line_separator.as_str());
+    write_indent(output, item_depth, ctx);
+
+    // Check if item is empty dict - encode as bare hyphen without space
+    if let Ok(dict) = item.cast::<PyDict>()
+        && dict.is_empty()
+    {
+        output.push('-');
+        return Ok(());
+    }
+
+    output.push_str("- ");
+
+    // Check if item itself is a primitive array
+    if let Ok(inner_list) = item.cast::<PyList>() {
+        if is_inline_array(py, inner_list, item_depth, delimiter, ctx, || {
+            output.column()
+        })? {
+            // Inline inner array
+            let inner_len = inner_list.len();
+            write_array_header(output, inner_len, delimiter, !ctx.compact);
+            for (i, inner_item) in inner_list.iter().enumerate() {
+                if i > 0 {
+                    output.push(delimiter);
                 }
-            } else {
-                // Nested complex array
-                serialize_value(
-                    py,
-                    &item,
-                    output,
-                    depth + 1,
-                    delimiter,
-                    false,
-                    indent_size,
-                    ctx,
-                )?;
+                serialize_value(py, &inner_item, output, item_depth, delimiter, false, ctx)?;
             }
-        } else if let Ok(dict) = item.cast::<PyDict>() {
-            // Object as list item - serialize with first field on same line as "-"
-            serialize_list_item_object(py, &dict, output, depth + 1, delimiter, indent_size, ctx)?;
         } else {
-            serialize_value(
-                py,
-                &item,
-                output,
-                depth + 1,
-                delimiter,
-                false,
-                indent_size,
-                ctx,
-            )?;
+            // Nested complex array - header should be on same line as hyphen
+            serialize_nested_list_item(py, inner_list, output, item_depth, delimiter, ctx)?;
         }
+    } else if let Ok(dict) = item.cast::<PyDict>() {
+        // Object as list item - serialize with first field on same line as "-"
+        serialize_list_item_object(py, dict, output, item_depth, delimiter, ctx)?;
+    } else {
+        serialize_value(py, item, output, item_depth, delimiter, false, ctx)?;
     }
+    Ok(())
+}
 
+/// Write a list of non-primitive items that is itself a list item, with its
+/// header on the `- ` line at `item_depth` and its rows or items one level
+/// deeper
+fn serialize_nested_list_item(
+    py: Python,
+    inner_list: &Bound<'_, PyList>,
+    output: &mut impl Output,
+    item_depth: usize,
+    delimiter: char,
+    ctx: &SerializationContext,
+) -> PyResult<()> {
+    if let Some(table) = detect_tabular(inner_list, ctx)? {
+        // Tabular format: [N]{f1,f2}:
+        write_table(inner_list, table, output, item_depth + 1, delimiter, ctx)?;
+    } else {
+        // Expanded list format: [N]:
+        write_array_header(output, inner_list.len(), delimiter, false);
+        for list_item in inner_list.iter() {
+            write_list_item(py, &list_item, output, item_depth + 1, delimiter, ctx)?;
+        }
+    }
     Ok(())
 }
 
@@ -948,135 +3061,23 @@ fn serialize_expanded_list_with_key(
 fn serialize_expanded_list(
     py: Python,
     list: &Bound<'_, PyList>,
-    output: &mut String,
+    output: &mut impl Output,
     depth: usize,
     delimiter: char,
     is_root: bool,
-    indent_size: usize,
     ctx: &SerializationContext,
 ) -> PyResult<()> {
     let len = list.len();
 
     // Header: [N]:
     if !is_root {
-        output.push('\n');
-        write_indent(output, depth, indent_size);
+        output.push_str(ctx.line_separator.as_str());
+        write_indent(output, depth, ctx);
     }
     write_array_header(output, len, delimiter, false);
 
-    // List items with "- " prefix
     for item in list.iter() {
-        output.push('\n');
-        write_indent(output, depth + 1, indent_size);
-
-        // Check if item is empty dict - encode as bare hyphen without space
-        if let Ok(dict) = item.cast::<PyDict>() {
-            if dict.is_empty() {
-                output.push('-');
-                continue;
-            }
-        }
-
-        output.push_str("- ");
-
-        // Check if item itself is a primitive array
-        if let Ok(inner_list) = item.cast::<PyList>() {
-            if inner_list.iter().all(|x| is_primitive(&x)) {
-                // Inline inner array
-                let inner_len = inner_list.len();
-                write_array_header(output, inner_len, delimiter, true);
-                if inner_len > 0 {
-                    for (i, inner_item) in inner_list.iter().enumerate() {
-                        if i > 0 {
-                            output.push(delimiter);
-                        }
-                        serialize_value(
-                            py,
-                            &inner_item,
-                            output,
-                            depth + 1,
-                            delimiter,
-                            false,
-                            indent_size,
-                            ctx,
-                        )?;
-                    }
-                }
-            } else {
-                // Nested complex array - header should be on same line as hyphen
-                if let Some(fields) = detect_tabular(&inner_list)? {
-                    // Tabular format: [N]{f1,f2}:
-                    write_tabular_header(output, inner_list.len(), delimiter, &fields);
-                    // Rows at depth + 2
-                    for row_item in inner_list.iter() {
-                        output.push('\n');
-                        write_indent(output, depth + 2, indent_size);
-                        let dict = row_item.cast::<PyDict>()?;
-                        for (i, field) in fields.iter().enumerate() {
-                            if i > 0 {
-                                output.push(delimiter);
-                            }
-                            let value = dict.get_item(field)?.unwrap();
-                            serialize_value(
-                                py,
-                                &value,
-                                output,
-                                depth + 2,
-                                delimiter,
-                                false,
-                                indent_size,
-                                ctx,
-                            )?;
-                        }
-                    }
-                } else {
-                    // Expanded list format: [N]:
-                    write_array_header(output, inner_list.len(), delimiter, false);
-                    // Items at depth + 2 with hyphen
-                    for list_item in inner_list.iter() {
-                        output.push('\n');
-                        write_indent(output, depth + 2, indent_size);
-                        output.push_str("- ");
-                        if let Ok(item_dict) = list_item.cast::<PyDict>() {
-                            serialize_list_item_object(
-                                py,
-                                &item_dict,
-                                output,
-                                depth + 2,
-                                delimiter,
-                                indent_size,
-                                ctx,
-                            )?;
-                        } else {
-                            serialize_value(
-                                py,
-                                &list_item,
-                                output,
-                                depth + 2,
-                                delimiter,
-                                false,
-                                indent_size,
-                                ctx,
-                            )?;
-                        }
-                    }
-                }
-            }
-        } else if let Ok(dict) = item.cast::<PyDict>() {
-            // Object as list item - serialize with first field on same line as "-"
-            serialize_list_item_object(py, &dict, output, depth + 1, delimiter, indent_size, ctx)?;
-        } else {
-            serialize_value(
-                py,
-                &item,
-                output,
-                depth + 1,
-                delimiter,
-                false,
-                indent_size,
-                ctx,
-            )?;
-        }
+        write_list_item(py, &item, output, depth + 1, delimiter, ctx)?;
     }
 
     Ok(())
@@ -1086,20 +3087,17 @@ fn serialize_expanded_list(
 fn serialize_list_item_object(
     py: Python,
     dict: &Bound<'_, PyDict>,
-    output: &mut String,
+    output: &mut impl Output,
     depth: usize,
     delimiter: char,
-    indent_size: usize,
     ctx: &SerializationContext,
 ) -> PyResult<()> {
-    let items: Vec<_> = dict.items().iter().collect();
-
-    if items.is_empty() {
-        return Ok(());
-    }
+    let mut items = ctx.ordered_items(dict)?.into_iter();
 
     // First field on same line as "- "
-    let (first_key, first_value) = items[0].extract::<(String, Bound<'_, PyAny>)>()?;
+    let Some((first_key, first_value)) = items.next() else {
+        return Ok(());
+    };
 
     // Check if first value is an array
     if first_value.is_instance_of::<PyList>() {
@@ -1107,102 +3105,50 @@ fn serialize_list_item_object(
             // For both tabular and list format, items must be at depth + 2
             // (one level deeper than the "- " line)
             // So we pass depth + 1 to serialize_array_with_key which will add another +1
-            serialize_array_with_key(
-                py,
-                &first_key,
-                &list,
-                output,
-                depth + 1,
-                delimiter,
-                indent_size,
-                ctx,
-            )?;
+            serialize_array_with_key(py, &first_key, list, output, depth + 1, delimiter, ctx)?;
         }
     } else {
-        serialize_key(&first_key, output);
+        ctx.write_key(&first_key, output);
         output.push(':');
 
         if first_value.is_instance_of::<PyDict>() {
             // Nested object
             if let Ok(nested_dict) = first_value.cast::<PyDict>() {
-                serialize_object(
-                    py,
-                    &nested_dict,
-                    output,
-                    depth + 2,
-                    delimiter,
-                    false,
-                    indent_size,
-                    ctx,
-                )?;
+                serialize_object(nested_dict, output, depth + 2, delimiter, false, ctx)?;
             }
         } else {
             // Primitive
-            output.push(' ');
-            serialize_value(
-                py,
-                &first_value,
-                output,
-                depth + 1,
-                delimiter,
-                false,
-                indent_size,
-                ctx,
-            )?;
+            if !ctx.compact {
+                output.push(' ');
+            }
+            serialize_value(py, &first_value, output, depth + 1, delimiter, false, ctx)?;
         }
     }
 
     // Remaining fields on new lines
-    for item in items.iter().skip(1) {
-        let (key, value) = item.extract::<(String, Bound<'_, PyAny>)>()?;
-
-        output.push('\n');
+    for (key, value) in items {
+        output.push_str(ctx.line_separator.as_str());
         // Fields of list item object are indented one level deeper than the "- " line
-        write_indent(output, depth + 1, indent_size);
+        write_indent(output, depth + 1, ctx);
 
         if value.is_instance_of::<PyList>() {
             if let Ok(list) = value.cast::<PyList>() {
                 // Pass depth+1 so tabular rows are correctly indented at depth+2
-                serialize_array_with_key(
-                    py,
-                    &key,
-                    &list,
-                    output,
-                    depth + 1,
-                    delimiter,
-                    indent_size,
-                    ctx,
-                )?;
+                serialize_array_with_key(py, &key, list, output, depth + 1, delimiter, ctx)?;
             }
         } else {
-            serialize_key(&key, output);
+            ctx.write_key(&key, output);
             output.push(':');
 
             if value.is_instance_of::<PyDict>() {
                 if let Ok(nested_dict) = value.cast::<PyDict>() {
-                    serialize_object(
-                        py,
-                        &nested_dict,
-                        output,
-                        depth + 2,
-                        delimiter,
-                        false,
-                        indent_size,
-                        ctx,
-                    )?;
+                    serialize_object(nested_dict, output, depth + 2, delimiter, false, ctx)?;
                 }
             } else {
-                output.push(' ');
-                serialize_value(
-                    py,
-                    &value,
-                    output,
-                    depth + 1,
-                    delimiter,
-                    false,
-                    indent_size,
-                    ctx,
-                )?;
+                if !ctx.compact {
+                    output.push(' ');
+                }
+                serialize_value(py, &value, output, depth + 1, delimiter, false, ctx)?;
             }
         }
     }
@@ -1210,9 +3156,21 @@ fn serialize_list_item_object(
     Ok(())
 }
 
-/// Write indentation (2 spaces per level per spec default)
-pub fn write_indent(output: &mut String, depth: usize, indent_size: usize) {
-    for _ in 0..depth * indent_size {
-        output.push(' ');
+/// Indentation source for `write_indent`, which copies slices of it
+const SPACES: &str = "                                                                ";
+const TABS: &str = "\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t";
+
+/// Write indentation: `ctx.indent` spaces per level, or one tab per level
+/// with `indent_char="\t"`
+pub fn write_indent(output: &mut impl Output, depth: usize, ctx: &SerializationContext) {
+    let (unit, mut remaining) = match ctx.indent_char {
+        IndentChar::Space => (SPACES, depth * ctx.indent),
+        IndentChar::Tab => (TABS, depth),
+    };
+    // One copy per line in all but very deep documents
+    while remaining > 0 {
+        let len = remaining.min(unit.len());
+        output.push_str(&unit[..len]);
+        remaining -= len;
     }
 }
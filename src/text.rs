@@ -0,0 +1,459 @@
+use std::fmt::Write as FmtWrite;
+
+/// Sink for encoder output: the `String` being built by `serialize`, or a
+/// `ByteCounter` for `estimate_size`. `Send` so large tables can be formatted
+/// with the GIL released.
+pub trait Output: FmtWrite + Send {
+    fn push(&mut self, ch: char) {
+        self.write_char(ch).unwrap();
+    }
+
+    fn push_str(&mut self, s: &str) {
+        self.write_str(s).unwrap();
+    }
+
+    /// Characters written since the last line break
+    fn column(&self) -> usize;
+}
+
+impl Output for String {
+    fn column(&self) -> usize {
+        let line_start = self.rfind('\n').map_or(0, |i| i + 1);
+        self[line_start..].chars().count()
+    }
+}
+
+/// ASCII punctuation that cannot delimit values: TOON syntax, and characters
+/// of numbers (`-+.`), unquoted keys (`_`) and `*/path` aliases (`/*%`)
+pub const RESERVED_DELIMITERS: &str = "\"\\[]{}:-+._/*%";
+
+/// Whether `ch` can delimit array values: tab, or ASCII punctuation outside
+/// `RESERVED_DELIMITERS`. Anything else could be read as part of a value.
+pub fn is_valid_delimiter(ch: char) -> bool {
+    ch == '\t' || (ch.is_ascii_punctuation() && !RESERVED_DELIMITERS.contains(ch))
+}
+
+/// Write a finite, non-zero float in its shortest round-trip form, in plain
+/// decimal notation. `force_point` keeps the `.0` of integral values.
+pub fn write_shortest_float(output: &mut impl Output, f: f64, force_point: bool) {
+    let mut buffer = ryu::Buffer::new();
+    let formatted = buffer.format_finite(f);
+    if formatted.contains('e') {
+        // ryu switches to exponent form for very small and large values;
+        // `Display` picks the same digits but always writes them out
+        if force_point && f.fract() == 0.0 {
+            // `{:.1}` is exact for integral values; keeps the float-ness
+            write!(output, "{:.1}", f).unwrap();
+        } else {
+            write!(output, "{}", f).unwrap();
+        }
+    } else if force_point {
+        output.push_str(formatted);
+    } else {
+        // ryu writes integral values as `1.0`
+        output.push_str(formatted.strip_suffix(".0").unwrap_or(formatted));
+    }
+}
+
+/// Serialize a string with proper quoting and escaping per TOON v3.0 Section 7
+pub fn serialize_string(s: &str, output: &mut impl Output, delimiter: char) {
+    if needs_quoting(s, delimiter) {
+        write_quoted_string(s, output);
+    } else {
+        output.push_str(s);
+    }
+}
+
+/// Write `s` as a quoted, escaped TOON string
+pub fn write_quoted_string(s: &str, output: &mut impl Output) {
+    write_quoted(s, output, false);
+}
+
+/// Like `write_quoted_string`, but with characters outside ASCII written as
+/// `\uXXXX` escapes (surrogate pairs beyond U+FFFF), as `json.dumps` does
+pub fn write_ascii_quoted_string(s: &str, output: &mut impl Output) {
+    write_quoted(s, output, true);
+}
+
+fn write_quoted(s: &str, output: &mut impl Output, ascii: bool) {
+    output.push('"');
+    for ch in s.chars() {
+        match ch {
+            '\\' => output.push_str("\\\\"),
+            '"' => output.push_str("\\\""),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            _ if ascii && !ch.is_ascii() => write_unicode_escape(ch, output),
+            _ => output.push(ch),
+        }
+    }
+    output.push('"');
+}
+
+/// Write `ch` as one `\uXXXX` escape, or two for a surrogate pair
+pub fn write_unicode_escape(ch: char, output: &mut impl Output) {
+    let mut units = [0; 2];
+    for unit in ch.encode_utf16(&mut units) {
+        write!(output, "\\u{:04x}", unit).unwrap();
+    }
+}
+
+/// Error for a `#` comment line where strict decoding expects content
+pub const COMMENT_IN_STRICT_MODE: &str =
+    "Comment lines starting with '#' are only accepted with strict=False";
+
+/// Which of `lines` are full-line `#` comments to lenient decoding. A line
+/// at the depth of a tabular array's rows is a row even if it starts with
+/// `#`, as a cell written by an older encoder may; `table_indent` opens such
+/// an array before the first line.
+pub fn comment_lines(lines: &[&str], table_indent: Option<usize>) -> Vec<bool> {
+    // Indentation of the open tabular header, and of its rows once one is seen
+    let mut table = table_indent.map(|indent| (indent, None));
+    lines
+        .iter()
+        .map(|line| {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                return false;
+            }
+            let indent = line.len() - line.trim_start().len();
+            let is_hash = trimmed.starts_with('#');
+            match table {
+                Some((header, None)) if indent > header => {
+                    table = Some((header, Some(indent)));
+                    return false;
+                }
+                Some((_, Some(rows))) if indent >= rows => return false,
+                // A shallower comment does not end the rows around it
+                Some(_) if is_hash => return true,
+                Some(_) => table = None,
+                None => {}
+            }
+            if is_hash {
+                return true;
+            }
+            // A header with no inline values: `key[N]{fields}:` or `- [N]{fields}:`
+            if trimmed.ends_with("}:") && trimmed.contains('[') {
+                table = Some((indent, None));
+            }
+            false
+        })
+        .collect()
+}
+
+/// Check if a string needs quoting per TOON v3.0 Section 7.2
+pub fn needs_quoting(s: &str, delimiter: char) -> bool {
+    if s.is_empty() {
+        return true;
+    }
+
+    // Check for leading/trailing whitespace
+    if s.starts_with(|c: char| c.is_whitespace()) || s.ends_with(|c: char| c.is_whitespace()) {
+        return true;
+    }
+
+    // Check for reserved keywords
+    if s == "true" || s == "false" || s == "null" {
+        return true;
+    }
+
+    // Check if numeric-like
+    if is_numeric_like(s) {
+        return true;
+    }
+
+    // Check for special characters
+    for ch in s.chars() {
+        match ch {
+            ':' | '"' | '\\' | '[' | ']' | '{' | '}' | '\n' | '\r' | '\t' => return true,
+            _ if ch == delimiter => return true,
+            _ => {}
+        }
+    }
+
+    // Check if starts with hyphen
+    if s.starts_with('-') {
+        return true;
+    }
+
+    // A line starting with `#` is a comment to lenient decoding
+    if s.starts_with('#') {
+        return true;
+    }
+
+    false
+}
+
+/// Check if string looks numeric per TOON v3.0 Section 7.2
+pub fn is_numeric_like(s: &str) -> bool {
+    // Matches: -?\d+(\.\d+)?(e[+-]?\d+)? or 0\d+
+    // Check for leading zero with more digits (e.g., "05"); the whole string
+    // must be digits, so "00000000-0000-..." style identifiers stay bare
+    if s.starts_with('0') && s.len() > 1 && s.chars().all(|c| c.is_ascii_digit()) {
+        return true;
+    }
+
+    // Try to parse as number; `1_000` and `0xff` count too, since lenient
+    // decoding reads them as numbers
+    s.parse::<f64>().is_ok()
+        || strip_digit_separators(s).is_some_and(|number| number.parse::<f64>().is_ok())
+        || split_radix_literal(s).is_some()
+}
+
+/// `s` without the `+` sign of a lenient number such as `+5` or `+1.5`, or
+/// `None` if it has no such sign (`5`, `++5`, `+-5`, `+inf`)
+pub fn strip_plus_sign(s: &str) -> Option<&str> {
+    s.strip_prefix('+')
+        .filter(|unsigned| unsigned.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// Split a hexadecimal, octal or binary integer literal (`0xff`, `-0o17`,
+/// `+0b1010`; prefixes in either case) into whether it is negative, its
+/// radix and its digits. `None` if `s` is not one, e.g. `0xZZ`.
+pub fn split_radix_literal(s: &str) -> Option<(bool, u32, &str)> {
+    let (negative, unsigned) = match s.as_bytes().first()? {
+        b'-' => (true, &s[1..]),
+        b'+' => (false, &s[1..]),
+        _ => (false, s),
+    };
+    let radix = match unsigned.get(..2)? {
+        "0x" | "0X" => 16,
+        "0o" | "0O" => 8,
+        "0b" | "0B" => 2,
+        _ => return None,
+    };
+    let digits = &unsigned[2..];
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    Some((negative, radix, digits))
+}
+
+/// `s` without the underscores separating its digits (`1_000` -> `1000`),
+/// or `None` if it has none or one is not between two digits (`_1`, `1__0`)
+pub fn strip_digit_separators(s: &str) -> Option<String> {
+    if !s.contains('_') {
+        return None;
+    }
+    let bytes = s.as_bytes();
+    let is_digit_at =
+        |i: Option<usize>| i.and_then(|i| bytes.get(i)).is_some_and(u8::is_ascii_digit);
+    for (i, &b) in bytes.iter().enumerate() {
+        if b == b'_' && !(is_digit_at(i.checked_sub(1)) && is_digit_at(Some(i + 1))) {
+            return None;
+        }
+    }
+    Some(s.replace('_', ""))
+}
+
+/// Write array header with delimiter per TOON v3.0 Section 6; `space`
+/// separates the items of an inline array from the colon
+pub fn write_array_header(output: &mut impl Output, len: usize, delimiter: char, space: bool) {
+    write!(output, "[{}", len).unwrap();
+    // Only include delimiter in header if it's not comma (default)
+    if delimiter != ',' {
+        output.push(delimiter);
+    }
+    output.push_str("]:");
+    // Add space for inline arrays with elements
+    if space && len > 0 {
+        output.push(' ');
+    }
+}
+
+/// Serialize object key per TOON v3.0 Section 7.3
+pub fn serialize_key(key: &str, output: &mut impl Output) {
+    // Key can be unquoted if it is an identifier (Unicode XID) with dots
+    if is_valid_unquoted_key(key) {
+        output.push_str(key);
+    } else {
+        write_quoted_string(key, output);
+    }
+}
+
+/// Check if key can be unquoted
+///
+/// Accepts `^[XID_Start_][XID_Continue.]*$`, a Unicode superset of the
+/// spec's ASCII `^[A-Za-z_][\w.]*$`, so keys like `café` or `名前` stay bare.
+pub fn is_valid_unquoted_key(key: &str) -> bool {
+    if key.is_empty() {
+        return false;
+    }
+
+    let mut chars = key.chars();
+    let first = chars.next().unwrap();
+
+    if !unicode_ident::is_xid_start(first) && first != '_' {
+        return false;
+    }
+
+    for ch in chars {
+        if !unicode_ident::is_xid_continue(ch) && ch != '.' {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Split `s` at each unquoted `delimiter` into trimmed values; a trailing
+/// delimiter leaves an empty last value
+pub fn split_by_delimiter(s: &str, delimiter: char) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    let mut prev_ch = '\0';
+
+    // Track byte positions while iterating through characters
+    for (byte_pos, ch) in s.char_indices() {
+        if ch == '"' && prev_ch != '\\' {
+            in_quotes = !in_quotes;
+        } else if ch == delimiter && !in_quotes {
+            let segment = &s[start..byte_pos];
+            result.push(segment.trim());
+            start = byte_pos + ch.len_utf8();
+        }
+        prev_ch = ch;
+    }
+
+    if start < s.len() {
+        result.push(s[start..].trim());
+    } else if start == s.len() && s.ends_with(delimiter) {
+        result.push("");
+    }
+
+    result
+}
+
+/// Whether `line` is a tabular row rather than a `key: value` field: it has
+/// no unquoted colon, or its first delimiter comes before the colon
+pub fn is_tabular_row(line: &str, delimiter: char) -> bool {
+    let mut in_quotes = false;
+    let mut escape_next = false;
+    let mut first_delim_pos = None;
+    let mut first_colon_pos = None;
+    // A cell starting with `[` holds an inline array (`[2|]: a|b`); keys
+    // starting with `[` are quoted, so its colon never ends a key
+    let mut cell_start = true;
+    let mut in_array_cell = false;
+
+    for (i, ch) in line.char_indices() {
+        if escape_next {
+            escape_next = false;
+            continue;
+        }
+
+        if ch == '\\' {
+            escape_next = true;
+            continue;
+        }
+
+        if cell_start && !ch.is_whitespace() {
+            cell_start = false;
+            in_array_cell = ch == '[';
+        }
+
+        if ch == '"' {
+            in_quotes = !in_quotes;
+        } else if !in_quotes {
+            if ch == delimiter {
+                first_delim_pos.get_or_insert(i);
+                cell_start = true;
+            }
+            if ch == ':' && !in_array_cell && first_colon_pos.is_none() {
+                first_colon_pos = Some(i);
+            }
+        }
+    }
+
+    match (first_delim_pos, first_colon_pos) {
+        (None, None) => true,
+        (Some(_), None) => true,
+        (None, Some(_)) => false,
+        (Some(d), Some(c)) => d < c,
+    }
+}
+
+/// Byte offset of the first unquoted `target` (safe to use for slicing `s`)
+pub fn find_unquoted_char(s: &str, target: char) -> Option<usize> {
+    let mut in_quotes = false;
+    let mut escape_next = false;
+
+    for (i, ch) in s.char_indices() {
+        if escape_next {
+            escape_next = false;
+            continue;
+        }
+
+        if ch == '\\' {
+            escape_next = true;
+            continue;
+        }
+
+        if ch == '"' {
+            in_quotes = !in_quotes;
+        } else if ch == target && !in_quotes {
+            return Some(i);
+        }
+    }
+
+    None
+}
+
+/// Resolve the escape sequences of a quoted string's contents (without the
+/// quotes); the error is the message for an invalid or unterminated escape
+pub fn unescape(s: &str) -> Result<String, String> {
+    let mut result = String::new();
+    let mut chars = s.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            match chars.next() {
+                Some('\\') => result.push('\\'),
+                Some('"') => result.push('"'),
+                Some('n') => result.push('\n'),
+                Some('r') => result.push('\r'),
+                Some('t') => result.push('\t'),
+                // Beyond TOON's five escapes, accepted for JSON-derived text
+                // and for `\uXXXX` as written by `ensure_ascii`
+                Some('/') => result.push('/'),
+                Some('b') => result.push('\u{8}'),
+                Some('f') => result.push('\u{c}'),
+                Some('u') => result.push(unicode_escape(&mut chars)?),
+                Some(other) => return Err(format!("Invalid escape sequence: \\{}", other)),
+                None => return Err("Unterminated escape sequence".to_string()),
+            }
+        } else {
+            result.push(ch);
+        }
+    }
+
+    Ok(result)
+}
+
+/// The character of a `\uXXXX` escape whose `\u` was just read from
+/// `chars`, combining a surrogate pair into one
+fn unicode_escape(chars: &mut std::str::Chars) -> Result<char, String> {
+    let first = hex_code_unit(chars)?;
+    let mut units = vec![first];
+    if (0xD800..0xDC00).contains(&first) && chars.as_str().starts_with("\\u") {
+        chars.nth(1);
+        units.push(hex_code_unit(chars)?);
+    }
+    match char::decode_utf16(units).next() {
+        Some(Ok(ch)) => Ok(ch),
+        _ => Err(format!(
+            "Unpaired surrogate in unicode escape: \\u{:04x}",
+            first
+        )),
+    }
+}
+
+fn hex_code_unit(chars: &mut std::str::Chars) -> Result<u16, String> {
+    let digits: String = chars.take(4).collect();
+    if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("Invalid unicode escape: \\u{}", digits));
+    }
+    Ok(u16::from_str_radix(&digits, 16).unwrap())
+}
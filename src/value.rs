@@ -0,0 +1,1021 @@
+use crate::text::{
+    COMMENT_IN_STRICT_MODE, Output, comment_lines, find_unquoted_char, is_tabular_row,
+    is_valid_delimiter, serialize_key, serialize_string, split_by_delimiter, split_radix_literal,
+    strip_digit_separators, strip_plus_sign, unescape, write_array_header,
+    write_ascii_quoted_string, write_shortest_float,
+};
+use serde_json::{Map, Number, Value};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Deepest nesting `decode_value` accepts, as `loads` does by default
+const MAX_DEPTH: usize = 1000;
+
+/// Options for `encode_value`; the defaults are those of `dumps`
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeOptions {
+    /// Spaces per indentation level (default: 2, minimum: 2)
+    pub indent: usize,
+    /// Separator of inline array values and tabular cells (default: `,`): a
+    /// tab or an ASCII punctuation character TOON does not reserve
+    pub delimiter: char,
+    /// Quote strings and keys with non-ASCII characters and write those as
+    /// `\uXXXX` escapes, as JSON does (default: false)
+    pub ensure_ascii: bool,
+    /// Write a list of primitives as `- ` items when its inline line would
+    /// be longer than this many characters (default: `None`, no limit)
+    pub max_line_length: Option<usize>,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        EncodeOptions {
+            indent: 2,
+            delimiter: ',',
+            ensure_ascii: false,
+            max_line_length: None,
+        }
+    }
+}
+
+/// Options for `decode_value`; the defaults are those of `loads`
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeOptions {
+    /// Reject what strict TOON v3.0 forbids: tabs in or uneven indentation,
+    /// blank lines inside arrays, bare empty keys and duplicate tabular
+    /// fields (default: true)
+    pub strict: bool,
+    /// Spaces per indentation level, or `None` (default) to take it from the
+    /// first indented line
+    pub indent: Option<usize>,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        DecodeOptions {
+            strict: true,
+            indent: None,
+        }
+    }
+}
+
+/// Why `decode_value` rejected its input
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    /// 1-based number of the offending line; `None` for an empty input
+    pub line: Option<usize>,
+    /// What is wrong, without the line number
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "TOON parse error at line {}: {}", line, self.message),
+            None => write!(f, "TOON parse error: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Encode a JSON value as TOON, writing what `dumps` writes for the
+/// equivalent Python object.
+///
+/// # Panics
+///
+/// If `options.indent` is less than 2 or `options.delimiter` cannot delimit
+/// values.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use toons::value::{EncodeOptions, encode_value};
+///
+/// let value = json!({"id": 1, "rows": [{"a": 1, "b": "x"}, {"a": 2, "b": "y"}]});
+/// let toon = encode_value(&value, &EncodeOptions::default());
+/// assert_eq!(toon, "id: 1\nrows[2]{a,b}:\n  1,x\n  2,y");
+/// ```
+pub fn encode_value(value: &Value, options: &EncodeOptions) -> String {
+    assert!(options.indent >= 2, "indent must be >= 2");
+    assert!(
+        is_valid_delimiter(options.delimiter),
+        "invalid delimiter {:?}",
+        options.delimiter
+    );
+    let encoder = Encoder {
+        indent: options.indent,
+        delimiter: options.delimiter,
+        ensure_ascii: options.ensure_ascii,
+        max_line_length: options.max_line_length,
+    };
+    let mut output = String::new();
+    match value {
+        Value::Object(map) => encoder.write_fields(&mut output, map, 0, true),
+        Value::Array(items) => encoder.write_array(&mut output, items, 0),
+        _ => encoder.write_primitive(&mut output, value),
+    }
+    output
+}
+
+/// Decode a TOON document into a JSON value, as `loads` decodes it with
+/// default options. Numbers become integers when they fit `i64` or `u64`
+/// and floats otherwise.
+///
+/// With `strict: false` it reads what lenient `loads` reads: `#` comment
+/// lines, any casing of `true`, `false`, `null`, `none` and `nil`, `+5`,
+/// `1_000`, `0xff`/`0o17`/`0b1010`, and rows or inline values written with
+/// another delimiter than their header's. Unlike `loads`, it rejects lines
+/// indented deeper than their block rather than skipping them, and reports
+/// no warnings.
+///
+/// # Panics
+///
+/// If `options.indent` is `Some` value less than 2.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use toons::value::{DecodeOptions, decode_value};
+///
+/// let value = decode_value("tags[2]: a,b\nuser:\n  id: 7", &DecodeOptions::default());
+/// assert_eq!(value, Ok(json!({"tags": ["a", "b"], "user": {"id": 7}})));
+///
+/// let err = decode_value("tags[3]: a,b", &DecodeOptions::default()).unwrap_err();
+/// assert_eq!(err.line, Some(1));
+///
+/// let lenient = DecodeOptions { strict: false, ..DecodeOptions::default() };
+/// let value = decode_value("# limits\nmax: 1_000\nmask: 0xFF\non: True", &lenient);
+/// assert_eq!(value, Ok(json!({"max": 1000, "mask": 255, "on": true})));
+///
+/// let rows = "[1|]{a|b}:\n  1,2";
+/// assert_eq!(decode_value(rows, &lenient), Ok(json!([{"a": 1, "b": 2}])));
+/// let err = decode_value(rows, &DecodeOptions::default()).unwrap_err();
+/// assert_eq!(err.message, "Values are separated by ',' but the header declares '|': 1,2");
+/// ```
+pub fn decode_value(input: &str, options: &DecodeOptions) -> Result<Value, Error> {
+    if let Some(indent) = options.indent {
+        assert!(indent >= 2, "indent must be >= 2");
+    }
+    Decoder::new(input, options)?.decode()
+}
+
+struct Encoder {
+    indent: usize,
+    delimiter: char,
+    ensure_ascii: bool,
+    max_line_length: Option<usize>,
+}
+
+impl Encoder {
+    /// Write an object key or tabular field name
+    fn write_key(&self, output: &mut String, key: &str) {
+        if self.ensure_ascii && !key.is_ascii() {
+            write_ascii_quoted_string(key, output);
+        } else {
+            serialize_key(key, output);
+        }
+    }
+
+    /// Start a line at `depth`
+    fn newline(&self, output: &mut String, depth: usize) {
+        output.push('\n');
+        output.extend(std::iter::repeat_n(' ', depth * self.indent));
+    }
+
+    /// Write the fields of an object, each on its own line at `depth`; with
+    /// `first_inline` the first continues the current line (at the root, or
+    /// after a list item's `- `)
+    fn write_fields(
+        &self,
+        output: &mut String,
+        map: &Map<String, Value>,
+        depth: usize,
+        first_inline: bool,
+    ) {
+        for (i, (key, value)) in map.iter().enumerate() {
+            if i > 0 || !first_inline {
+                self.newline(output, depth);
+            }
+            self.write_field(output, key, value, depth);
+        }
+    }
+
+    /// Write `key` and its value for a field at `depth`; nested fields, rows
+    /// and list items go one level deeper
+    fn write_field(&self, output: &mut String, key: &str, value: &Value, depth: usize) {
+        self.write_key(output, key);
+        match value {
+            Value::Array(items) => self.write_array(output, items, depth),
+            Value::Object(map) => {
+                output.push(':');
+                self.write_fields(output, map, depth + 1, false);
+            }
+            _ => {
+                output.push_str(": ");
+                self.write_primitive(output, value);
+            }
+        }
+    }
+
+    /// Write an array header and its items: inline after the header, or as
+    /// tabular rows or list items at `depth + 1`
+    fn write_array(&self, output: &mut String, items: &[Value], depth: usize) {
+        if items.iter().all(is_primitive) && self.fits_inline(output, items) {
+            write_array_header(output, items.len(), self.delimiter, true);
+            self.write_values(output, items);
+        } else if let Some(fields) = tabular_fields(items) {
+            self.write_table(output, items, &fields, depth + 1);
+        } else {
+            write_array_header(output, items.len(), self.delimiter, false);
+            for item in items {
+                self.write_list_item(output, item, depth + 1);
+            }
+        }
+    }
+
+    /// Whether the inline form of `items`, continuing the last line of
+    /// `output`, stays within `max_line_length`
+    fn fits_inline(&self, output: &String, items: &[Value]) -> bool {
+        let Some(max) = self.max_line_length else {
+            return true;
+        };
+        let mut line = String::new();
+        write_array_header(&mut line, items.len(), self.delimiter, true);
+        self.write_values(&mut line, items);
+        items.is_empty() || output.column() + line.chars().count() <= max
+    }
+
+    /// Write primitives separated by the delimiter
+    fn write_values<'v>(&self, output: &mut String, values: impl IntoIterator<Item = &'v Value>) {
+        for (i, value) in values.into_iter().enumerate() {
+            if i > 0 {
+                output.push(self.delimiter);
+            }
+            self.write_primitive(output, value);
+        }
+    }
+
+    /// Write a `[N]{f1,f2}:` header and one row per object at `row_depth`
+    fn write_table(
+        &self,
+        output: &mut String,
+        items: &[Value],
+        fields: &[&String],
+        row_depth: usize,
+    ) {
+        output.push('[');
+        output.push_str(itoa::Buffer::new().format(items.len()));
+        // Only include delimiter in header if it's not comma (default)
+        if self.delimiter != ',' {
+            output.push(self.delimiter);
+        }
+        output.push_str("]{");
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                output.push(self.delimiter);
+            }
+            self.write_key(output, field);
+        }
+        output.push_str("}:");
+        for item in items {
+            if let Value::Object(map) = item {
+                self.newline(output, row_depth);
+                self.write_values(output, fields.iter().map(|field| &map[field.as_str()]));
+            }
+        }
+    }
+
+    /// Write `item` as an entry of an expanded list at `depth`: an object's
+    /// first field on the `- ` line, or a bare `-` for an empty object
+    fn write_list_item(&self, output: &mut String, item: &Value, depth: usize) {
+        self.newline(output, depth);
+        match item {
+            Value::Object(map) if map.is_empty() => output.push('-'),
+            Value::Object(map) => {
+                output.push_str("- ");
+                self.write_fields(output, map, depth + 1, true);
+            }
+            Value::Array(items) => {
+                output.push_str("- ");
+                self.write_array(output, items, depth);
+            }
+            _ => {
+                output.push_str("- ");
+                self.write_primitive(output, item);
+            }
+        }
+    }
+
+    fn write_primitive(&self, output: &mut String, value: &Value) {
+        match value {
+            Value::Bool(true) => output.push_str("true"),
+            Value::Bool(false) => output.push_str("false"),
+            Value::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    output.push_str(itoa::Buffer::new().format(i));
+                } else if let Some(u) = n.as_u64() {
+                    output.push_str(itoa::Buffer::new().format(u));
+                } else {
+                    // serde_json numbers are finite; -0 is written as 0
+                    match n.as_f64() {
+                        Some(f) if f != 0.0 => write_shortest_float(output, f, false),
+                        _ => output.push('0'),
+                    }
+                }
+            }
+            Value::String(s) if self.ensure_ascii && !s.is_ascii() => {
+                write_ascii_quoted_string(s, output)
+            }
+            Value::String(s) => serialize_string(s, output, self.delimiter),
+            _ => output.push_str("null"),
+        }
+    }
+}
+
+fn is_primitive(value: &Value) -> bool {
+    !matches!(value, Value::Array(_) | Value::Object(_))
+}
+
+/// The fields of `items` if they can be written as a table: objects with
+/// the first one's keys (in any order) and only primitive values
+fn tabular_fields(items: &[Value]) -> Option<Vec<&String>> {
+    let Some(Value::Object(first)) = items.first() else {
+        return None;
+    };
+    if first.is_empty() {
+        return None;
+    }
+    let fields: Vec<&String> = first.keys().collect();
+    let fits = |item: &Value| match item {
+        Value::Object(map) => {
+            map.len() == fields.len()
+                && fields
+                    .iter()
+                    .all(|field| map.get(field.as_str()).is_some_and(is_primitive))
+        }
+        _ => false,
+    };
+    items.iter().all(fits).then_some(fields)
+}
+
+/// A line of the input
+struct Line<'a> {
+    /// 1-based line number
+    number: usize,
+    /// Indentation level
+    depth: usize,
+    /// The line without its indentation and trailing whitespace; empty for
+    /// blank lines
+    content: &'a str,
+}
+
+/// An array header: `[N]:`, `[N|]{a|b}:` and so on
+struct Header<'a> {
+    length: usize,
+    delimiter: char,
+    fields: Option<Vec<String>>,
+    /// What follows the colon: the values of an inline array
+    values: &'a str,
+}
+
+struct Decoder<'a> {
+    lines: Vec<Line<'a>>,
+    pos: usize,
+    strict: bool,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(input: &'a str, options: &DecodeOptions) -> Result<Self, Error> {
+        let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+        let raw: Vec<&str> = input.lines().collect();
+        // Lenient mode drops full-line `#` comments, as `loads` does
+        let comments = if options.strict {
+            vec![false; raw.len()]
+        } else {
+            comment_lines(&raw, None)
+        };
+        let indent_size = options.indent.unwrap_or_else(|| {
+            // The first indented line sets the indentation, 2 if none is
+            raw.iter()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.len() - line.trim_start_matches(' ').len())
+                .find(|&spaces| spaces > 0)
+                .unwrap_or(2)
+        });
+
+        let mut lines = Vec::with_capacity(raw.len());
+        for (i, line) in raw.into_iter().enumerate() {
+            if comments[i] {
+                continue;
+            }
+            let body = line.trim_start_matches([' ', '\t']);
+            let content = body.trim_end();
+            let error = |message: String| Error {
+                line: Some(i + 1),
+                message,
+            };
+            let indent = &line[..line.len() - body.len()];
+            let width = if content.is_empty() {
+                0
+            } else if indent.contains('\t') {
+                if options.strict {
+                    return Err(error("Tabs are not allowed in indentation".to_string()));
+                }
+                // A tab counts as one level
+                indent
+                    .chars()
+                    .map(|c| if c == '\t' { indent_size } else { 1 })
+                    .sum()
+            } else {
+                indent.len()
+            };
+            if options.strict && !width.is_multiple_of(indent_size) {
+                return Err(error(format!(
+                    "Indentation {} is not a multiple of indent size {}",
+                    width, indent_size
+                )));
+            }
+            lines.push(Line {
+                number: i + 1,
+                depth: width / indent_size,
+                content,
+            });
+        }
+
+        Ok(Decoder {
+            lines,
+            pos: 0,
+            strict: options.strict,
+        })
+    }
+
+    /// An `Error` at the line with index `idx`
+    fn err(&self, idx: usize, message: impl Into<String>) -> Error {
+        Error {
+            line: self
+                .lines
+                .get(idx)
+                .or(self.lines.last())
+                .map(|line| line.number),
+            message: message.into(),
+        }
+    }
+
+    /// Index of the first non-blank line from `self.pos`
+    fn next_line(&self) -> Option<usize> {
+        (self.pos..self.lines.len()).find(|&i| !self.lines[i].content.is_empty())
+    }
+
+    fn decode(mut self) -> Result<Value, Error> {
+        let Some(idx) = self.next_line() else {
+            // Empty document → empty object per TOON v3.0 Section 5
+            return Ok(Value::Object(Map::new()));
+        };
+        let line = &self.lines[idx];
+        if line.depth > 0 {
+            return Err(self.err(idx, "Unexpected indentation"));
+        }
+        let content = line.content;
+
+        let value = if content.starts_with('[') {
+            // Root array
+            self.pos = idx + 1;
+            let header = self.parse_header(idx, content)?;
+            self.array(idx, header, 1)?
+        } else if find_unquoted_char(content, ':').is_none() {
+            self.pos = idx + 1;
+            if self.next_line().is_none() {
+                return self.primitive(idx, content);
+            }
+            if self.strict && content.starts_with('#') {
+                return Err(self.err(idx, COMMENT_IN_STRICT_MODE));
+            }
+            if !content.starts_with('-') {
+                return Err(self.err(
+                    idx,
+                    "A root primitive must be a single line; \
+                     write line breaks in a quoted string as \\n",
+                ));
+            }
+            return Err(self.err(idx, format!("Missing colon in line: {}", content)));
+        } else {
+            self.pos = idx;
+            Value::Object(self.object(0)?)
+        };
+
+        match self.next_line() {
+            Some(idx) => Err(self.err(idx, "Unexpected indentation")),
+            None => Ok(value),
+        }
+    }
+
+    /// Decode the fields at `depth` from `self.pos`, up to the first
+    /// shallower line
+    fn object(&mut self, depth: usize) -> Result<Map<String, Value>, Error> {
+        let mut map = Map::new();
+        while let Some(idx) = self.next_line() {
+            let line = &self.lines[idx];
+            if line.depth < depth {
+                break;
+            }
+            let content = line.content;
+            if line.depth > depth {
+                return Err(self.err(idx, "Unexpected indentation"));
+            }
+            if content == "-" || content.starts_with("- ") {
+                return Err(self.err(idx, format!("Missing colon in line: {}", content)));
+            }
+            if self.strict && content.starts_with('#') && find_unquoted_char(content, ':').is_none()
+            {
+                return Err(self.err(idx, COMMENT_IN_STRICT_MODE));
+            }
+            self.pos = idx + 1;
+            let (key, value) = self.field(idx, content, depth)?;
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+
+    /// Decode the field written as `content` on line `idx`, at `depth`; its
+    /// nested fields, rows or list items follow from `self.pos`
+    fn field(
+        &mut self,
+        idx: usize,
+        content: &'a str,
+        depth: usize,
+    ) -> Result<(String, Value), Error> {
+        if depth >= MAX_DEPTH {
+            return Err(self.err(
+                idx,
+                format!("maximum nesting depth exceeded ({})", MAX_DEPTH),
+            ));
+        }
+        let key_end = if content.starts_with('"') {
+            closing_quote(content).ok_or_else(|| self.err(idx, "Unterminated string"))? + 1
+        } else {
+            content.find(['[', ':']).unwrap_or(content.len())
+        };
+        let key = self.key(idx, &content[..key_end])?;
+        let rest = content[key_end..].trim_start();
+
+        if rest.starts_with('[') {
+            let header = self.parse_header(idx, rest)?;
+            let value = self.array(idx, header, depth + 1)?;
+            return Ok((key, value));
+        }
+        let Some(value) = rest.strip_prefix(':') else {
+            return Err(self.err(idx, format!("Missing colon in line: {}", content)));
+        };
+        let value = value.trim();
+        if !value.is_empty() {
+            return Ok((key, self.primitive(idx, value)?));
+        }
+        // Nested object, or an empty one
+        let nested = match self.next_line() {
+            Some(next) if self.lines[next].depth > depth => self.object(depth + 1)?,
+            _ => Map::new(),
+        };
+        Ok((key, Value::Object(nested)))
+    }
+
+    fn key(&self, idx: usize, s: &str) -> Result<String, Error> {
+        let trimmed = s.trim();
+        if trimmed.is_empty() && self.strict {
+            return Err(self.err(idx, "Empty key must be quoted as \"\""));
+        }
+        match trimmed.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+            Some(inner) => unescape(inner).map_err(|message| self.err(idx, message)),
+            None => Ok(trimmed.to_string()),
+        }
+    }
+
+    /// Parse the array header `s` (starting at its `[`) on line `idx`
+    fn parse_header(&self, idx: usize, s: &'a str) -> Result<Header<'a>, Error> {
+        let close = s
+            .find(']')
+            .ok_or_else(|| self.err(idx, "Invalid array header: missing ']'"))?;
+        let inner = &s[1..close];
+        let (digits, delimiter) = match inner.char_indices().last() {
+            Some((i, ch)) if !ch.is_ascii_digit() => (&inner[..i], ch),
+            _ => (inner, ','),
+        };
+        if !is_valid_delimiter(delimiter) {
+            return Err(self.err(
+                idx,
+                format!("Invalid delimiter in array header: {:?}", delimiter),
+            ));
+        }
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(self.err(idx, format!("Invalid array length: {}", digits)));
+        }
+        let length = digits
+            .parse()
+            .map_err(|_| self.err(idx, format!("Invalid array length: {}", digits)))?;
+
+        let mut rest = &s[close + 1..];
+        let fields = match rest.strip_prefix('{') {
+            Some(field_list) => {
+                let end = find_unquoted_char(field_list, '}')
+                    .ok_or_else(|| self.err(idx, "Invalid field list: missing '}'"))?;
+                let mut seen = HashSet::new();
+                let mut fields = Vec::new();
+                for field in split_by_delimiter(&field_list[..end], delimiter) {
+                    let name = self.key(idx, field)?;
+                    if self.strict && !seen.insert(name.clone()) {
+                        return Err(
+                            self.err(idx, format!("Duplicate field name in header: {}", name))
+                        );
+                    }
+                    fields.push(name);
+                }
+                rest = &field_list[end + 1..];
+                Some(fields)
+            }
+            None => None,
+        };
+        let values = rest
+            .strip_prefix(':')
+            .ok_or_else(|| self.err(idx, "Invalid array header: missing ':'"))?;
+        Ok(Header {
+            length,
+            delimiter,
+            fields,
+            values: values.trim(),
+        })
+    }
+
+    /// Decode the array whose header is on line `idx`: its inline values, or
+    /// its rows or list items at `depth` from `self.pos`
+    fn array(&mut self, idx: usize, header: Header<'a>, depth: usize) -> Result<Value, Error> {
+        let items = if let Some(fields) = &header.fields {
+            self.rows(fields, header.delimiter, depth)?
+        } else if !header.values.is_empty() {
+            let values = self.split_row(idx, header.values, header.delimiter, header.length)?;
+            if values.len() != header.length {
+                return Err(self.err(
+                    idx,
+                    format!(
+                        "Array declared length {} but found {} elements: {}",
+                        header.length,
+                        values.len(),
+                        header.values
+                    ),
+                ));
+            }
+            values
+                .into_iter()
+                .map(|value| self.primitive(idx, value))
+                .collect::<Result<_, _>>()?
+        } else if header.length == 0 {
+            Vec::new()
+        } else {
+            self.list_items(depth)?
+        };
+        if items.len() != header.length {
+            return Err(self.err(
+                idx,
+                format!(
+                    "Array declared length {} but found {} elements",
+                    header.length,
+                    items.len()
+                ),
+            ));
+        }
+        Ok(Value::Array(items))
+    }
+
+    /// Index of the next line belonging to an array at `depth` if `accept`
+    /// takes it, checking for blank lines before it
+    fn next_array_line(
+        &self,
+        depth: usize,
+        accept: impl Fn(&str) -> bool,
+    ) -> Result<Option<usize>, Error> {
+        let Some(idx) = self.next_line() else {
+            return Ok(None);
+        };
+        let line = &self.lines[idx];
+        if line.depth != depth || !accept(line.content) {
+            return Ok(None);
+        }
+        if self.strict && idx > self.pos {
+            return Err(self.err(self.pos, "Blank line inside array"));
+        }
+        Ok(Some(idx))
+    }
+
+    /// Decode the tabular rows at `depth` from `self.pos`
+    fn rows(
+        &mut self,
+        fields: &[String],
+        delimiter: char,
+        depth: usize,
+    ) -> Result<Vec<Value>, Error> {
+        let mut rows = Vec::new();
+        while let Some(idx) =
+            self.next_array_line(depth, |content| is_tabular_row(content, delimiter))?
+        {
+            self.pos = idx + 1;
+            let content = self.lines[idx].content;
+            let cells = self.split_row(idx, content, delimiter, fields.len())?;
+            if cells.len() != fields.len() {
+                return Err(self.err(
+                    idx,
+                    format!(
+                        "Tabular row has {} values but header defines {} fields: {}",
+                        cells.len(),
+                        fields.len(),
+                        content
+                    ),
+                ));
+            }
+            let mut row = Map::with_capacity(fields.len());
+            for (field, cell) in fields.iter().zip(cells) {
+                row.insert(field.clone(), self.primitive(idx, cell)?);
+            }
+            rows.push(Value::Object(row));
+        }
+        Ok(rows)
+    }
+
+    /// Split the `expected` values of an inline array or tabular row on
+    /// line `idx`. Values that only split right on another delimiter than
+    /// the header's were likely written with it: an error in strict mode,
+    /// split on it otherwise, as `loads` does.
+    fn split_row<'s>(
+        &self,
+        idx: usize,
+        s: &'s str,
+        delimiter: char,
+        expected: usize,
+    ) -> Result<Vec<&'s str>, Error> {
+        let values = split_by_delimiter(s, delimiter);
+        if values.len() == expected || expected < 2 {
+            return Ok(values);
+        }
+        let Some(actual) = [',', '|', '\t']
+            .into_iter()
+            .filter(|&d| d != delimiter)
+            .find(|&d| split_by_delimiter(s, d).len() == expected)
+        else {
+            return Ok(values);
+        };
+        if self.strict {
+            return Err(self.err(
+                idx,
+                format!(
+                    "Values are separated by {:?} but the header declares {:?}: {}",
+                    actual, delimiter, s
+                ),
+            ));
+        }
+        Ok(split_by_delimiter(s, actual))
+    }
+
+    /// Decode the `- ` list items at `depth` from `self.pos`
+    fn list_items(&mut self, depth: usize) -> Result<Vec<Value>, Error> {
+        let mut items = Vec::new();
+        while let Some(idx) =
+            self.next_array_line(depth, |content| content == "-" || content.starts_with("- "))?
+        {
+            self.pos = idx + 1;
+            items.push(self.list_item(idx, depth)?);
+        }
+        Ok(items)
+    }
+
+    /// Decode the list item on line `idx`, at `depth`
+    fn list_item(&mut self, idx: usize, depth: usize) -> Result<Value, Error> {
+        let Some(body) = self.lines[idx].content.strip_prefix("- ") else {
+            // A bare `-` is an empty object
+            return Ok(Value::Object(Map::new()));
+        };
+        let body = body.trim_start();
+        if body.starts_with('[') {
+            let header = self.parse_header(idx, body)?;
+            return self.array(idx, header, depth + 1);
+        }
+        if find_unquoted_char(body, ':').is_none() {
+            return self.primitive(idx, body);
+        }
+        // An object: its first field on the `- ` line, the rest below it
+        let (key, value) = self.field(idx, body, depth + 1)?;
+        let mut map = Map::new();
+        map.insert(key, value);
+        map.extend(self.object(depth + 1)?);
+        Ok(Value::Object(map))
+    }
+
+    fn primitive(&self, idx: usize, token: &str) -> Result<Value, Error> {
+        let token = token.trim();
+        if let Some(quoted) = token.strip_prefix('"') {
+            let inner = quoted
+                .strip_suffix('"')
+                .ok_or_else(|| self.err(idx, "Unterminated string"))?;
+            return unescape(inner)
+                .map(Value::String)
+                .map_err(|message| self.err(idx, message));
+        }
+        Ok(match self.standard_literal(token) {
+            "null" => Some(Value::Null),
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ if self.strict => number(token),
+            // Lenient mode reads `0xff`, `1_000` and `+5` as numbers
+            _ => match split_radix_literal(token) {
+                Some((negative, radix, digits)) => radix_number(negative, radix, digits),
+                None => {
+                    let unsigned = strip_plus_sign(token).unwrap_or(token);
+                    match strip_digit_separators(unsigned) {
+                        Some(number_text) => number(&number_text),
+                        None => number(unsigned),
+                    }
+                }
+            },
+        }
+        .unwrap_or_else(|| Value::String(token.to_string())))
+    }
+
+    /// The standard literal (`null`, `true` or `false`) an unquoted token
+    /// stands for: in lenient mode any casing of `true`, `false`, `null`,
+    /// `none` or `nil`, as `loads` reads them. Other tokens are returned
+    /// unchanged.
+    fn standard_literal<'t>(&self, token: &'t str) -> &'t str {
+        if self.strict {
+            token
+        } else if token.eq_ignore_ascii_case("true") {
+            "true"
+        } else if token.eq_ignore_ascii_case("false") {
+            "false"
+        } else if ["null", "none", "nil"]
+            .iter()
+            .any(|null| token.eq_ignore_ascii_case(null))
+        {
+            "null"
+        } else {
+            token
+        }
+    }
+}
+
+/// Byte offset of the quote closing the string that `s` starts with
+fn closing_quote(s: &str) -> Option<usize> {
+    let mut escape_next = false;
+    for (i, ch) in s.char_indices().skip(1) {
+        if escape_next {
+            escape_next = false;
+        } else if ch == '\\' {
+            escape_next = true;
+        } else if ch == '"' {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// The integer a radix literal's `digits` spell; one too large for `u64`
+/// becomes the nearest float, as a decimal one does
+fn radix_number(negative: bool, radix: u32, digits: &str) -> Option<Value> {
+    if let Ok(i) = i64::from_str_radix(digits, radix) {
+        return Some(Value::from(if negative { -i } else { i }));
+    }
+    if !negative && let Ok(u) = u64::from_str_radix(digits, radix) {
+        return Some(Value::from(u));
+    }
+    let magnitude = digits.chars().fold(0.0, |acc, c| {
+        acc * f64::from(radix) + f64::from(c.to_digit(radix).unwrap_or(0))
+    });
+    Number::from_f64(if negative { -magnitude } else { magnitude }).map(Value::Number)
+}
+
+/// The number an unquoted token spells, if any; `05` and `1e999` stay strings
+fn number(token: &str) -> Option<Value> {
+    let unsigned = token.strip_prefix('-').unwrap_or(token);
+    if !unsigned.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    if unsigned.len() > 1 && unsigned.starts_with('0') && unsigned.as_bytes()[1].is_ascii_digit() {
+        return None;
+    }
+    if let Ok(i) = token.parse::<i64>() {
+        return Some(Value::from(i));
+    }
+    if let Ok(u) = token.parse::<u64>() {
+        return Some(Value::from(u));
+    }
+    let f = token.parse::<f64>().ok()?;
+    // -0.0 decodes as 0
+    Number::from_f64(if f == 0.0 { 0.0 } else { f }).map(Value::Number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn strict(input: &str) -> Result<Value, Error> {
+        decode_value(input, &DecodeOptions::default())
+    }
+
+    fn lenient(input: &str) -> Result<Value, Error> {
+        let options = DecodeOptions {
+            strict: false,
+            ..DecodeOptions::default()
+        };
+        decode_value(input, &options)
+    }
+
+    #[test]
+    fn strict_rejects_what_lenient_reads() {
+        let cases = [
+            ("# note\na: 1", json!({"a": 1})),
+            ("a: True", json!({"a": true})),
+            ("a: NONE", json!({"a": null})),
+            ("a: +5", json!({"a": 5})),
+            ("a: 1_000", json!({"a": 1000})),
+            ("a: 0xFF", json!({"a": 255})),
+            ("a: -0o17", json!({"a": -15})),
+            ("[2|]: 1,2", json!([1, 2])),
+        ];
+        for (input, expected) in cases {
+            assert_ne!(strict(input), Ok(expected.clone()), "{input:?}");
+            assert_eq!(lenient(input), Ok(expected), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn strict_errors_report_their_line() {
+        let err = strict("a:\n\tb: 1").unwrap_err();
+        assert_eq!(err.line, Some(2));
+        let err = strict("a[2]:\n  - 1\n\n  - 2").unwrap_err();
+        assert_eq!(err.line, Some(3));
+        let err = strict("a[3]: 1,2").unwrap_err();
+        assert_eq!(err.line, Some(1));
+        assert!(err.to_string().starts_with("TOON parse error at line 1: "));
+        let err = strict("a: \"open").unwrap_err();
+        assert_eq!(err.line, Some(1));
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(strict("a: -0"), Ok(json!({"a": 0})));
+        assert_eq!(strict("a: 1.50"), Ok(json!({"a": 1.5})));
+        assert_eq!(strict("a: 1e3"), Ok(json!({"a": 1000.0})));
+        assert_eq!(strict("a: 05"), Ok(json!({"a": "05"})));
+        assert_eq!(strict("a: 1e999"), Ok(json!({"a": "1e999"})));
+        assert_eq!(
+            strict("a: 18446744073709551615"),
+            Ok(json!({"a": u64::MAX}))
+        );
+        assert_eq!(
+            strict("a: 18446744073709551616"),
+            Ok(json!({"a": 18446744073709551616.0}))
+        );
+        assert_eq!(
+            lenient("a: 0x10000000000000000"),
+            Ok(json!({"a": 18446744073709551616.0}))
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(strict(r#"a: "x\n\"\\""#), Ok(json!({"a": "x\n\"\\"})));
+        assert_eq!(strict(r#"a: "\u00e9""#), Ok(json!({"a": "é"})));
+        assert_eq!(lenient(r#"a: "\u00e9""#), Ok(json!({"a": "é"})));
+        assert!(strict(r#"a: "\U00000041""#).is_err());
+        assert!(strict(r#"a: "\q""#).is_err());
+    }
+
+    #[test]
+    fn round_trips() {
+        let values = [
+            json!({}),
+            json!([]),
+            json!(null),
+            json!("true"),
+            json!({"a": "", "b": " padded ", "c": "x: y", "d": "-"}),
+            json!({"ids": [1, 2, 3], "mixed": [1, "a", null, [true]]}),
+            json!({"rows": [{"a": 1, "b": "x,y"}, {"a": 2, "b": "z"}]}),
+            json!({"nested": {"deep": {"list": [{"k": [1, 2]}, {"k": []}]}}}),
+            json!({"odd key": -1.5, "": 0, "é": "ünïcode", "n": u64::MAX}),
+        ];
+        for value in values {
+            for options in [
+                EncodeOptions::default(),
+                EncodeOptions {
+                    indent: 4,
+                    delimiter: '|',
+                    ensure_ascii: true,
+                    max_line_length: Some(4),
+                },
+            ] {
+                let toon = encode_value(&value, &options);
+                let decoded = decode_value(&toon, &DecodeOptions::default());
+                assert_eq!(decoded, Ok(value.clone()), "{toon:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,1826 @@
+"""
+Tests for decoder behaviour not covered by the specification fixtures.
+"""
+
+import datetime
+import gzip
+import io
+import json
+import math
+from collections import OrderedDict
+from typing import Any, Dict, List, Optional, TypedDict
+
+import pytest
+
+import toons
+
+
+class TestIndentedDocuments:
+    """Documents whose content is uniformly indented (e.g. extracted from a
+    larger structure) are parsed relative to their minimum indentation."""
+
+    @pytest.mark.parametrize("strict", [True, False])
+    def test_indented_root_tabular_array(self, strict):
+        """An indented `[N]{fields}:` header is recognized as a root array."""
+        toon_str = "    [2]{id,name}:\n      1,Alice\n      2,Bob"
+        assert toons.loads(toon_str, strict=strict) == [
+            {"id": 1, "name": "Alice"},
+            {"id": 2, "name": "Bob"},
+        ]
+
+    def test_indented_root_inline_array(self):
+        """An indented inline root array is recognized."""
+        assert toons.loads("  [3]: a,b,c") == ["a", "b", "c"]
+
+    def test_indented_root_expanded_array(self):
+        """An indented expanded root array is recognized."""
+        toon_str = "  [2]:\n    - x\n    - y"
+        assert toons.loads(toon_str) == ["x", "y"]
+
+    def test_indented_object(self):
+        """Uniformly indented objects keep their nesting."""
+        toon_str = "  user:\n    name: Alice\n  active: true"
+        assert toons.loads(toon_str) == {
+            "user": {"name": "Alice"},
+            "active": True,
+        }
+
+    def test_indented_root_array_blank_line_strict(self):
+        """Strict-mode array rules still apply after dedenting."""
+        toon_str = "  [2]{a,b}:\n\n    1,2\n    3,4"
+        with pytest.raises(toons.ToonDecodeError, match="Blank line"):
+            toons.loads(toon_str)
+
+
+class TestCollectWarnings:
+    """`collect_warnings=True` returns `(value, warnings)` so lenient-mode
+    recoveries are visible to the caller."""
+
+    def test_default_returns_plain_value(self):
+        assert toons.loads("a: 1") == {"a": 1}
+
+    def test_clean_document_has_no_warnings(self):
+        value, warnings = toons.loads("a: 1\nb[2]: x,y", collect_warnings=True)
+        assert value == {"a": 1, "b": ["x", "y"]}
+        assert warnings == []
+
+    def test_blank_line_inside_array(self):
+        toon_str = "[3]:\n  - 1\n\n  - 2\n  - 3"
+        value, warnings = toons.loads(
+            toon_str, strict=False, collect_warnings=True
+        )
+        assert value == [1, 2, 3]
+        assert warnings == [(3, "Blank line inside array")]
+
+    def test_blank_line_inside_tabular_array(self):
+        toon_str = "rows[2]{a,b}:\n  1,2\n\n  3,4"
+        value, warnings = toons.loads(
+            toon_str, strict=False, collect_warnings=True
+        )
+        assert value == {"rows": [{"a": 1, "b": 2}, {"a": 3, "b": 4}]}
+        assert warnings == [(3, "Blank line inside array")]
+
+    def test_non_multiple_indentation(self):
+        toon_str = "a:\n  b:\n     c: 1"
+        _, warnings = toons.loads(
+            toon_str, strict=False, collect_warnings=True
+        )
+        assert len(warnings) == 1
+        line, message = warnings[0]
+        assert line == 3
+        assert "not a multiple of indent size" in message
+
+    def test_strict_mode_still_raises(self):
+        with pytest.raises(toons.ToonDecodeError):
+            toons.loads("[2]:\n  - 1\n\n  - 2", collect_warnings=True)
+
+    def test_load_collects_warnings(self, tmp_path):
+        path = tmp_path / "data.toon"
+        path.write_text("[2]:\n  - 1\n\n  - 2")
+        with open(path) as f:
+            value, warnings = toons.load(
+                f, strict=False, collect_warnings=True
+            )
+        assert value == [1, 2]
+        assert warnings == [(3, "Blank line inside array")]
+
+
+class TestBareHyphen:
+    """Test the bare_hyphen option for `-` list items with no value."""
+
+    def test_default_is_empty_object(self):
+        assert toons.loads("[2]:\n  -\n  - 1") == [{}, 1]
+
+    def test_null_mode(self):
+        toon_str = "[2]:\n  -\n  - 1"
+        assert toons.loads(toon_str, bare_hyphen="null") == [None, 1]
+
+    def test_nested_array_null_mode(self):
+        toon_str = "items[3]:\n  - a\n  -\n  - c"
+        assert toons.loads(toon_str, bare_hyphen="null") == {
+            "items": ["a", None, "c"]
+        }
+
+    def test_empty_dict_roundtrip_default(self):
+        """An empty dict inside a mixed list survives the round trip."""
+        data = {"items": [{}, 1, "x"]}
+        assert toons.loads(toons.dumps(data)) == data
+
+    def test_invalid_mode(self):
+        with pytest.raises(ValueError, match="bare_hyphen"):
+            toons.loads("[1]:\n  -", bare_hyphen="none")
+
+
+class TestIntegralFloatTokens:
+    """Numbers written with a decimal point decode as float"""
+
+    @pytest.mark.parametrize(
+        "text,expected", [("1.0", 1.0), ("-3.00", -3.0), ("0.0", 0.0)]
+    )
+    def test_point_keeps_float(self, text, expected):
+        value = toons.loads(f"x: {text}")["x"]
+        assert type(value) is float
+        assert value == expected
+
+    def test_plain_integer_is_int(self):
+        assert type(toons.loads("x: 1")["x"]) is int
+
+
+class TestBytesInput:
+    """loads/load accept UTF-8 bytes as well as str"""
+
+    def test_loads_bytes(self):
+        assert toons.loads(b"name: Alice") == {"name": "Alice"}
+
+    def test_loads_bytearray(self):
+        assert toons.loads(bytearray(b"[2]: 1,2")) == [1, 2]
+
+    def test_loads_non_ascii_bytes(self):
+        data = {"city": "Zürich", "greeting": "こんにちは"}
+        encoded = toons.dumps(data).encode("utf-8")
+        assert toons.loads(encoded) == data
+
+    def test_bytes_bom_stripped(self):
+        assert toons.loads(b"\xef\xbb\xbfname: Alice") == {"name": "Alice"}
+
+    def test_invalid_utf8_raises(self):
+        with pytest.raises(toons.ToonDecodeError, match="UTF-8") as exc:
+            toons.loads(b"a: 1\nb: \xff\xfe")
+        assert isinstance(exc.value, ValueError)
+        assert exc.value.line == 2
+
+    def test_bytes_options_apply(self):
+        result = toons.loads(
+            b"items[2]:\n  - 1\n\n  - 2", strict=False, collect_warnings=True
+        )
+        assert result == ({"items": [1, 2]}, [(3, "Blank line inside array")])
+
+    @pytest.mark.parametrize("value", [1, None, ["a: 1"]])
+    def test_other_types_rejected(self, value):
+        with pytest.raises(TypeError, match="str, bytes or bytearray"):
+            toons.loads(value)
+
+    def test_load_binary_file(self, tmp_path):
+        path = tmp_path / "data.toon"
+        path.write_bytes("name: Zoë\nage: 30".encode("utf-8"))
+        with open(path, "rb") as fp:
+            assert toons.load(fp) == {"name": "Zoë", "age": 30}
+
+    def test_load_bytesio(self):
+        assert toons.load(io.BytesIO(b"[3]: a,b,c")) == ["a", "b", "c"]
+
+
+class LineStream:
+    """A readable stream that can only be iterated line by line."""
+
+    def __init__(self, lines):
+        self._lines = lines
+
+    def readable(self):
+        return True
+
+    def __iter__(self):
+        return iter(self._lines)
+
+
+class TestLoadReaders:
+    """load accepts whatever shape of reader it is given"""
+
+    DOC = "name: Zoë\ntags[2]: a,b\n"
+    DATA = {"name": "Zoë", "tags": ["a", "b"]}
+
+    def test_stringio(self):
+        assert toons.load(io.StringIO(self.DOC)) == self.DATA
+
+    def test_bytesio(self):
+        assert toons.load(io.BytesIO(self.DOC.encode())) == self.DATA
+
+    def test_gzip_text_wrapper(self, tmp_path):
+        path = tmp_path / "data.toon.gz"
+        with gzip.open(path, "wt", encoding="utf-8") as f:
+            toons.dump(self.DATA, f)
+        with gzip.open(path, "rt", encoding="utf-8") as f:
+            assert toons.load(f) == self.DATA
+        with gzip.open(path, "rb") as f:
+            assert toons.load(f) == self.DATA
+
+    def test_text_wrapper_over_bytes(self):
+        raw = io.BytesIO(self.DOC.encode())
+        assert toons.load(io.TextIOWrapper(raw, "utf-8")) == self.DATA
+
+    def test_readable_stream_without_read(self):
+        lines = self.DOC.splitlines(keepends=True)
+        assert toons.load(LineStream(lines)) == self.DATA
+
+    def test_iterator_of_bytes_lines(self):
+        lines = (line.encode() for line in self.DOC.splitlines(True))
+        assert toons.load(lines) == self.DATA
+
+    def test_empty_iterator(self):
+        assert toons.load(iter([])) == {}
+
+
+class TestByteOrderMark:
+    """A leading BOM is not part of the document"""
+
+    def test_bom_not_in_first_key(self):
+        result = toons.loads("\ufeffname: Alice")
+        assert result == {"name": "Alice"}
+        assert list(result) == ["name"]
+
+    def test_bom_before_root_array(self):
+        assert toons.loads("\ufeff[2]: 1,2") == [1, 2]
+
+    def test_bom_before_root_primitive(self):
+        assert toons.loads("\ufeff42") == 42
+
+    def test_bom_only_stripped_at_start(self):
+        assert toons.loads('a: "x\ufeff"') == {"a": "x\ufeff"}
+
+    def test_load_utf8_sig_file(self, tmp_path):
+        path = tmp_path / "bom.toon"
+        path.write_text("name: Alice\nage: 30", encoding="utf-8-sig")
+        with open(path, encoding="utf-8") as fp:
+            assert toons.load(fp) == {"name": "Alice", "age": 30}
+
+
+class TestLineEndings:
+    """LF, CRLF and lone CR line endings parse identically"""
+
+    DOCUMENT = [
+        "name: Alice",
+        "tags[2]: a,b",
+        "users[2]{id,name}:",
+        "  1,Bob",
+        "  2,Carol",
+        "items[2]:",
+        "  - x: 1",
+        "    y: 2",
+        "  - plain",
+        "note: hello world",
+    ]
+
+    @pytest.mark.parametrize("ending", ["\r\n", "\r"])
+    def test_same_result_as_lf(self, ending):
+        expected = toons.loads("\n".join(self.DOCUMENT))
+        assert toons.loads(ending.join(self.DOCUMENT)) == expected
+        assert toons.loads(ending.join(self.DOCUMENT) + ending) == expected
+
+    def test_mixed_endings(self):
+        text = "a: 1\r\nb: 2\rc: 3\nd: 4"
+        assert toons.loads(text) == {"a": 1, "b": 2, "c": 3, "d": 4}
+
+    @pytest.mark.parametrize("ending", ["\n", "\r\n", "\r"])
+    def test_no_carriage_return_in_values(self, ending):
+        result = toons.loads(ending.join(self.DOCUMENT))
+        assert result["note"] == "hello world"
+        assert result["items"][1] == "plain"
+        assert toons.dumps(result) == "\n".join(self.DOCUMENT)
+
+    def test_error_line_numbers_with_cr(self):
+        with pytest.raises(toons.ToonDecodeError) as exc:
+            toons.loads("a: 1\rb[3]: 1,2")
+        assert exc.value.line == 2
+
+
+class TestLenientComments:
+    """strict=False skips full-line # comments"""
+
+    DOCUMENT = "\n".join(
+        [
+            "# service configuration",
+            "name: api",
+            "server:",
+            "  # listen address",
+            "  host: localhost",
+            "    # deeper comment",
+            "  port: 8080",
+            "tags[2]:",
+            "  # first tag",
+            "  - web",
+            "  - api",
+            "# header comment",
+            "users[2]{id,name}:",
+            "  1,Alice",
+            "# between rows",
+            "  2,Bob",
+            "items[2]:",
+            "  - x: 1",
+            "    # inside list item",
+            "    y: 2",
+            "  - 3",
+            "# trailing comment",
+        ]
+    )
+
+    EXPECTED = {
+        "name": "api",
+        "server": {"host": "localhost", "port": 8080},
+        "tags": ["web", "api"],
+        "users": [{"id": 1, "name": "Alice"}, {"id": 2, "name": "Bob"}],
+        "items": [{"x": 1, "y": 2}, 3],
+    }
+
+    def test_comments_skipped(self):
+        assert toons.loads(self.DOCUMENT, strict=False) == self.EXPECTED
+
+    def test_comments_produce_no_warnings(self):
+        _, warnings = toons.loads(
+            self.DOCUMENT, strict=False, collect_warnings=True
+        )
+        assert warnings == []
+
+    def test_strict_rejects_comments(self):
+        with pytest.raises(toons.ToonDecodeError) as exc:
+            toons.loads(self.DOCUMENT)
+        assert exc.value.line == 1
+
+    @pytest.mark.parametrize(
+        "text,expected",
+        [
+            ("a: # not a comment", {"a": "# not a comment"}),
+            ('a: "#quoted"', {"a": "#quoted"}),
+            ("tags[2]: #a,#b", {"tags": ["#a", "#b"]}),
+            ('rows[1]{k}:\n  "#x"', {"rows": [{"k": "#x"}]}),
+        ],
+    )
+    def test_hash_inside_values_kept(self, text, expected):
+        assert toons.loads(text, strict=False) == expected
+
+    def test_hash_rows_kept(self):
+        text = "t[2]{tag,n}:\n  #1,1\n# between rows\n  #2,2\nx: 1"
+        assert toons.loads(text, strict=False) == {
+            "t": [{"tag": "#1", "n": 1}, {"tag": "#2", "n": 2}],
+            "x": 1,
+        }
+
+    def test_hash_rows_kept_in_list_item(self):
+        text = "[1]:\n  - t[1]{tag}:\n      #1\n    n: 2"
+        assert toons.loads(text, strict=False) == [
+            {"t": [{"tag": "#1"}], "n": 2}
+        ]
+
+    def test_hash_rows_kept_by_iter_rows(self):
+        text = "# rows\n[2]{tag,n}:\n  #1,1\n  #2,2\n"
+        rows = list(toons.iter_rows(io.StringIO(text), strict=False))
+        assert rows == [{"tag": "#1", "n": 1}, {"tag": "#2", "n": 2}]
+
+    @pytest.mark.parametrize(
+        "value",
+        [
+            [{"tag": "#1", "n": 1}, {"tag": "#2", "n": 2}],
+            {"rows": [{"tag": "#x"}], "tags": ["#a", "b"]},
+            {"#key": "#value"},
+            "#x",
+            ["#x", {"a": "#y"}],
+        ],
+    )
+    def test_hash_values_roundtrip(self, value):
+        text = toons.dumps(value)
+        assert toons.loads(text, strict=False) == value
+        assert toons.loads(text) == value
+        assert toons.json_to_toon(json.dumps(value)) == text
+
+    def test_error_line_numbers_count_comments(self):
+        with pytest.raises(toons.ToonDecodeError) as exc:
+            toons.loads("# one\n# two\na[3]: 1,2", strict=False)
+        assert exc.value.line == 3
+        assert exc.value.source == "a[3]: 1,2"
+
+    def test_warning_line_numbers_count_comments(self):
+        _, warnings = toons.loads(
+            "# c\nitems[2]:\n  - 1\n\n  - 2",
+            strict=False,
+            collect_warnings=True,
+        )
+        assert warnings == [(4, "Blank line inside array")]
+
+
+class TestParseDatetime:
+    """Tests for the parse_datetime option"""
+
+    @pytest.mark.parametrize(
+        "value",
+        [
+            datetime.date(2024, 1, 15),
+            datetime.time(10, 30),
+            datetime.time(23, 59, 59, 999999),
+            datetime.datetime(2024, 1, 15, 10, 30),
+            datetime.datetime(2024, 1, 15, 10, 30, 5, 120000),
+            datetime.datetime(
+                2024, 1, 15, 10, 30, tzinfo=datetime.timezone.utc
+            ),
+            datetime.datetime(
+                2024,
+                1,
+                15,
+                10,
+                30,
+                tzinfo=datetime.timezone(
+                    -datetime.timedelta(hours=5, minutes=30)
+                ),
+            ),
+        ],
+    )
+    def test_roundtrip(self, value):
+        out = toons.dumps({"v": value, "rows": [{"v": value, "n": 1}]})
+        result = toons.loads(out, parse_datetime=True)
+        assert result == {"v": value, "rows": [{"v": value, "n": 1}]}
+        assert type(result["v"]) is type(value)
+        assert getattr(result["v"], "tzinfo", None) == getattr(
+            value, "tzinfo", None
+        )
+
+    def test_default_keeps_strings(self):
+        assert toons.loads("d: 2024-01-15") == {"d": "2024-01-15"}
+
+    @pytest.mark.parametrize(
+        "text,expected",
+        [
+            ("2024-01-15", datetime.date(2024, 1, 15)),
+            ('"2024-01-15"', datetime.date(2024, 1, 15)),
+            (
+                "2024-01-15T10:30",
+                datetime.datetime(2024, 1, 15, 10, 30),
+            ),
+            (
+                '"2024-01-15 10:30:00.5"',
+                datetime.datetime(2024, 1, 15, 10, 30, 0, 500000),
+            ),
+            (
+                "2024-01-15T10:30:00Z",
+                datetime.datetime(
+                    2024, 1, 15, 10, 30, tzinfo=datetime.timezone.utc
+                ),
+            ),
+            ('"08:15:00"', datetime.time(8, 15)),
+        ],
+    )
+    def test_iso_forms(self, text, expected):
+        assert toons.loads(f"v: {text}", parse_datetime=True)["v"] == expected
+
+    @pytest.mark.parametrize(
+        "text",
+        [
+            "2026",
+            "12:30",
+            "2024-02-30",
+            "2024-1-15",
+            "2024-01-15T",
+            "2024-01-15T25:00:00",
+            "2024-01-15T10:30:00.1234567",
+            "2024-01-15T10:30:00+5",
+            "2024-01-15x",
+            "10:30:00 UTC",
+            "２０２４-01-15",
+        ],
+    )
+    def test_non_iso_strings_kept(self, text):
+        result = toons.loads(f'v: "{text}"', parse_datetime=True)
+        assert result == {"v": text}
+
+    def test_inline_array_and_root(self):
+        result = toons.loads(
+            "[2]: 2024-01-15,2024-01-16", parse_datetime=True
+        )
+        assert result == [
+            datetime.date(2024, 1, 15),
+            datetime.date(2024, 1, 16),
+        ]
+        assert toons.loads("2024-01-15", parse_datetime=True) == (
+            datetime.date(2024, 1, 15)
+        )
+
+
+class TestValidate:
+    def test_valid_documents(self):
+        assert toons.validate("name: Alice\nage: 30") is True
+        assert toons.validate("items[2]{id,name}:\n  1,A\n  2,B") is True
+        assert toons.validate("[3]: 1,2,3") is True
+        assert toons.validate("") is True
+
+    def test_matches_loads_for_encoded_data(self):
+        data = {
+            "users": [{"id": i, "name": f"u{i}"} for i in range(50)],
+            "meta": {"tags": ["a", "b"], "nested": [[1, 2], {"x": None}]},
+        }
+        assert toons.validate(toons.dumps(data)) is True
+
+    @pytest.mark.parametrize(
+        ("text", "line"),
+        [
+            ("items[3]: a,b", 1),
+            ("rows[2]{a,b}:\n  1,2\n  3", 3),
+            ("rows[1]{a,b}:\n  1,2\n  3,4", 1),
+            ('a: 1\nb: "bad \\q"', 2),
+            ("a: 1\nno colon here\n", 2),
+            ('a: "open\nb: 1', 1),
+        ],
+    )
+    def test_errors_match_loads(self, text, line):
+        with pytest.raises(toons.ToonDecodeError) as loads_err:
+            toons.loads(text)
+        with pytest.raises(toons.ToonDecodeError) as exc:
+            toons.validate(text)
+        assert str(exc.value) == str(loads_err.value)
+        assert exc.value.line == line
+
+    def test_strict_flag(self):
+        text = "items[2]:\n  - 1\n\n  - 2"
+        with pytest.raises(toons.ToonDecodeError):
+            toons.validate(text)
+        assert toons.validate(text, strict=False) is True
+
+    def test_duplicate_key_conflict_in_strict_mode(self):
+        text = "a:\n  b: 1\na: 2"
+        with pytest.raises(toons.ToonDecodeError):
+            toons.loads(text)
+        with pytest.raises(toons.ToonDecodeError):
+            toons.validate(text)
+
+    def test_bytes_input(self):
+        assert toons.validate(b"k: v") is True
+        with pytest.raises(toons.ToonDecodeError):
+            toons.validate(b"k: \xff")
+
+    def test_rejects_non_text(self):
+        with pytest.raises(TypeError):
+            toons.validate(123)
+
+
+class TestIterRows:
+    # Large enough to span several reads of the file
+    ROWS = [
+        {"id": i, "name": f"user {i}", "ok": i % 2 == 0} for i in range(5000)
+    ]
+
+    def test_matches_loads(self):
+        text = toons.dumps(self.ROWS)
+        assert list(toons.iter_rows(io.StringIO(text))) == self.ROWS
+        assert list(toons.iter_rows(io.BytesIO(text.encode()))) == self.ROWS
+
+    def test_path_argument(self, tmp_path):
+        path = tmp_path / "rows.toon"
+        path.write_text(toons.dumps(self.ROWS), encoding="utf-8")
+        assert list(toons.iter_rows(path)) == self.ROWS
+        assert list(toons.iter_rows(str(path))) == self.ROWS
+
+    def test_is_lazy(self):
+        rows = toons.iter_rows(io.StringIO(toons.dumps(self.ROWS)))
+        assert iter(rows) is rows
+        assert next(rows) == self.ROWS[0]
+        assert next(rows) == self.ROWS[1]
+
+    def test_rejects_other_documents(self):
+        for text in ["", "a: 1", "[2]: 1,2", "items[1]{a}:\n  1"]:
+            with pytest.raises(toons.ToonDecodeError):
+                toons.iter_rows(io.StringIO(text))
+
+    def test_length_mismatch_raised_at_end(self):
+        text = toons.dumps(self.ROWS).replace("[5000]", "[5001]", 1)
+        rows = toons.iter_rows(io.StringIO(text))
+        seen = 0
+        with pytest.raises(toons.ToonDecodeError) as exc:
+            for _ in rows:
+                seen += 1
+        assert seen == 5000
+        assert exc.value.line == 1
+        assert "declared length 5001" in str(exc.value)
+
+    def test_malformed_row_after_valid_rows(self):
+        lines = toons.dumps(self.ROWS).split("\n")
+        lines[4001] = "  1,2"
+        rows = toons.iter_rows(io.StringIO("\n".join(lines)))
+        seen = 0
+        with pytest.raises(toons.ToonDecodeError) as exc:
+            for _ in rows:
+                seen += 1
+        assert seen == 4000
+        assert exc.value.line == 4002
+        assert exc.value.source == "  1,2"
+        assert list(rows) == []
+
+    def test_strict_flag(self):
+        text = "[2]{a}:\n  1\n\n  2\n"
+        with pytest.raises(toons.ToonDecodeError):
+            list(toons.iter_rows(io.StringIO(text)))
+        rows = toons.iter_rows(io.StringIO(text), strict=False)
+        assert list(rows) == [{"a": 1}, {"a": 2}]
+
+    def test_stops_at_end_of_array(self):
+        text = "[1]{a,b}:\n  1,x\nother: 2"
+        assert list(toons.iter_rows(io.StringIO(text))) == [{"a": 1, "b": "x"}]
+
+
+class TestTOONDecoder:
+    def test_matches_loads(self):
+        text = "items[2]:\n  - 1\n\n  - 2"
+        decoder = toons.TOONDecoder(strict=False, collect_warnings=True)
+        assert decoder.decode(text) == toons.loads(
+            text, strict=False, collect_warnings=True
+        )
+        assert decoder.strict is False
+        assert decoder.collect_warnings is True
+
+    def test_options_are_reused(self):
+        decoder = toons.TOONDecoder(expand_paths="safe")
+        assert decoder.decode("a.b: 1") == {"a": {"b": 1}}
+        assert decoder.decode(b"c.d: 2") == {"c": {"d": 2}}
+
+    def test_errors(self):
+        with pytest.raises(toons.ToonDecodeError):
+            toons.TOONDecoder().decode("items[3]: a,b")
+        with pytest.raises(ValueError):
+            toons.TOONDecoder(bare_hyphen="nothing")
+
+    def test_subclassable(self):
+        class UpperKeys(toons.TOONDecoder):
+            def decode(self, s):
+                return {k.upper(): v for k, v in super().decode(s).items()}
+
+        assert UpperKeys().decode("a: 1") == {"A": 1}
+
+    def test_subclass_init_calls_super(self):
+        class Counting(toons.TOONDecoder):
+            def __init__(self, **kwargs):
+                super().__init__(**kwargs)
+                self.calls = 0
+
+            def decode(self, s):
+                self.calls += 1
+                return super().decode(s)
+
+        decoder = Counting(strict=False)
+        assert decoder.decode("# c\na: 1") == {"a": 1}
+        assert decoder.calls == 1
+
+
+class TestArrayCells:
+    def test_inline_array_cells(self):
+        text = '[2]{n,t}:\n  x,[2|]: a|b\n  y,[1|]: "c,d"'
+        assert toons.loads(text) == [
+            {"n": "x", "t": ["a", "b"]},
+            {"n": "y", "t": ["c,d"]},
+        ]
+
+    def test_array_cell_first_is_still_a_row(self):
+        text = "t[1]{a,b}:\n  [2|]: 1|2,x\nk: 1"
+        assert toons.loads(text) == {"t": [{"a": [1, 2], "b": "x"}], "k": 1}
+
+    def test_quoted_cell_stays_string(self):
+        assert toons.loads('[1]{a}:\n  "[2|]: a|b"') == [{"a": "[2|]: a|b"}]
+
+    def test_length_mismatch_raises(self):
+        with pytest.raises(toons.ToonDecodeError, match="length 2"):
+            toons.loads("[1]{a}:\n  [2|]: a")
+
+
+class TestCustomDelimiterHeaders:
+    def test_inline_and_tabular(self):
+        assert toons.loads("[2;]: a;b") == ["a", "b"]
+        assert toons.loads("x[2~]{a~b}:\n  1~2\n  3~4") == {
+            "x": [{"a": 1, "b": 2}, {"a": 3, "b": 4}]
+        }
+
+    def test_quoted_values_keep_delimiter(self):
+        assert toons.loads('[2;]: "a;b";c') == ["a;b", "c"]
+
+    def test_invalid_marker(self):
+        for text in ["[3x]: a", "[2;;]: a;b", "[2:]: a:b"]:
+            with pytest.raises(toons.ToonDecodeError):
+                toons.loads(text)
+
+
+class TestZeroLengthArrays:
+    @pytest.mark.parametrize(
+        ("text", "expected"),
+        [
+            ("[0]:", []),
+            ("[0]{id,name}:", []),
+            ("[0|]:", []),
+            ("items[0]:", {"items": []}),
+            ("items[0]{id,name}:", {"items": []}),
+            ("items[0]:\nnext: 1", {"items": [], "next": 1}),
+            ("items[0]{a}:\nnext: 1", {"items": [], "next": 1}),
+            ("x:\n  items[0]{a}:\n  b: 2", {"x": {"items": [], "b": 2}}),
+            ("[1]:\n  - a[0]{x}:\n    b: 1", [{"a": [], "b": 1}]),
+            ("[2]:\n  - [0]:\n  - [0]{a}:", [[], []]),
+        ],
+    )
+    @pytest.mark.parametrize("strict", [True, False])
+    def test_empty_lists(self, text, expected, strict):
+        assert toons.loads(text, strict=strict) == expected
+
+    @pytest.mark.parametrize(
+        "text",
+        ["[0]: 1", "items[0]: a,b", "items[0]:\n  - 1", "items[0]{a}:\n  1"],
+    )
+    def test_items_under_zero_length_header(self, text):
+        with pytest.raises(toons.ToonDecodeError, match="declared length 0"):
+            toons.loads(text)
+
+    def test_iter_rows_zero_length(self):
+        assert list(toons.iter_rows(io.StringIO("[0]{a,b}:"))) == []
+        with pytest.raises(toons.ToonDecodeError, match="declared length 0"):
+            list(toons.iter_rows(io.StringIO("[0]{a}:\n  1")))
+
+
+class TestTypedTabularHeaders:
+    def test_cells_converted_to_column_type(self):
+        result = toons.loads("[2]{count:int,ratio:float}:\n  1,2\n  3.0,0.5")
+        assert repr(result) == (
+            "[{'count': 1, 'ratio': 2.0}, {'count': 3, 'ratio': 0.5}]"
+        )
+
+    def test_null_fits_any_column(self):
+        assert toons.loads("[1]{a:int,b:float}:\n  null,null") == [
+            {"a": None, "b": None}
+        ]
+
+    def test_long_int_kept_exact(self):
+        result = toons.loads("x[1]{n:int}:\n  123456789012345678901234567890")
+        assert result == {"x": [{"n": 123456789012345678901234567890}]}
+
+    def test_quoted_field_name_with_colon(self):
+        result = toons.loads('[1]{"a:b":int,c}:\n  1e3,x')
+        assert result == [{"a:b": 1000, "c": "x"}]
+
+    def test_delimiter_and_list_item_headers(self):
+        text = "[1]:\n  - rows[2|]{a:float|b}:\n      1|x\n      2|y"
+        assert toons.loads(text) == [
+            {"rows": [{"a": 1.0, "b": "x"}, {"a": 2.0, "b": "y"}]}
+        ]
+
+    @pytest.mark.parametrize(
+        "cell", ["abc", "1.5", '"1"', "true"], ids=repr
+    )
+    def test_mismatch_raises_in_strict_mode(self, cell):
+        with pytest.raises(toons.ToonDecodeError, match="typed int") as exc:
+            toons.loads(f"[1]{{a:int}}:\n  {cell}")
+        assert exc.value.line == 2
+        with pytest.raises(toons.ToonDecodeError, match="typed int"):
+            toons.validate(f"[1]{{a:int}}:\n  {cell}")
+
+    def test_mismatch_kept_with_warning_when_lenient(self):
+        value, warnings = toons.loads(
+            "[1]{a:float}:\n  abc", strict=False, collect_warnings=True
+        )
+        assert value == [{"a": "abc"}]
+        assert warnings == [(2, "Column 'a' is typed float but holds abc")]
+
+    def test_bool_str_and_null_columns(self):
+        text = 'users[2]{id:int,name:str,active:bool,gone:null}:\n'
+        text += '  1,007,true,null\n  2,"a,b",false,null'
+        assert toons.loads(text) == {
+            "users": [
+                {"id": 1, "name": "007", "active": True, "gone": None},
+                {"id": 2, "name": "a,b", "active": False, "gone": None},
+            ]
+        }
+
+    def test_bool_column_coerces_quoted_literals(self):
+        result = toons.loads('[3]{a:bool}:\n  "true"\n  "false"\n  null')
+        assert result == [{"a": True}, {"a": False}, {"a": None}]
+        assert type(result[0]["a"]) is bool
+
+    @pytest.mark.parametrize(
+        "cell", ["maybe", '"maybe"', '"True"', "True"], ids=repr
+    )
+    def test_bool_column_rejects_other_cells(self, cell):
+        with pytest.raises(toons.ToonDecodeError, match="typed bool") as exc:
+            toons.loads(f"[2]{{a:bool}}:\n  true\n  {cell}")
+        assert exc.value.line == 3
+
+    def test_bool_column_mismatch_kept_with_warning_when_lenient(self):
+        value, warnings = toons.loads(
+            "[1]{a:bool}:\n  maybe", strict=False, collect_warnings=True
+        )
+        assert value == [{"a": "maybe"}]
+        assert warnings == [(2, "Column 'a' is typed bool but holds maybe")]
+
+    def test_null_column_rejects_quoted_null(self):
+        with pytest.raises(toons.ToonDecodeError, match="typed null"):
+            toons.loads('[1]{a:null}:\n  "null"')
+
+    def test_str_column_keeps_literals_as_text(self):
+        result = toons.loads("[1]{a:str,b:str}:\n  true,12")
+        assert result == [{"a": "true", "b": "12"}]
+
+    @pytest.mark.parametrize(
+        "header,cell",
+        [("a:bool", "1"), ("a:bool", "yes"), ("a:null", "0")],
+        ids=repr,
+    )
+    def test_bool_and_null_mismatch_raises(self, header, cell):
+        with pytest.raises(toons.ToonDecodeError, match="is typed"):
+            toons.loads(f"[1]{{{header}}}:\n  {cell}")
+
+    def test_unknown_type_raises(self):
+        with pytest.raises(toons.ToonDecodeError, match="Unknown column type"):
+            toons.loads("[1]{a:decimal}:\n  1")
+
+    def test_unknown_type_untyped_with_warning_when_lenient(self):
+        value, warnings = toons.loads(
+            "[1]{a:decimal,b}:\n  1.5,x", strict=False, collect_warnings=True
+        )
+        assert value == [{"a": 1.5, "b": "x"}]
+        assert warnings == [(1, "Unknown column type: decimal")]
+
+    def test_iter_rows_converts_cells(self):
+        rows = toons.iter_rows(io.StringIO("[2]{n:float}:\n  1\n  2"))
+        assert [repr(row["n"]) for row in rows] == ["1.0", "2.0"]
+
+
+class TestDigitSeparators:
+    @pytest.mark.parametrize(
+        "token,expected",
+        [("1_000", 1000), ("-1_000_000", -1000000), ("1_000.5", 1000.5)],
+        ids=repr,
+    )
+    def test_lenient_mode_strips_underscores(self, token, expected):
+        value = toons.loads(f"n: {token}", strict=False)["n"]
+        assert value == expected
+        assert type(value) is type(expected)
+
+    def test_strict_mode_keeps_string(self):
+        assert toons.loads("n: 1_000") == {"n": "1_000"}
+
+    @pytest.mark.parametrize(
+        "token", ["_1", "1_", "1__0", "1_.5", "0_1", "a_1"], ids=repr
+    )
+    def test_malformed_forms_stay_strings(self, token):
+        assert toons.loads(f"n: {token}", strict=False) == {"n": token}
+
+    def test_applies_to_array_values(self):
+        result = toons.loads("a[2]: 1_0,2_0", strict=False)
+        assert result == {"a": [10, 20]}
+
+    def test_underscore_strings_are_quoted_on_encode(self):
+        encoded = toons.dumps({"n": "1_000", "m": 1000})
+        assert encoded == 'n: "1_000"\nm: 1000'
+        assert toons.loads(encoded, strict=False) == {"n": "1_000", "m": 1000}
+
+
+class TestPlusSignedNumbers:
+    @pytest.mark.parametrize(
+        "token,expected",
+        [("+5", 5), ("+1.5", 1.5), ("+1e3", 1000.0), ("+1_000", 1000)],
+        ids=repr,
+    )
+    def test_lenient_mode_strips_sign(self, token, expected):
+        value = toons.loads(f"n: {token}", strict=False)["n"]
+        assert value == expected
+        assert type(value) is type(expected)
+
+    @pytest.mark.parametrize("token", ["+5", "+1.5"], ids=repr)
+    def test_strict_mode_keeps_string(self, token):
+        assert toons.loads(f"n: {token}") == {"n": token}
+
+    @pytest.mark.parametrize(
+        "token", ["++5", "+-5", "+", "+inf", "+007"], ids=repr
+    )
+    def test_malformed_forms_stay_strings(self, token):
+        assert toons.loads(f"n: {token}", strict=False) == {"n": token}
+
+    def test_applies_to_array_values(self):
+        result = toons.loads("a[2]: +1,-2", strict=False)
+        assert result == {"a": [1, -2]}
+
+    def test_typed_columns(self):
+        text = "t[1]{a:int,b:float}:\n  +5,+1.5"
+        assert toons.loads(text, strict=False) == {"t": [{"a": 5, "b": 1.5}]}
+        with pytest.raises(toons.ToonDecodeError, match="typed int"):
+            toons.loads(text)
+
+    def test_signed_strings_are_quoted_on_encode(self):
+        encoded = toons.dumps({"n": "+5", "m": 5})
+        assert encoded == 'n: "+5"\nm: 5'
+        assert toons.loads(encoded, strict=False) == {"n": "+5", "m": 5}
+
+
+class TestRadixIntegers:
+    @pytest.mark.parametrize(
+        "token,expected",
+        [
+            ("0xff", 255),
+            ("0XFF", 255),
+            ("0o17", 15),
+            ("0O17", 15),
+            ("0b1010", 10),
+            ("0B1010", 10),
+            ("-0x10", -16),
+            ("+0o17", 15),
+            ("-0b11", -3),
+        ],
+        ids=repr,
+    )
+    def test_lenient_mode_reads_ints(self, token, expected):
+        value = toons.loads(f"n: {token}", strict=False)["n"]
+        assert value == expected
+        assert type(value) is int
+
+    def test_values_beyond_64_bits(self):
+        result = toons.loads("n: 0xFFFFFFFFFFFFFFFFFF", strict=False)
+        assert result == {"n": 2**72 - 1}
+
+    @pytest.mark.parametrize("token", ["0xff", "0o17", "0b1"], ids=repr)
+    def test_strict_mode_keeps_string(self, token):
+        assert toons.loads(f"n: {token}") == {"n": token}
+
+    @pytest.mark.parametrize(
+        "token", ["0xZZ", "0x", "0o8", "0b2", "0b_1", "x0b1"], ids=repr
+    )
+    def test_malformed_forms_stay_strings(self, token):
+        assert toons.loads(f"n: {token}", strict=False) == {"n": token}
+
+    def test_applies_to_array_values(self):
+        result = toons.loads("a[3]: 0x10,0o10,0b10", strict=False)
+        assert result == {"a": [16, 8, 2]}
+
+    def test_encode_writes_decimal_and_quotes_strings(self):
+        encoded = toons.dumps({"s": "0xff", "n": 0xFF})
+        assert encoded == 's: "0xff"\nn: 255'
+        assert toons.loads(encoded, strict=False) == {"s": "0xff", "n": 255}
+
+
+class TestFloatTokens:
+    TOKENS = {
+        "nan_token": "NaN",
+        "inf_token": "Infinity",
+        "neg_inf_token": "-Infinity",
+    }
+
+    def test_quoted_tokens_read_as_floats(self):
+        text = 'a: "NaN"\nb[2]: "Infinity","-Infinity"'
+        result = toons.loads(text, **self.TOKENS)
+        assert math.isnan(result["a"])
+        assert result["b"] == [float("inf"), float("-inf")]
+
+    def test_default_keeps_strings(self):
+        assert toons.loads('a: "NaN"') == {"a": "NaN"}
+
+    def test_other_strings_unaffected(self):
+        text = 'a: "nan"\nb: "Infinity!"'
+        result = toons.loads(text, **self.TOKENS)
+        assert result == {"a": "nan", "b": "Infinity!"}
+
+    def test_typed_columns(self):
+        text = '[2]{x:float,s:str}:\n  "NaN","-Infinity"\n  1.5,x'
+        result = toons.loads(text, **self.TOKENS)
+        assert math.isnan(result[0]["x"])
+        assert result[0]["s"] == float("-inf")
+        assert result[1] == {"x": 1.5, "s": "x"}
+
+    def test_decoder_accepts_option(self):
+        decoder = toons.TOONDecoder(inf_token="inf")
+        assert decoder.inf_token == "inf"
+        assert decoder.decode('v: "inf"') == {"v": float("inf")}
+
+
+class TestObjectHooks:
+    def test_pairs_hook_gets_document_order(self):
+        seen = []
+
+        def hook(pairs):
+            seen.append(pairs)
+            return dict(pairs)
+
+        toons.loads("b: 1\na:\n  z: 2\n  y: 3", object_pairs_hook=hook)
+        assert seen == [
+            [("z", 2), ("y", 3)],
+            [("b", 1), ("a", {"z": 2, "y": 3})],
+        ]
+
+    def test_ordered_dict(self):
+        text = "users[2]{id,name}:\n  1,Ann\n  2,Bo\nmeta:\n  n: 2"
+        result = toons.loads(text, object_pairs_hook=OrderedDict)
+        assert type(result) is OrderedDict
+        assert type(result["users"][0]) is OrderedDict
+        assert type(result["meta"]) is OrderedDict
+        assert list(result) == ["users", "meta"]
+
+    def test_object_hook_replaces_dicts(self):
+        result = toons.loads(
+            "a:\n  b: 1\nc[1]:\n  - d: 2", object_hook=lambda d: sorted(d)
+        )
+        assert result == ["a", "c"]
+
+    def test_object_hook_sees_converted_children(self):
+        result = toons.loads("a:\n  b: 1", object_hook=lambda d: len(d) * 10)
+        assert result == 10
+
+    def test_pairs_hook_takes_precedence(self):
+        result = toons.loads(
+            "a: 1",
+            object_hook=lambda d: "object",
+            object_pairs_hook=lambda pairs: "pairs",
+        )
+        assert result == "pairs"
+
+    def test_hooks_do_not_touch_primitives_or_lists(self):
+        result = toons.loads("[2]: 1,2", object_hook=lambda d: None)
+        assert result == [1, 2]
+
+    def test_non_callable_hook_rejected(self):
+        with pytest.raises(TypeError, match="object_pairs_hook"):
+            toons.loads("a: 1", object_pairs_hook=1)
+
+    def test_decoder_keeps_hooks(self):
+        decoder = toons.TOONDecoder(object_pairs_hook=OrderedDict)
+        assert decoder.object_pairs_hook is OrderedDict
+        assert decoder.object_hook is None
+        assert type(decoder.decode("a: 1")) is OrderedDict
+
+    def test_load_accepts_hooks(self):
+        result = toons.load(io.StringIO("a: 1"), object_hook=lambda d: d["a"])
+        assert result == 1
+
+    def test_pairs_hook_gets_repeated_keys(self):
+        result = toons.loads("a: 1\nb: 2\na: 3", object_pairs_hook=list)
+        assert result == [("a", 1), ("b", 2), ("a", 3)]
+
+    def test_pairs_hook_gets_repeated_keys_of_list_items(self):
+        text = "[1]:\n  - a: 1\n    a:\n      x: 1"
+        result = toons.loads(text, object_pairs_hook=list)
+        assert result == [[("a", 1), ("a", [("x", 1)])]]
+
+    def test_object_hook_gets_last_value(self):
+        result = toons.loads("a: 1\na: 2", object_hook=lambda d: d)
+        assert result == {"a": 2}
+
+    def test_repeated_key_aliases_resolved(self):
+        text = "x:\n  k: 1\na: */x\na: 2"
+        result = toons.loads(
+            text, resolve_aliases=True, object_pairs_hook=list
+        )
+        assert result == [("x", [("k", 1)]), ("a", [("k", 1)]), ("a", 2)]
+
+    def test_hooks_see_resolved_aliases(self):
+        text = "x:\n  a[1]: 1\n  b: */x/a"
+        result = toons.loads(
+            text, resolve_aliases=True, object_pairs_hook=list
+        )
+        assert result == [("x", [("a", [1]), ("b", [1])])]
+
+    def test_aliased_object_converted_once(self):
+        calls = []
+
+        def hook(d):
+            calls.append(d)
+            return tuple(d.items())
+
+        text = "x:\n  a:\n    k: 1\n  b: */x/a"
+        result = toons.loads(text, resolve_aliases=True, object_hook=hook)
+        (_, x), = result
+        assert x[0][1] is x[1][1]
+        assert len(calls) == 3
+
+    def test_hooks_reject_object_aliasing_itself(self):
+        with pytest.raises(ValueError, match="alias of itself"):
+            toons.loads("x:\n  a: */x", resolve_aliases=True, object_hook=dict)
+
+
+class TestAlternateLiterals:
+    PYTHON = {
+        "null_literal": "None",
+        "true_literal": "True",
+        "false_literal": "False",
+    }
+
+    def test_default_reads_python_tokens_as_strings(self):
+        assert toons.loads("a: None\nb: True") == {"a": "None", "b": "True"}
+
+    def test_reads_configured_tokens(self):
+        text = "a: None\nb: True\nc[2]: False,x\nrows[1]{p,q}:\n  None,True"
+        assert toons.loads(text, **self.PYTHON) == {
+            "a": None,
+            "b": True,
+            "c": [False, "x"],
+            "rows": [{"p": None, "q": True}],
+        }
+
+    def test_standard_tokens_still_read(self):
+        result = toons.loads("a: null\nb: true\nc: false", **self.PYTHON)
+        assert result == {"a": None, "b": True, "c": False}
+
+    def test_quoted_tokens_stay_strings(self):
+        assert toons.loads('a: "None"', **self.PYTHON) == {"a": "None"}
+
+    def test_typed_columns(self):
+        text = "[2]{a:bool,b:int}:\n  True,None\n  None,1"
+        assert toons.loads(text, **self.PYTHON) == [
+            {"a": True, "b": None},
+            {"a": None, "b": 1},
+        ]
+
+    def test_decoder_holds_options(self):
+        decoder = toons.TOONDecoder(**self.PYTHON)
+        assert decoder.null_literal == "None"
+        assert decoder.decode("[2]: None,False") == [None, False]
+
+
+class TestLenientLiterals:
+    @pytest.mark.parametrize(
+        "token,expected",
+        [
+            ("True", True),
+            ("TRUE", True),
+            ("tRuE", True),
+            ("False", False),
+            ("FALSE", False),
+            ("None", None),
+            ("NONE", None),
+            ("NULL", None),
+            ("Null", None),
+            ("nil", None),
+            ("NIL", None),
+        ],
+        ids=repr,
+    )
+    def test_lenient_mode_reads_other_casings(self, token, expected):
+        assert toons.loads(f"x: {token}", strict=False) == {"x": expected}
+
+    @pytest.mark.parametrize("token", ["True", "FALSE", "None", "NULL", "nil"])
+    def test_strict_mode_keeps_strings(self, token):
+        assert toons.loads(f"x: {token}") == {"x": token}
+
+    def test_none_example(self):
+        assert toons.loads("x: None", strict=False) == {"x": None}
+        assert toons.loads("x: None", strict=True) == {"x": "None"}
+
+    def test_inline_arrays_and_tables(self):
+        text = "a[3]: True,None,x\n"
+        text += "rows[2]{p,q:bool}:\n  NULL,FALSE\n  nil,True"
+        assert toons.loads(text, strict=False) == {
+            "a": [True, None, "x"],
+            "rows": [{"p": None, "q": False}, {"p": None, "q": True}],
+        }
+
+    @pytest.mark.parametrize("token", ['"None"', '"True"', "Nones", "truely"])
+    def test_quoted_and_longer_tokens_stay_strings(self, token):
+        expected = token.strip('"')
+        assert toons.loads(f"x: {token}", strict=False) == {"x": expected}
+
+
+class TestJsonEscapes:
+    @pytest.mark.parametrize(
+        "escaped,expected",
+        [(r"a\/b", "a/b"), (r"a\bb", "a\bb"), (r"a\fb", "a\fb")],
+        ids=repr,
+    )
+    def test_json_escapes_accepted(self, escaped, expected):
+        assert toons.loads(f'x: "{escaped}"') == {"x": expected}
+        assert toons.loads(f'x: "{escaped}"', strict=False) == {"x": expected}
+
+    def test_json_escapes_in_keys_and_cells(self):
+        text = '"a\\/b": 1\nrows[1]{"c\\fd"}:\n  "e\\bf"'
+        assert toons.loads(text) == {"a/b": 1, "rows": [{"c\fd": "e\bf"}]}
+
+    def test_other_escapes_still_rejected(self):
+        with pytest.raises(toons.ToonDecodeError, match=r"Invalid escape"):
+            toons.loads(r'x: "a\x"')
+        with pytest.raises(toons.ToonDecodeError, match=r"Invalid escape"):
+            toons.loads(r'x: "a\U00000041"', strict=False)
+
+    @pytest.mark.parametrize(
+        "escaped,expected",
+        [
+            (r"caf\u00e9", "café"),
+            (r"\u00C9", "É"),
+            (r"\ud83d\ude00!", "😀!"),
+            (r"\u0041\/", "A/"),
+        ],
+        ids=repr,
+    )
+    def test_unicode_escapes(self, escaped, expected):
+        text = f'"k{escaped}": "{escaped}"'
+        assert toons.loads(text) == {"k" + expected: expected}
+        assert toons.loads(text, strict=False) == {"k" + expected: expected}
+
+    @pytest.mark.parametrize(
+        "escaped", [r"\u12", r"\u12G4", r"\ud83d", r"\ude00", r"\ud83d\u0041"]
+    )
+    def test_invalid_unicode_escapes(self, escaped):
+        with pytest.raises(toons.ToonDecodeError, match="nicode escape"):
+            toons.loads(f'x: "{escaped}"')
+        with pytest.raises(toons.ToonDecodeError, match="nicode escape"):
+            toons.loads(f'x: "{escaped}"', strict=False)
+
+    @pytest.mark.parametrize(
+        "value", ["a\fb", "\f", "x\f", "a\bb", "\b", "\f\b mixed\f"], ids=repr
+    )
+    def test_form_feed_and_backspace_round_trip(self, value):
+        data = {"text": value, "items": [value, "x"]}
+        text = toons.dumps(data)
+        # Written raw: TOON itself only defines \\ \" \n \r \t
+        assert "\\f" not in text and "\\b" not in text
+        assert toons.loads(text) == data
+
+
+class TestEmptyKeys:
+    @pytest.mark.parametrize(
+        "data,text",
+        [
+            ({"": "v"}, '"": v'),
+            ({" ": 1}, '" ": 1'),
+            ({"\t": 1}, '"\\t": 1'),
+            ({"": ""}, '"": ""'),
+            ({"": {"": 1}}, '"":\n  "": 1'),
+            ({"": [1, 2]}, '""[2]: 1,2'),
+            ([{"": 1, "b": 2}], '[1]{"",b}:\n  1,2'),
+            ([{"": 1, "x": [1]}], '[1]:\n  - "": 1\n    x[1]: 1'),
+        ],
+        ids=repr,
+    )
+    def test_round_trip(self, data, text):
+        assert toons.dumps(data) == text
+        assert toons.loads(text) == data
+
+    def test_quoted_key_with_colon_value(self):
+        assert toons.loads('"": ":"') == {"": ":"}
+
+    @pytest.mark.parametrize(
+        "text", [": 1", "a: 1\n: 2", "a:\n  : 1", "[1]:\n  - : 1"], ids=repr
+    )
+    def test_bare_empty_key_rejected_in_strict_mode(self, text):
+        with pytest.raises(toons.ToonDecodeError, match="Empty key"):
+            toons.loads(text)
+
+    def test_bare_empty_key_tolerated_in_lenient_mode(self):
+        value, warnings = toons.loads(
+            "a: 1\n: 2", strict=False, collect_warnings=True
+        )
+        assert value == {"a": 1, "": 2}
+        assert warnings == [(2, 'Empty key must be quoted as ""')]
+
+    def test_bare_empty_field_name(self):
+        with pytest.raises(toons.ToonDecodeError, match="Empty field name"):
+            toons.loads("[1]{,a}:\n  1,2")
+        value, warnings = toons.loads(
+            "[1]{,a}:\n  1,2", strict=False, collect_warnings=True
+        )
+        assert value == [{"": 1, "a": 2}]
+        assert warnings == [(1, 'Empty field name must be quoted as ""')]
+
+
+class TestTrailingDelimiter:
+    def test_rejected_by_default(self):
+        with pytest.raises(toons.ToonDecodeError, match="found 3 elements"):
+            toons.loads("a[2]: 1,2,")
+        with pytest.raises(toons.ToonDecodeError, match="has 3 values"):
+            toons.loads("[1]{a,b}:\n  1,2,")
+
+    def test_inline_array(self):
+        text = "a[2]: 1,2,\nb[1]: x,"
+        result = toons.loads(text, allow_trailing_delimiter=True)
+        assert result == {"a": [1, 2], "b": ["x"]}
+
+    def test_tabular_rows(self):
+        text = "[2]{a,b}:\n  1,2,\n  3,4"
+        result = toons.loads(text, allow_trailing_delimiter=True)
+        assert result == [{"a": 1, "b": 2}, {"a": 3, "b": 4}]
+
+    def test_other_delimiters(self):
+        text = "a[2|]: 1|2|\nt[1|]{x|y}:\n  1|2|"
+        result = toons.loads(text, allow_trailing_delimiter=True)
+        assert result == {"a": [1, 2], "t": [{"x": 1, "y": 2}]}
+
+    def test_only_one_empty_value_dropped(self):
+        with pytest.raises(toons.ToonDecodeError, match="found 3 elements"):
+            toons.loads("a[2]: 1,2,,", allow_trailing_delimiter=True)
+
+    def test_quoted_empty_string_kept(self):
+        text = 'a[3]: 1,2,""'
+        result = toons.loads(text, allow_trailing_delimiter=True)
+        assert result == {"a": [1, 2, ""]}
+
+    def test_decoder_accepts_option(self):
+        decoder = toons.TOONDecoder(allow_trailing_delimiter=True)
+        assert decoder.allow_trailing_delimiter
+        assert decoder.decode("a[1]: 1,") == {"a": [1]}
+
+
+class TestIndentOption:
+    def test_explicit_indent(self):
+        text = "a:\n    b:\n        c: 1"
+        assert toons.loads(text, indent=4) == {"a": {"b": {"c": 1}}}
+
+    @pytest.mark.parametrize("indent", [0, 1])
+    def test_below_two_rejected(self, indent):
+        for call in (
+            lambda: toons.loads("a: 1", indent=indent),
+            lambda: toons.validate("a: 1", indent=indent),
+            lambda: toons.TOONDecoder(indent=indent),
+        ):
+            with pytest.raises(ValueError, match="indent must be >= 2"):
+                call()
+
+
+class TestTabIndent:
+    TEXT = "a:\n\tb:\n\t\tc: 1\n\titems[2]:\n\t\t- x\n\t\t- y\nd: 2"
+    DATA = {"a": {"b": {"c": 1}, "items": ["x", "y"]}, "d": 2}
+
+    def test_rejected_by_default(self):
+        with pytest.raises(toons.ToonDecodeError, match="Tabs are not"):
+            toons.loads(self.TEXT)
+
+    def test_one_tab_per_level(self):
+        assert toons.loads(self.TEXT, allow_tab_indent=True) == self.DATA
+
+    def test_with_explicit_indent(self):
+        result = toons.loads(self.TEXT, allow_tab_indent=True, indent=4)
+        assert result == self.DATA
+
+    def test_tabular_rows(self):
+        text = "t[2]{x,y}:\n\t1,2\n\t3,4"
+        assert toons.loads(text, allow_tab_indent=True) == {
+            "t": [{"x": 1, "y": 2}, {"x": 3, "y": 4}]
+        }
+
+    def test_decoder_and_validate(self):
+        decoder = toons.TOONDecoder(allow_tab_indent=True)
+        assert decoder.allow_tab_indent
+        assert decoder.decode(self.TEXT) == self.DATA
+
+
+class TestRootPrimitive:
+    def test_escaped_newline_in_root_string(self):
+        assert toons.loads('"a\\nb"') == "a\nb"
+        assert toons.loads(toons.dumps("a\nb\r\nc")) == "a\nb\r\nc"
+
+    @pytest.mark.parametrize(
+        "text", ['"x"\n', '\n"x"\n\n', '"x"\r\n\r\n', "  \n  x  \n"], ids=repr
+    )
+    def test_surrounding_blank_lines_ignored(self, text):
+        assert toons.loads(text) == "x"
+
+    @pytest.mark.parametrize(
+        "text", ["hello\nworld", '"a"\n"b"', "1\n  2"], ids=repr
+    )
+    def test_multiline_unquoted_root_rejected(self, text):
+        for strict in (True, False):
+            with pytest.raises(
+                toons.ToonDecodeError, match="must be a single line"
+            ) as excinfo:
+                toons.loads(text, strict=strict)
+            assert excinfo.value.line == 1
+
+    def test_comment_after_root_primitive_in_lenient_mode(self):
+        assert toons.loads("x\n# note", strict=False) == "x"
+
+    @pytest.mark.parametrize(
+        "text,line,expected",
+        [
+            ("# c\na: 1", 1, {"a": 1}),
+            ("a: 1\n# c", 2, {"a": 1}),
+            ("a:\n  # c\n  b: 1", 2, {"a": {"b": 1}}),
+        ],
+        ids=repr,
+    )
+    def test_strict_comment_points_to_lenient_mode(self, text, line, expected):
+        with pytest.raises(
+            toons.ToonDecodeError, match="only accepted with strict=False"
+        ) as excinfo:
+            toons.loads(text)
+        assert excinfo.value.line == line
+        assert toons.loads(text, strict=False) == expected
+
+    @pytest.mark.parametrize("strict", [True, False])
+    @pytest.mark.parametrize(
+        "text,expected",
+        [
+            ("true", True),
+            ("false", False),
+            ("null", None),
+            ("42", 42),
+            ("-1.5", -1.5),
+            ("hello", "hello"),
+            ('"true"', "true"),
+            ('"false"', "false"),
+            ('"null"', "null"),
+            ('"42"', "42"),
+            ('"-1.5"', "-1.5"),
+            ('""', ""),
+            ('"[1]: a"', "[1]: a"),
+        ],
+        ids=repr,
+    )
+    def test_quoting_decides_the_type(self, text, expected, strict):
+        value = toons.loads(text, strict=strict)
+        assert value == expected
+        assert type(value) is type(expected)
+
+    def test_quoted_custom_literal_is_a_string(self):
+        assert toons.loads('"None"', null_literal="None") == "None"
+        assert toons.loads("None", null_literal="None") is None
+
+    @pytest.mark.parametrize(
+        "value", ["true", "false", "null", "42", "-1.5", "", True, None, 42]
+    )
+    def test_scalar_roundtrip(self, value):
+        decoded = toons.loads(toons.dumps(value))
+        assert decoded == value
+        assert type(decoded) is type(value)
+
+
+class TestDuplicateHeaderFields:
+    TEXT = "[2]{id,name,id}:\n  1,a,2\n  3,b,4"
+
+    def test_rejected_in_strict_mode(self):
+        with pytest.raises(toons.ToonDecodeError) as excinfo:
+            toons.loads(self.TEXT)
+        assert excinfo.value.line == 1
+        assert "Duplicate field name in header: id" in str(excinfo.value)
+
+    def test_quoted_duplicate_rejected(self):
+        with pytest.raises(toons.ToonDecodeError, match="Duplicate field"):
+            toons.loads('[1]{a,"a"}:\n  1,2')
+
+    def test_typed_duplicate_rejected(self):
+        with pytest.raises(toons.ToonDecodeError, match="Duplicate field"):
+            toons.loads("[1]{a:int,a:str}:\n  1,x")
+
+    def test_last_wins_in_lenient_mode(self):
+        value, warnings = toons.loads(
+            self.TEXT, strict=False, collect_warnings=True
+        )
+        assert value == [{"id": 2, "name": "a"}, {"id": 4, "name": "b"}]
+        assert warnings == [(1, "Duplicate field name in header: id")]
+
+    def test_validate_rejects(self):
+        with pytest.raises(toons.ToonDecodeError, match="Duplicate field"):
+            toons.validate(self.TEXT)
+
+
+class TestLeadingZero:
+    def test_string_by_default(self):
+        assert toons.loads("a: 007\nb: -007") == {"a": "007", "b": "-007"}
+
+    def test_number_mode(self):
+        result = toons.loads("a: 007\nb: -007", leading_zero="number")
+        assert result == {"a": 7, "b": -7}
+        assert type(result["a"]) is int
+
+    def test_number_mode_float(self):
+        assert toons.loads("a: 007.5", leading_zero="number") == {"a": 7.5}
+
+    def test_lone_zero_is_int(self):
+        for mode in ("string", "number"):
+            assert toons.loads("a: 0\nb: -0", leading_zero=mode) == {
+                "a": 0,
+                "b": 0,
+            }
+
+    def test_quoted_stays_string(self):
+        assert toons.loads('a: "007"', leading_zero="number") == {"a": "007"}
+
+    def test_arrays_and_rows(self):
+        text = "a[2]: 007,08\nrows[1]{x}:\n  010"
+        assert toons.loads(text, leading_zero="number") == {
+            "a": [7, 8],
+            "rows": [{"x": 10}],
+        }
+
+    def test_decoder_and_load(self, tmp_path):
+        decoder = toons.TOONDecoder(leading_zero="number")
+        assert decoder.leading_zero == "number"
+        assert decoder.decode("a: 007") == {"a": 7}
+        path = tmp_path / "data.toon"
+        path.write_text("a: 007")
+        with open(path) as f:
+            assert toons.load(f, leading_zero="number") == {"a": 7}
+
+    def test_invalid_value(self):
+        with pytest.raises(ValueError, match="leading_zero"):
+            toons.loads("a: 1", leading_zero="int")
+
+
+class TestEmptyCell:
+    def test_string_by_default(self):
+        assert toons.loads("a[3]: a,,c") == {"a": ["a", "", "c"]}
+        rows = toons.loads("t[1]{x,y,z}:\n  a,,c")
+        assert rows == {"t": [{"x": "a", "y": "", "z": "c"}]}
+
+    def test_null_mode(self):
+        assert toons.loads("a[3]: a,,c", empty_cell="null") == {
+            "a": ["a", None, "c"]
+        }
+        rows = toons.loads("t[1]{x,y,z}:\n  ,b,", empty_cell="null")
+        assert rows == {"t": [{"x": None, "y": "b", "z": None}]}
+
+    def test_quoted_empty_string_kept(self):
+        result = toons.loads('a[2]: "",x', empty_cell="null")
+        assert result == {"a": ["", "x"]}
+
+    def test_typed_columns(self):
+        text = "t[1]{n:int,s:str}:\n  ,"
+        with pytest.raises(toons.ToonDecodeError, match="typed int"):
+            toons.loads(text)
+        assert toons.loads(text, empty_cell="null") == {
+            "t": [{"n": None, "s": None}]
+        }
+
+    def test_decoder_and_load(self, tmp_path):
+        decoder = toons.TOONDecoder(empty_cell="null")
+        assert decoder.empty_cell == "null"
+        assert decoder.decode("a[2]: ,x") == {"a": [None, "x"]}
+        path = tmp_path / "data.toon"
+        path.write_text("a[2]: ,x")
+        with open(path) as f:
+            assert toons.load(f, empty_cell="null") == {"a": [None, "x"]}
+
+    def test_invalid_value(self):
+        with pytest.raises(ValueError, match="empty_cell"):
+            toons.loads("a: 1", empty_cell="none")
+
+
+class TestQuotedHeaderFields:
+    @pytest.mark.parametrize("delimiter", [",", "|", "\t"])
+    @pytest.mark.parametrize("name", ["a,b", "a|b", "a\tb", "x:y", 'q"t'])
+    def test_round_trip(self, delimiter, name):
+        data = [{name: 1, "c": 2}, {name: 3, "c": 4}]
+        toon = toons.dumps(data, delimiter=delimiter)
+        assert toons.loads(toon) == data
+
+    def test_comma_in_field_name(self):
+        toon = toons.dumps([{"a,b": 1, "c": 2}])
+        assert toon == '[1]{"a,b",c}:\n  1,2'
+        assert toons.loads(toon) == [{"a,b": 1, "c": 2}]
+
+    def test_quoted_key_and_quoted_fields(self):
+        data = {"c d": [{"a,b": 1, "-x": "true"}]}
+        toon = toons.dumps(data)
+        assert toon == '"c d"[1]{"a,b","-x"}:\n  1,"true"'
+        assert toons.loads(toon) == data
+
+    def test_in_list_items(self):
+        data = [
+            {"k:": [{"a|b": 1, "c": 2}], "z": 1},
+            {"y": 0, "k[0]": [{"x:y": 3, "c": 4}]},
+        ]
+        assert toons.loads(toons.dumps(data)) == data
+
+
+class TestDelimiterMismatch:
+    """Values or fields that only split right on a delimiter other than the
+    declared one raise in strict mode and are split on it otherwise."""
+
+    @pytest.mark.parametrize(
+        "toon_str",
+        [
+            "a[3|]: 1,2,3",
+            "a[3]: 1|2|3",
+            "a[2\t]: 1,2",
+            "rows[2|]{a|b}:\n  1,2\n  3,4",
+            "rows[2]{a,b}:\n  1\t2\n  3\t4",
+        ],
+    )
+    def test_values_strict(self, toon_str):
+        with pytest.raises(toons.ToonDecodeError, match="Values are sep"):
+            toons.loads(toon_str)
+
+    def test_inline_array_lenient(self):
+        value, warnings = toons.loads(
+            "a[3|]: 1,2,3", strict=False, collect_warnings=True
+        )
+        assert value == {"a": [1, 2, 3]}
+        message = "Values are separated by ',' but the header declares '|'"
+        assert warnings == [(1, message + ": 1,2,3")]
+
+    def test_tabular_rows_lenient(self):
+        toon_str = "rows[2|]{a|b}:\n  1,2\n  3,4"
+        value, warnings = toons.loads(
+            toon_str, strict=False, collect_warnings=True
+        )
+        assert value == {"rows": [{"a": 1, "b": 2}, {"a": 3, "b": 4}]}
+        assert [line for line, _ in warnings] == [2, 3]
+
+    def test_fields_strict(self):
+        with pytest.raises(toons.ToonDecodeError, match="Fields are sep"):
+            toons.loads("rows[1|]{a,b}:\n  1|2")
+
+    def test_fields_lenient(self):
+        value, warnings = toons.loads(
+            "rows[1|]{a,b}:\n  1|2", strict=False, collect_warnings=True
+        )
+        assert value == {"rows": [{"a": 1, "b": 2}]}
+        assert warnings == [
+            (1, "Fields are separated by ',' but the header declares '|'")
+        ]
+
+    def test_other_delimiter_inside_values(self):
+        # Declared delimiter gives the right count, so commas are data
+        assert toons.loads("a[2|]: x,y|z") == {"a": ["x,y", "z"]}
+        assert toons.loads("rows[1|]{a|b}:\n  1,5|2") == {
+            "rows": [{"a": "1,5", "b": 2}]
+        }
+
+    def test_quoted_field_with_other_delimiter(self):
+        assert toons.loads('rows[1|]{"a,b"}:\n  x') == {"rows": [{"a,b": "x"}]}
+
+    def test_count_mismatch_without_other_delimiter(self):
+        with pytest.raises(toons.ToonDecodeError, match="declared length 3"):
+            toons.loads("a[3|]: 1|2")
+
+
+class TestListItemNestedFirstField:
+    """An object list item whose first field holds a nested object."""
+
+    def test_round_trip(self):
+        data = {
+            "people": [
+                {"name": {"first": "A", "last": "B"}, "id": 1},
+                {"name": {"first": "C", "last": {"x": 1}}},
+            ]
+        }
+        toon_str = toons.dumps(data)
+        assert toon_str.startswith(
+            "people[2]:\n  - name:\n      first: A\n      last: B\n    id: 1"
+        )
+        assert toons.loads(toon_str) == data
+
+    def test_fields_one_level_under_hyphen_are_siblings(self):
+        toon_str = "items[1]:\n  - name:\n    first: A\n    last: B"
+        assert toons.loads(toon_str) == {
+            "items": [{"name": {}, "first": "A", "last": "B"}]
+        }
+
+    def test_lenient_keeps_over_indented_object(self):
+        toon_str = "items[1]:\n  - name:\n        first: A\n    id: 1"
+        value, warnings = toons.loads(
+            toon_str, strict=False, collect_warnings=True
+        )
+        assert value == {"items": [{"name": {"first": "A"}, "id": 1}]}
+        assert warnings == []
+
+    def test_lenient_keeps_over_indented_later_field(self):
+        toon_str = "[1]:\n  - id: 1\n    name:\n        first: A\n    x: 2"
+        assert toons.loads(toon_str, strict=False) == [
+            {"id": 1, "name": {"first": "A"}, "x": 2}
+        ]
+
+
+class TestSchema:
+    SCHEMA = {"users": [{"id": int, "name": str, "score": float}]}
+
+    def test_matching_value_returned(self):
+        text = "users[1]{id,name,score}:\n  1,Ada,9.5"
+        assert toons.loads(text, schema=self.SCHEMA) == {
+            "users": [{"id": 1, "name": "Ada", "score": 9.5}]
+        }
+
+    @pytest.mark.parametrize(
+        "spec, value, expected",
+        [
+            (float, "3", 3.0),
+            (int, "3.0", 3),
+            (int, '"42"', 42),
+            (int, '"-7"', -7),
+            (float, '"2.5"', 2.5),
+            (bool, '"true"', True),
+            (bool, '"false"', False),
+        ],
+    )
+    def test_unambiguous_values_coerced(self, spec, value, expected):
+        result = toons.loads(f"v: {value}", schema={"v": spec})
+        assert result == {"v": expected}
+        assert type(result["v"]) is spec
+
+    @pytest.mark.parametrize(
+        "spec, value",
+        [
+            (int, "1.5"),
+            (int, "true"),
+            (bool, "1"),
+            (str, "1"),
+            (float, '"nan"'),
+            (int, '"1e3"'),
+            (None, "0"),
+        ],
+    )
+    def test_ambiguous_values_rejected(self, spec, value):
+        with pytest.raises(toons.ToonDecodeError, match="^Schema mismatch"):
+            toons.loads(f"v: {value}", schema={"v": spec})
+
+    def test_error_names_path(self):
+        text = "users[2]{id,name,score}:\n  1,Ada,1\n  x,Bo,2"
+        with pytest.raises(toons.ToonDecodeError) as exc_info:
+            toons.loads(text, schema=self.SCHEMA)
+        assert str(exc_info.value) == (
+            "Schema mismatch at /users/1/id: expected int, got str 'x'"
+        )
+        assert exc_info.value.line is None
+
+    def test_missing_key_rejected_extra_key_kept(self):
+        with pytest.raises(toons.ToonDecodeError, match="/b: missing"):
+            toons.loads("a: 1", schema={"a": int, "b": int})
+        assert toons.loads("a: 1\nc: x", schema={"a": int}) == {
+            "a": 1,
+            "c": "x",
+        }
+
+    def test_container_type_mismatch(self):
+        with pytest.raises(toons.ToonDecodeError, match="expected list"):
+            toons.loads("a: 1", schema={"a": [int]})
+        with pytest.raises(toons.ToonDecodeError, match="/a: expected dict"):
+            toons.loads("a[1]: 1", schema={"a": {"b": int}})
+
+    def test_root_schema(self):
+        assert toons.loads("[2]: 1,2", schema=[float]) == [1.0, 2.0]
+        with pytest.raises(toons.ToonDecodeError, match=r"at \(root\)"):
+            toons.loads("a: 1", schema=[int])
+
+    def test_union_prefers_exact_match(self):
+        assert toons.loads('v: "3"', schema={"v": (int, str)}) == {"v": "3"}
+        assert toons.loads("v: null", schema={"v": (int, None)}) == {
+            "v": None
+        }
+        with pytest.raises(toons.ToonDecodeError, match="int or None"):
+            toons.loads("v: x", schema={"v": (int, None)})
+
+    def test_typing_forms(self):
+        schema = {
+            "a": List[int],
+            "b": Optional[float],
+            "c": Any,
+            "d": Dict[str, int],
+        }
+        text = "a[2]: 1,2\nb: 1\nc: x\nd:\n  k: 1"
+        assert toons.loads(text, schema=schema) == {
+            "a": [1, 2],
+            "b": 1.0,
+            "c": "x",
+            "d": {"k": 1},
+        }
+
+    def test_typed_dict(self):
+        class User(TypedDict):
+            id: int
+            tags: List[str]
+
+        class Extra(TypedDict, total=False):
+            nick: str
+
+        text = "id: 1\ntags[1]: admin"
+        assert toons.loads(text, schema=User) == {"id": 1, "tags": ["admin"]}
+        with pytest.raises(toons.ToonDecodeError, match="/tags/0"):
+            toons.loads("id: 1\ntags[1]: 2", schema=User)
+        assert toons.loads("a: 1", schema=Extra) == {"a": 1}
+
+    def test_class_checked_with_isinstance(self):
+        result = toons.loads(
+            "d: 2026-01-02", schema={"d": datetime.date}, parse_datetime=True
+        )
+        assert result == {"d": datetime.date(2026, 1, 2)}
+        with pytest.raises(toons.ToonDecodeError, match="expected date"):
+            toons.loads("d: 2026-01-02", schema={"d": datetime.date})
+
+    @pytest.mark.parametrize("spec", [5, {1: int}, [int, str], ()])
+    def test_invalid_schema(self, spec):
+        with pytest.raises(TypeError, match="schema"):
+            toons.loads("a: 1", schema=spec)
+
+    def test_checked_before_object_hook(self):
+        result = toons.loads(
+            "a: 1",
+            schema={"a": float},
+            object_hook=lambda d: sorted(d.items()),
+        )
+        assert result == [("a", 1.0)]
+
+    def test_decoder_and_load(self, tmp_path):
+        schema = {"a": float}
+        decoder = toons.TOONDecoder(schema=schema)
+        assert decoder.schema is schema
+        assert decoder.decode("a: 1") == {"a": 1.0}
+        path = tmp_path / "data.toon"
+        path.write_text("a: 1")
+        assert toons.load(path, schema=schema) == {"a": 1.0}
+        with pytest.raises(TypeError, match="schema"):
+            toons.TOONDecoder(schema=5)
+
+    def test_decoder_builds_schema_once(self):
+        schema = {"a": float}
+        decoder = toons.TOONDecoder(schema=schema)
+        schema["a"] = bool
+        assert decoder.decode("a: 1") == {"a": 1.0}
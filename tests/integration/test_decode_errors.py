@@ -54,6 +54,43 @@ class TestToonDecodeErrorAttributes:
         assert "b" in str(exc.source)
 
 
+class TestMismatchContext:
+    """Length mismatches name the offending row and what is off in it."""
+
+    def test_short_tabular_row_names_missing_fields(self):
+        content = "[2]{id,name,role,team}:\n  1,Ada,admin,core\n  2,Bob,dev\n"
+        with pytest.raises(toons.ToonDecodeError) as excinfo:
+            toons.loads(content)
+        exc = excinfo.value
+        assert exc.line == 3
+        assert exc.source == "  2,Bob,dev"
+        assert str(exc) == (
+            "TOON parse error at line 3: Tabular row has 3 values but "
+            "header defines 4 fields (missing team): 2,Bob,dev"
+        )
+
+    def test_long_tabular_row_names_extra_values(self):
+        content = "[1]{a,b}:\n  1,2,3,4\n"
+        with pytest.raises(toons.ToonDecodeError) as excinfo:
+            toons.loads(content)
+        assert str(excinfo.value).endswith(
+            "Tabular row has 4 values but header defines 2 fields "
+            "(extra 3, 4): 1,2,3,4"
+        )
+
+    def test_inline_array_mismatch_shows_values(self):
+        content = "a: 1\ntags[3|]: x|y\n"
+        with pytest.raises(toons.ToonDecodeError) as excinfo:
+            toons.loads(content)
+        exc = excinfo.value
+        assert exc.line == 2
+        assert exc.source == "tags[3|]: x|y"
+        assert str(exc) == (
+            "TOON parse error at line 2: "
+            "Array declared length 3 but found 2 elements: x|y"
+        )
+
+
 class TestToonDecodeErrorClassHierarchy:
     """ToonDecodeError MUST stay a subclass of ValueError for back-compat."""
 
@@ -87,3 +124,61 @@ class TestPathExpansionErrorClass:
         content = "parent: scalar\nparent.child: 1\n"
         with pytest.raises(toons.ToonDecodeError):
             toons.loads(content, expand_paths="safe")
+
+
+def nested_objects(depth):
+    """`depth` objects, each the only field of the one above it."""
+    lines = ["  " * level + "k:" for level in range(depth - 1)]
+    return "\n".join(lines + ["  " * (depth - 1) + "v: 1"])
+
+
+class TestMaxDepth:
+    """Deeply nested input raises instead of overflowing the stack."""
+
+    def test_default_accepts_moderate_nesting(self):
+        value = toons.loads(nested_objects(500))
+        for _ in range(499):
+            value = value["k"]
+        assert value == {"v": 1}
+
+    def test_limit_is_inclusive(self):
+        assert toons.loads(nested_objects(3), max_depth=3) == {
+            "k": {"k": {"v": 1}}
+        }
+        with pytest.raises(toons.ToonDecodeError) as exc:
+            toons.loads(nested_objects(4), max_depth=3)
+        assert "maximum nesting depth exceeded" in str(exc.value)
+        assert exc.value.line == 4
+
+    def test_hostile_nesting_raises(self):
+        with pytest.raises(
+            toons.ToonDecodeError, match="maximum nesting depth exceeded"
+        ):
+            toons.loads(nested_objects(3000), max_depth=100)
+
+    def test_nested_lists_count(self):
+        text = "[1]:\n  - [1]:\n    - [1]:\n      - x"
+        assert toons.loads(text, max_depth=3) == [[["x"]]]
+        with pytest.raises(toons.ToonDecodeError):
+            toons.loads(text, max_depth=2)
+
+    def test_list_item_objects_count(self):
+        text = "[1]:\n  - a:\n      b: 1"
+        assert toons.loads(text, max_depth=3) == [{"a": {"b": 1}}]
+        with pytest.raises(toons.ToonDecodeError):
+            toons.loads(text, max_depth=2)
+
+    def test_expanded_paths_count(self):
+        with pytest.raises(toons.ToonDecodeError):
+            toons.loads("a.b.c: 1", expand_paths="safe", max_depth=2)
+        deep = ".".join(["k"] * 100_000) + ": 1"
+        with pytest.raises(toons.ToonDecodeError):
+            toons.loads(deep, expand_paths="safe")
+
+    def test_validate_and_decoder_honour_limit(self):
+        with pytest.raises(toons.ToonDecodeError):
+            toons.validate(nested_objects(4), max_depth=3)
+        decoder = toons.TOONDecoder(max_depth=3)
+        assert decoder.max_depth == 3
+        with pytest.raises(toons.ToonDecodeError):
+            decoder.decode(nested_objects(4))
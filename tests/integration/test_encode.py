@@ -0,0 +1,2085 @@
+"""
+Tests for encoder options not covered by the specification fixtures.
+"""
+
+import datetime
+import io
+import json
+import math
+from collections import OrderedDict
+from fractions import Fraction
+
+import pytest
+
+import toons
+
+
+class TestSortKeys:
+    """Test the sort_keys option."""
+
+    def test_default_keeps_insertion_order(self):
+        """Keys are emitted in insertion order by default."""
+        data = {"b": 1, "a": 2}
+        assert toons.dumps(data) == "b: 1\na: 2"
+
+    def test_sort_keys_lexicographic(self):
+        """sort_keys=True sorts by code point."""
+        data = {"item10": 1, "item2": 2, "item1": 3}
+        assert toons.dumps(data, sort_keys=True) == (
+            "item1: 3\nitem10: 1\nitem2: 2"
+        )
+
+    def test_sort_keys_natural(self):
+        """sort_keys="natural" orders numeric suffixes numerically."""
+        data = {"item10": 1, "item2": 2, "item1": 3}
+        assert toons.dumps(data, sort_keys="natural") == (
+            "item1: 3\nitem2: 2\nitem10: 1"
+        )
+
+    def test_natural_sort_leading_zeros_and_multiple_runs(self):
+        """Digit runs compare by value; later runs break ties."""
+        data = {"v1.10": 1, "v1.9": 2, "v01.2": 3, "v1.2": 4}
+        assert toons.dumps(data, sort_keys="natural") == (
+            'v01.2: 3\nv1.2: 4\nv1.9: 2\nv1.10: 1'
+        )
+
+    def test_natural_sort_recursive(self):
+        """Nested objects and list-item objects are sorted too."""
+        data = {
+            "section10": {"key2": 1, "key1": 2},
+            "section9": [{"b": 1, "a": [1, {"z": 1, "y": 2}]}],
+        }
+        assert toons.dumps(data, sort_keys="natural") == (
+            "section9[1]:\n"
+            "  - a[2]:\n"
+            "      - 1\n"
+            "      - y: 2\n"
+            "        z: 1\n"
+            "    b: 1\n"
+            "section10:\n"
+            "  key1: 2\n"
+            "  key2: 1"
+        )
+
+    def test_natural_sort_tabular_fields(self):
+        """Tabular headers follow the requested order."""
+        data = [
+            {"col10": 1, "col2": 2, "col1": 3},
+            {"col10": 4, "col2": 5, "col1": 6},
+        ]
+        assert toons.dumps(data, sort_keys="natural") == (
+            "[2]{col1,col2,col10}:\n  3,2,1\n  6,5,4"
+        )
+
+    def test_sorted_output_round_trips(self):
+        """Sorting only changes order, never content."""
+        data = {"item10": [{"b": 1, "a": 2}], "item2": {"y": 1, "x": 2}}
+        assert toons.loads(toons.dumps(data, sort_keys="natural")) == data
+
+    def test_dump_accepts_sort_keys(self):
+        """dump() forwards sort_keys."""
+        fp = io.StringIO()
+        toons.dump({"b": 1, "a": 2}, fp, sort_keys=True)
+        assert fp.getvalue() == "a: 2\nb: 1"
+
+    def test_invalid_sort_keys(self):
+        """Unknown sort modes are rejected."""
+        with pytest.raises(ValueError, match="sort_keys"):
+            toons.dumps({"a": 1}, sort_keys="reverse")
+
+
+class TestStripTrailingZeros:
+    """Test strip_trailing_zeros together with float_precision."""
+
+    @pytest.mark.parametrize(
+        "value,stripped,padded",
+        [
+            (1.5, "1.5", "1.5"),
+            (0.5, "0.5", "0.50"),
+            (2.0, "2", "2.0"),
+            (0.1, "0.1", "0.10"),
+            (12.0, "12", "12"),
+            (-0.25, "-0.25", "-0.25"),
+        ],
+    )
+    def test_precision_two(self, value, stripped, padded):
+        """float_precision=2 pads to two significant digits unless stripped."""
+        assert toons.dumps({"v": value}, float_precision=2) == f"v: {stripped}"
+        assert (
+            toons.dumps(
+                {"v": value}, float_precision=2, strip_trailing_zeros=False
+            )
+            == f"v: {padded}"
+        )
+
+    def test_padding_preserved_on_decode_as_float(self):
+        """Padded output still decodes to the same float."""
+        out = toons.dumps(
+            {"v": 1.5}, float_precision=3, strip_trailing_zeros=False
+        )
+        assert out == "v: 1.50"
+        assert toons.loads(out) == {"v": 1.5}
+
+    def test_no_effect_without_precision(self):
+        """Without float_precision the shortest form is always used."""
+        assert toons.dumps({"v": 1.5}, strip_trailing_zeros=False) == "v: 1.5"
+
+    def test_zero_stays_zero(self):
+        """Zero is normalized regardless of padding."""
+        out = toons.dumps(
+            {"v": -0.0}, float_precision=3, strip_trailing_zeros=False
+        )
+        assert out == "v: 0"
+
+    def test_tabular_columns_padded(self):
+        """Padding applies inside tabular rows."""
+        data = [{"p": 1.5}, {"p": 2.25}]
+        assert (
+            toons.dumps(data, float_precision=3, strip_trailing_zeros=False)
+            == "[2]{p}:\n  1.50\n  2.25"
+        )
+
+
+class TestNonStringKeys:
+    """Test conversion of non-string dict keys and the skipkeys option."""
+
+    def test_int_and_bool_keys(self):
+        """Keys convert like json.dumps; {1: ..., True: ...} share a key."""
+        assert toons.dumps({1: "a", True: "b"}) == '"1": b'
+        assert toons.dumps({1: "a", False: "b"}) == '"1": a\nfalse: b'
+
+    def test_key_forms_match_json(self):
+        """Every supported key type produces the json.dumps key string."""
+        data = {2: "i", 1.5: "f", None: "n", "s": "s", True: "t"}
+        expected = list(json.loads(json.dumps(data)))
+        assert list(toons.loads(toons.dumps(data))) == expected
+
+    def test_float_key_keeps_repr(self):
+        """Float keys use repr, so 1.0 stays "1.0"."""
+        assert toons.dumps({1.0: "x"}) == '"1.0": x'
+
+    def test_key_order_preserved(self):
+        """Converted keys stay in their original position."""
+        data = {"a": 1, 2: 2, "c": 3}
+        assert toons.dumps(data) == 'a: 1\n"2": 2\nc: 3'
+
+    def test_nested_dict_keys(self):
+        """Conversion applies at every level."""
+        assert toons.loads(toons.dumps({"m": {10: "ten"}})) == {
+            "m": {"10": "ten"}
+        }
+
+    def test_tabular_with_int_keys(self):
+        """Rows with numeric keys still use the tabular form."""
+        data = [{1: "a", 2: "b"}, {1: "c", 2: "d"}]
+        assert toons.dumps(data) == '[2]{"1","2"}:\n  a,b\n  c,d'
+
+    def test_unsupported_key_raises(self):
+        """Keys of other types raise TypeError by default."""
+        with pytest.raises(TypeError, match="keys must be"):
+            toons.dumps({(1, 2): "x"})
+
+    def test_skipkeys_drops_unsupported(self):
+        """skipkeys=True drops unsupported keys silently."""
+        data = {(1, 2): "x", "ok": 1, 3: "y"}
+        assert toons.dumps(data, skipkeys=True) == 'ok: 1\n"3": y'
+
+
+class TestTupleKeySep:
+    """Test joining tuple dict keys with tuple_key_sep."""
+
+    def test_mixed_items(self):
+        assert toons.dumps({("x", 1): "v"}, tuple_key_sep=".") == "x.1: v"
+
+    def test_items_convert_like_keys(self):
+        data = {("a", None, True, 1.5): 1, ("b", (1, 2)): 2}
+        assert toons.dumps(data, tuple_key_sep="/") == (
+            '"a/null/true/1.5": 1\n"b/(1, 2)": 2'
+        )
+
+    def test_tabular_fields(self):
+        data = [{("p", "x"): 1, ("p", "y"): 2}, {("p", "x"): 3, ("p", "y"): 4}]
+        assert toons.dumps(data, tuple_key_sep="_") == (
+            "[2]{p_x,p_y}:\n  1,2\n  3,4"
+        )
+
+    def test_expand_paths_restores_nesting(self):
+        data = {("eu", "sales"): 10, ("eu", "costs"): 4, "total": 14}
+        text = toons.dumps(data, tuple_key_sep=".")
+        assert toons.loads(text, expand_paths="safe") == {
+            "eu": {"sales": 10, "costs": 4},
+            "total": 14,
+        }
+
+    def test_default_still_raises(self):
+        with pytest.raises(TypeError, match="not tuple"):
+            toons.dumps({("x", 1): "v"})
+
+
+class TestShareByIdentity:
+    """Test share_by_identity aliases and resolve_aliases decoding."""
+
+    def test_shared_list_from_two_keys(self):
+        """The second reference becomes an alias to the first."""
+        shared = [1, 2, 3]
+        out = toons.dumps({"a": shared, "b": shared}, share_by_identity=True)
+        assert out == "a[3]: 1,2,3\nb: */a"
+
+        data = toons.loads(out, resolve_aliases=True)
+        assert data == {"a": [1, 2, 3], "b": [1, 2, 3]}
+        assert data["a"] is data["b"]
+
+    def test_equal_but_distinct_objects_not_aliased(self):
+        """Sharing is by identity, not structural equality."""
+        data = {"a": [1, 2], "b": [1, 2]}
+        assert toons.dumps(data, share_by_identity=True) == toons.dumps(data)
+
+    def test_default_writes_repeats_in_full(self):
+        """Without the option shared objects are duplicated."""
+        shared = {"x": 1}
+        assert toons.dumps({"a": shared, "b": shared}) == (
+            "a:\n  x: 1\nb:\n  x: 1"
+        )
+
+    def test_nested_path_and_list_index(self):
+        """Alias paths name dict keys and list indices."""
+        shared = {"id": 7}
+        data = {"users": [{"name": "x", "profile": shared}], "me": shared}
+        out = toons.dumps(data, share_by_identity=True)
+        assert out.endswith("me: */users/0/profile")
+        decoded = toons.loads(out, resolve_aliases=True)
+        assert decoded["me"] is decoded["users"][0]["profile"]
+
+    def test_cycle_is_aliased(self):
+        """A self-reference becomes an alias to an ancestor."""
+        node = {"name": "root"}
+        node["self"] = node
+        out = toons.dumps(node, share_by_identity=True)
+        assert out == "name: root\nself: *"
+        decoded = toons.loads(out, resolve_aliases=True)
+        assert decoded["self"] is decoded
+
+    def test_cycle_without_sharing_raises(self):
+        """Cycles are reported instead of recursing forever."""
+        items = []
+        items.append(items)
+        with pytest.raises(ValueError, match="Circular reference"):
+            toons.dumps(items)
+
+    def test_key_segments_are_percent_encoded(self):
+        """Keys with reserved characters never need quoting in aliases."""
+        shared = [1]
+        data = {"a b/c": shared, "d": shared}
+        out = toons.dumps(data, share_by_identity=True)
+        assert out.endswith("d: */a%20b%2Fc")
+        decoded = toons.loads(out, resolve_aliases=True)
+        assert decoded["d"] is decoded["a b/c"]
+
+    def test_literal_star_strings_are_quoted(self):
+        """Strings that look like aliases are quoted and left alone."""
+        data = {"s": "*/a", "a": 1}
+        out = toons.dumps(data, share_by_identity=True)
+        assert out == 's: "*/a"\na: 1'
+        assert toons.loads(out, resolve_aliases=True) == data
+
+    def test_aliases_are_strings_without_resolution(self):
+        """Plain loads keeps alias tokens as strings."""
+        assert toons.loads("a[1]: 1\nb: */a") == {"a": [1], "b": "*/a"}
+
+    def test_unresolved_alias_raises(self):
+        with pytest.raises(toons.ToonDecodeError, match="Unresolved alias"):
+            toons.loads("a: 1\nb: */missing", resolve_aliases=True)
+
+    @pytest.mark.parametrize(
+        "text, line, source",
+        [
+            ("a: */nope", 1, "a: */nope"),
+            ("a: */nope\nb: 1", 1, "a: */nope"),
+            ("a[2]: */nope,1", 1, "a[2]: */nope,1"),
+            ("a[1]:\n  - */nope", 2, "  - */nope"),
+            ("- a: */nope", 1, "- a: */nope"),
+        ],
+    )
+    def test_unresolved_alias_reports_its_line(self, text, line, source):
+        with pytest.raises(toons.ToonDecodeError) as exc:
+            toons.loads(text, resolve_aliases=True)
+        assert exc.value.line == line
+        assert exc.value.source == source
+
+    def test_key_folding_conflict(self):
+        with pytest.raises(ValueError, match="key_folding"):
+            toons.dumps({}, share_by_identity=True, key_folding="safe")
+
+
+class TestNestedKeyFolding:
+    """Key folding applies below the root, with collision checks."""
+
+    def test_folds_at_depth_one(self):
+        data = {"outer": {"a": {"b": 1}, "c": 2}}
+        out = toons.dumps(data, key_folding="safe")
+        assert out == "outer:\n  a.b: 1\n  c: 2"
+        assert toons.loads(out, expand_paths="safe") == data
+
+    def test_folds_at_depth_two(self):
+        data = {"x": {"y": 1, "outer": {"m": {"a": {"b": [1, 2]}}, "c": 2}}}
+        out = toons.dumps(data, key_folding="safe")
+        assert out == "x:\n  y: 1\n  outer:\n    m.a.b[2]: 1,2\n    c: 2"
+        assert toons.loads(out, expand_paths="safe") == data
+
+    def test_flatten_depth_counts_from_fold_start(self):
+        data = {"x": {"y": 1, "outer": {"a": {"b": {"c": 1}}, "z": 2}}}
+        out = toons.dumps(data, key_folding="safe", flatten_depth=2)
+        assert out == (
+            "x:\n  y: 1\n  outer:\n    a.b:\n      c: 1\n    z: 2"
+        )
+        assert toons.loads(out, expand_paths="safe") == data
+
+    def test_sibling_collision_blocks_fold(self):
+        data = {"o": {"a": {"b": 1}, "a.b": 2}}
+        out = toons.dumps(data, key_folding="safe")
+        assert out == 'o:\n  a:\n    b: 1\n  a.b: 2'
+
+    def test_enclosing_dotted_key_blocks_fold(self):
+        data = {"r": {"a": {"b": {"c": 1}, "z": 0}, "a.b.c": 5}}
+        out = toons.dumps(data, key_folding="safe")
+        assert out == (
+            "r:\n  a:\n    b:\n      c: 1\n    z: 0\n  a.b.c: 5"
+        )
+
+    def test_disabled_by_default(self):
+        data = {"outer": {"a": {"b": 1}, "c": 2}}
+        assert toons.dumps(data) == "outer:\n  a:\n    b: 1\n  c: 2"
+
+
+class TestTabularMode:
+    """Test the tabular option ("auto", "fill", "never")."""
+
+    ROWS = [{"id": 1, "name": "A"}, {"id": 2}, {"id": 3, "role": "x"}]
+
+    def test_auto_falls_back_for_differing_keys(self):
+        """The default only uses tabular form for uniform rows."""
+        out = toons.dumps({"rows": self.ROWS})
+        assert out.startswith("rows[3]:\n  - id: 1")
+
+    def test_auto_ignores_key_insertion_order(self):
+        """Rows with the same keys in a different order are still tabular."""
+        data = [{"a": 1, "b": 2}, {"b": 4, "a": 3}]
+        assert toons.dumps(data) == "[2]{a,b}:\n  1,2\n  3,4"
+        assert toons.loads(toons.dumps(data)) == data
+
+    def test_auto_header_follows_first_row(self):
+        """The header takes the first row's key order; later rows follow it."""
+        data = {"rows": [{"b": 1, "a": 2}, {"a": 3, "b": 4}, {"a": 5, "b": 6}]}
+        assert toons.dumps(data) == "rows[3]{b,a}:\n  1,2\n  4,3\n  6,5"
+
+    def test_wide_rows_with_equal_but_distinct_keys(self):
+        """Keys built per row, partly reordered, still land in their column."""
+        names = [f"c{j}" for j in range(50)]
+        rows = [
+            {"".join(["c", str(j)]): i * j for j in range(50)}
+            for i in range(3)
+        ]
+        rows.append(dict(reversed(list(rows[1].items()))))
+        out = toons.dumps(rows, tabular="fill")
+        assert out.splitlines()[0] == f"[4]{{{','.join(names)}}}:"
+        assert out.splitlines()[4] == out.splitlines()[2]
+        assert toons.loads(out) == rows
+
+    def test_auto_falls_back_for_same_size_different_keys(self):
+        """Rows of equal length but different keys are not tabular."""
+        out = toons.dumps([{"a": 1, "b": 2}, {"a": 3, "c": 4}])
+        assert out == "[2]:\n  - a: 1\n    b: 2\n  - a: 3\n    c: 4"
+
+    def test_fill_uses_union_of_keys(self):
+        """fill emits the key union in first-seen order, null when missing."""
+        assert toons.dumps({"rows": self.ROWS}, tabular="fill") == (
+            "rows[3]{id,name,role}:\n"
+            "  1,A,null\n"
+            "  2,null,null\n"
+            "  3,null,x"
+        )
+
+    def test_fill_roundtrip_has_explicit_nulls(self):
+        """Decoding a filled table yields every key on every row."""
+        out = toons.dumps(self.ROWS, tabular="fill")
+        assert toons.loads(out) == [
+            {"id": 1, "name": "A", "role": None},
+            {"id": 2, "name": None, "role": None},
+            {"id": 3, "name": None, "role": "x"},
+        ]
+
+    def test_fill_respects_sort_keys(self):
+        """sort_keys orders the union header."""
+        out = toons.dumps([{"b": 1}, {"a": 2}], tabular="fill", sort_keys=True)
+        assert out == "[2]{a,b}:\n  null,1\n  2,null"
+
+    def test_fill_still_requires_primitive_values(self):
+        """Rows with nested values stay in expanded form."""
+        data = [{"a": 1}, {"b": [1, 2]}]
+        assert toons.dumps(data, tabular="fill") == toons.dumps(data)
+
+    def test_fill_sparse_nested_column_disqualifies(self):
+        """A column only some rows have, always nested there, is no cell."""
+        data = [{"a": 1}, {"a": 2, "meta": {"x": 1}}, {"a": 3}]
+        assert toons.dumps(data, tabular="fill") == toons.dumps(data)
+
+    def test_fill_sparse_list_column_with_nested_arrays(self):
+        """Primitive lists count as cells under tabular_nested_arrays."""
+        data = [{"a": 1}, {"a": 2, "tags": ["x", "y"]}]
+        out = toons.dumps(data, tabular="fill", tabular_nested_arrays=True)
+        assert out == "[2]{a,tags}:\n  1,null\n  2,[2|]: x|y"
+
+    def test_fill_matches_auto_for_uniform_rows(self):
+        data = [{"a": 1, "b": 2}, {"a": 3, "b": 4}]
+        assert toons.dumps(data, tabular="fill") == toons.dumps(data)
+
+    def test_never_uses_expanded_list(self):
+        data = {"rows": [{"a": 1, "b": 2}, {"a": 3, "b": 4}]}
+        out = toons.dumps(data, tabular="never")
+        assert out == "rows[2]:\n  - a: 1\n    b: 2\n  - a: 3\n    b: 4"
+        assert toons.loads(out) == data
+
+    def test_invalid_mode(self):
+        with pytest.raises(ValueError, match="tabular"):
+            toons.dumps([], tabular="always")
+
+
+class TestTabularFieldOrder:
+    """Test the tabular_field_order option ("first", "sorted", "union")."""
+
+    ROWS = [{"a": 1, "c": 3}, {"a": 4, "b": 5, "c": 6}, {"d": 7, "a": 8}]
+
+    def header(self, data, **kwargs):
+        return toons.dumps(data, **kwargs).split("\n")[0]
+
+    @pytest.mark.parametrize(
+        "order,expected",
+        [
+            ("first", "[3]{a,c,b,d}:"),
+            ("sorted", "[3]{a,b,c,d}:"),
+            ("union", "[3]{d,a,b,c}:"),
+        ],
+    )
+    def test_fill_headers(self, order, expected):
+        header = self.header(
+            self.ROWS, tabular="fill", tabular_field_order=order
+        )
+        assert header == expected
+
+    @pytest.mark.parametrize(
+        "order,expected",
+        [
+            ("first", "[2]{z,a}:"),
+            ("sorted", "[2]{a,z}:"),
+            ("union", "[2]{z,a}:"),
+        ],
+    )
+    def test_first_ordered_dict_sets_uniform_header(self, order, expected):
+        data = [OrderedDict([("z", 1), ("a", 2)]), {"a": 3, "z": 4}]
+        out = toons.dumps(data, tabular_field_order=order)
+        assert out.split("\n")[0] == expected
+        assert toons.loads(out) == data
+
+    def test_default_is_first(self):
+        assert self.header(self.ROWS, tabular="fill") == self.header(
+            self.ROWS, tabular="fill", tabular_field_order="first"
+        )
+
+    def test_union_cells_follow_header(self):
+        data = [{"a": 1, "c": 3}, {"a": 4, "b": 5, "c": 6}]
+        out = toons.dumps(data, tabular="fill", tabular_field_order="union")
+        assert out == "[2]{a,b,c}:\n  1,null,3\n  4,5,6"
+
+    def test_sort_keys_takes_precedence(self):
+        data = [{"item10": 1, "item2": 2}]
+        out = self.header(
+            data, sort_keys="natural", tabular_field_order="sorted"
+        )
+        assert out == "[1]{item2,item10}:"
+
+    def test_objects_keep_insertion_order(self):
+        data = {"b": 1, "rows": [{"y": 1, "x": 2}]}
+        out = toons.dumps(data, tabular_field_order="sorted")
+        assert out == "b: 1\nrows[1]{x,y}:\n  2,1"
+
+    def test_invalid_order(self):
+        with pytest.raises(ValueError, match="tabular_field_order"):
+            toons.dumps([], tabular_field_order="last")
+
+
+class TestTabularNestedArrays:
+    """Test the tabular_nested_arrays option."""
+
+    ROWS = [
+        {"name": "x", "tags": ["a", "b"]},
+        {"name": "y", "tags": []},
+        {"name": "z", "tags": ["c,d", "e|f", 1, None]},
+    ]
+
+    def test_primitive_list_cells(self):
+        out = toons.dumps(self.ROWS, tabular_nested_arrays=True)
+        assert out == (
+            "[3]{name,tags}:\n"
+            "  x,[2|]: a|b\n"
+            "  y,[0|]:\n"
+            '  z,[4|]: "c,d"|"e|f"|1|null'
+        )
+
+    def test_non_comma_delimiter_uses_comma_inside(self):
+        out = toons.dumps(self.ROWS, tabular_nested_arrays=True, delimiter="|")
+        assert out.split("\n")[1] == "  x|[2]: a,b"
+
+    @pytest.mark.parametrize(
+        "kwargs",
+        [
+            {},
+            {"delimiter": "|"},
+            {"delimiter": "\t"},
+            {"align": True},
+            {"compact": True},
+            {"typed_tabular": True},
+        ],
+        ids=repr,
+    )
+    def test_round_trip(self, kwargs):
+        text = toons.dumps(self.ROWS, tabular_nested_arrays=True, **kwargs)
+        assert toons.loads(text) == self.ROWS
+        assert toons.loads(text, strict=False) == self.ROWS
+
+    def test_array_in_first_column(self):
+        data = {"rows": [{"t": [1, 2], "n": "x"}, {"t": [], "n": "y"}], "k": 1}
+        text = toons.dumps(data, tabular_nested_arrays=True)
+        assert text == "rows[2]{t,n}:\n  [2|]: 1|2,x\n  [0|]:,y\nk: 1"
+        assert toons.loads(text) == data
+
+    def test_default_falls_back_to_expanded(self):
+        out = toons.dumps(self.ROWS)
+        assert out.startswith("[3]:\n  - name: x\n    tags[2]: a,b")
+
+    def test_nested_containers_still_fall_back(self):
+        data = [{"a": [[1]]}, {"a": [{"b": 1}]}]
+        assert toons.dumps(data, tabular_nested_arrays=True) == toons.dumps(
+            data
+        )
+
+
+class TestEstimateSize:
+    """estimate_size must equal the UTF-8 length of dumps exactly."""
+
+    CASES = [
+        {},
+        "plain",
+        42,
+        {"name": "Alice", "tags": ["a", "b"]},
+        {"text": "needs: quoting", "empty": "", "n": -1.5},
+        {"rows": [{"id": 1, "name": "A"}, {"id": 2, "name": "B"}]},
+        {"nested": {"deep": {"list": [1, [2, 3], {"k": "v"}]}}},
+        {"unicode": "café 名前", "emoji": "🙂"},
+        [{"a": 1}, "mixed", [1, 2]],
+    ]
+
+    @pytest.mark.parametrize("obj", CASES)
+    def test_matches_dumps(self, obj):
+        expected = len(toons.dumps(obj).encode("utf-8"))
+        assert toons.estimate_size(obj) == expected
+
+    @pytest.mark.parametrize(
+        "options",
+        [
+            {"indent": 4},
+            {"delimiter": "|"},
+            {"delimiter": "\t"},
+            {"key_folding": "safe"},
+            {"sort_keys": True},
+            {"float_precision": 3, "strip_trailing_zeros": False},
+            {"tabular": "never"},
+        ],
+    )
+    def test_matches_dumps_with_options(self, options):
+        obj = {
+            "a": {"b": {"c": 1.25}},
+            "rows": [{"x": 1, "y": "p, q"}, {"x": 2, "y": "r"}],
+        }
+        expected = len(toons.dumps(obj, **options).encode("utf-8"))
+        assert toons.estimate_size(obj, **options) == expected
+
+    def test_invalid_options_raise(self):
+        with pytest.raises(ValueError):
+            toons.estimate_size({}, indent=1)
+
+
+class TestCheckRoundtrip:
+    """check_roundtrip decodes the output of dumps and compares it."""
+
+    DATA = {
+        "a": {"b": {"c": 1.25}},
+        "rows": [{"x": 1, "y": "p, q"}, {"x": 2, "y": "r"}],
+        "flags": [True, False, None],
+        "text": "caf\u00e9: 42",
+    }
+
+    @pytest.mark.parametrize(
+        "options",
+        [
+            {},
+            {"delimiter": "|"},
+            {"key_folding": "safe"},
+            {"sort_keys": True},
+            {"share_by_identity": True},
+            {"typed_tabular": True},
+            {"null_literal": "None"},
+            {"indent_char": "\t"},
+            {"header_comment": "note"},
+            {"ensure_ascii": True},
+        ],
+    )
+    def test_matching_data_returns_true(self, options):
+        assert toons.check_roundtrip(self.DATA, **options) is True
+
+    def test_converted_scalars_are_not_compared(self):
+        data = {"day": datetime.date(2024, 1, 15), 3: "int key"}
+        assert toons.check_roundtrip(data) is True
+
+    def test_nan_needs_a_token(self):
+        with pytest.raises(ValueError, match=r"/nan: expected nan, got None"):
+            toons.check_roundtrip({"nan": math.nan})
+        assert toons.check_roundtrip({"nan": math.nan}, nan_token="NaN")
+
+    def test_float_precision_within_last_digit(self):
+        assert toons.check_roundtrip([1 / 3], float_precision=3)
+
+    def test_filled_rows_report_extra_keys(self):
+        data = {"rows": [{"a": 1}, {"b": 2}]}
+        with pytest.raises(ValueError) as info:
+            toons.check_roundtrip(data, tabular="fill")
+        assert str(info.value).splitlines()[1:] == [
+            "  /rows/0/b: not in the original",
+            "  /rows/1/a: not in the original",
+        ]
+
+    def test_mismatches_are_listed_up_to_ten(self):
+        data = {f"k{i}": 10**30 for i in range(12)}
+        with pytest.raises(ValueError) as info:
+            toons.check_roundtrip(data)
+        lines = str(info.value).splitlines()
+        assert lines[1] == (
+            "  /k0: expected 1000000000000000000000000000000, got 1e+30"
+        )
+        assert len(lines) == 12
+        assert lines[-1] == "  ... and 2 more"
+
+    @pytest.mark.parametrize(
+        "kwargs", [{}, {"header_comment": "note"}, {"ensure_ascii": True}]
+    )
+    @pytest.mark.parametrize(
+        "data", ["#x", "#é", [{"tag": "#1", "n": 1}, {"tag": "#2", "n": 2}]]
+    )
+    def test_hash_leading_values(self, data, kwargs):
+        assert toons.check_roundtrip(data, **kwargs)
+
+    def test_root_mismatch(self):
+        with pytest.raises(ValueError, match=r"\(root\): expected"):
+            toons.check_roundtrip(10**30)
+
+    def test_invalid_options_raise(self):
+        with pytest.raises(ValueError, match="indent"):
+            toons.check_roundtrip({}, indent=1)
+
+
+class TestTabularNever:
+    """tabular="never" writes expanded lists and round-trips losslessly."""
+
+    DATA = {
+        "users": [{"id": 1, "name": "Ada"}, {"id": 2, "name": "Bob"}],
+        "groups": [
+            {"name": "g", "members": [{"id": 1}, {"id": 2}]},
+        ],
+        "matrix": [[{"a": 1}, {"a": 2}]],
+    }
+
+    def test_root_array_expanded(self):
+        out = toons.dumps([{"a": 1}, {"a": 2}], tabular="never")
+        assert out == "[2]:\n  - a: 1\n  - a: 2"
+
+    def test_no_tabular_headers(self):
+        assert "{" not in toons.dumps(self.DATA, tabular="never")
+
+    @pytest.mark.parametrize(
+        "options",
+        [{}, {"delimiter": "|"}, {"delimiter": "\t"}, {"indent": 4}],
+    )
+    def test_roundtrip(self, options):
+        """Delimiter and indent settings combine with tabular="never"."""
+        out = toons.dumps(self.DATA, tabular="never", **options)
+        assert toons.loads(out, indent=options.get("indent")) == self.DATA
+
+    def test_auto_roundtrip_of_nested_tables(self):
+        """A uniform table nested in a list item decodes in auto mode too."""
+        out = toons.dumps(self.DATA)
+        assert "  - [2]{a}:" in out
+        assert toons.loads(out) == self.DATA
+
+
+class TestFloatPrecision:
+    """Test float formatting with and without float_precision."""
+
+    @pytest.mark.parametrize(
+        "value", [0.1, 1 / 3, 2.5e-8, 123456.789, 1e20, 5e-324, -7.25]
+    )
+    def test_default_is_shortest_roundtrip(self, value):
+        """Without a precision floats parse back to the exact same value."""
+        out = toons.dumps({"v": value})
+        assert toons.loads(out)["v"] == value
+
+    def test_default_shortest_forms(self):
+        assert toons.dumps(0.1) == "0.1"
+        assert toons.dumps(1 / 3) == "0.3333333333333333"
+
+    @pytest.mark.parametrize(
+        "value,digits,expected",
+        [
+            (1 / 3, 3, "0.333"),
+            (2 / 3, 3, "0.667"),
+            (123456.789, 4, "123500"),
+            (0.000123456, 2, "0.00012"),
+            (-9.999, 2, "-10"),
+            (1.0, 5, "1"),
+        ],
+    )
+    def test_significant_digits(self, value, digits, expected):
+        assert toons.dumps(value, float_precision=digits) == expected
+
+    @pytest.mark.parametrize("digits", [None, 3])
+    def test_negative_zero_normalized(self, digits):
+        assert toons.dumps({"v": -0.0}, float_precision=digits) == "v: 0"
+
+    @pytest.mark.parametrize("digits", [None, 3])
+    def test_large_floats_never_use_exponent(self, digits):
+        for value in (1e20, 1.5e300, 1.7976931348623157e308):
+            out = toons.dumps(value, float_precision=digits)
+            assert "e" not in out.lower()
+            assert abs(float(out) - value) <= value * 1e-2
+
+    def test_tiny_floats_never_use_exponent(self):
+        out = toons.dumps(1e-10)
+        assert out == "0.0000000001"
+        assert toons.loads(out) == 1e-10
+
+    def test_zero_precision_rejected(self):
+        with pytest.raises(ValueError, match="float_precision"):
+            toons.dumps(1.5, float_precision=0)
+
+
+class TestAllowExponent:
+    """Tests for the allow_exponent option"""
+
+    @pytest.mark.parametrize(
+        "value,expected",
+        [
+            (1e-10, "1e-10"),
+            (1e20, "1e+20"),
+            (-1.5e20, "-1.5e+20"),
+            (6.02214076e23, "6.02214076e+23"),
+            (1e16, "1e+16"),
+            (5e-5, "5e-5"),
+        ],
+    )
+    def test_exponent_form(self, value, expected):
+        assert toons.dumps(value, allow_exponent=True) == expected
+
+    @pytest.mark.parametrize("value", [1e-4, 0.5, 123.25, 1e15, 0.0])
+    def test_moderate_floats_stay_decimal(self, value):
+        out = toons.dumps(value, allow_exponent=True)
+        assert out == toons.dumps(value)
+        assert "e" not in out
+
+    def test_ints_never_use_exponent(self):
+        assert toons.dumps(10**20, allow_exponent=True) == str(10**20)
+
+    def test_with_float_precision(self):
+        out = toons.dumps(1.23456e-10, allow_exponent=True, float_precision=3)
+        assert out == "1.23e-10"
+        out = toons.dumps(
+            1e20,
+            allow_exponent=True,
+            float_precision=3,
+            strip_trailing_zeros=False,
+        )
+        assert out == "1.00e+20"
+
+    @pytest.mark.parametrize(
+        "value", [1e-10, 1e20, -2.5e-300, 1.7976931348623157e308, 5e-324]
+    )
+    def test_roundtrip(self, value):
+        out = toons.dumps({"v": [value]}, allow_exponent=True)
+        assert toons.loads(out)["v"] == [value]
+
+    @pytest.mark.parametrize("text", ["1e5", "1E+5", "2.5e-3"])
+    def test_exponent_like_strings_quoted(self, text):
+        out = toons.dumps({"s": text}, allow_exponent=True)
+        assert out == f's: "{text}"'
+        assert toons.loads(out) == {"s": text}
+
+
+class TestPreserveFloat:
+    """Tests for the preserve_float option"""
+
+    @pytest.mark.parametrize(
+        "value,expected",
+        [
+            (1.0, "1.0"),
+            (-3.0, "-3.0"),
+            (0.0, "0.0"),
+            (-0.0, "0.0"),
+            (2.5, "2.5"),
+        ],
+    )
+    def test_integral_floats_keep_point(self, value, expected):
+        assert toons.dumps(value, preserve_float=True) == expected
+
+    def test_default_drops_point(self):
+        assert toons.dumps({"x": 1.0}) == "x: 1"
+        assert type(toons.loads(toons.dumps({"x": 1.0}))["x"]) is int
+
+    def test_ints_unaffected(self):
+        assert toons.dumps([1, 0, 10**20], preserve_float=True) == (
+            "[3]: 1,0,100000000000000000000"
+        )
+
+    def test_large_integral_float(self):
+        out = toons.dumps(1e20, preserve_float=True)
+        assert out == "100000000000000000000.0"
+        assert toons.loads(out) == 1e20
+
+    def test_with_float_precision(self):
+        assert toons.dumps(2.0004, float_precision=3, preserve_float=True) == (
+            "2.0"
+        )
+        assert toons.dumps(1234.5, float_precision=2, preserve_float=True) == (
+            "1200.0"
+        )
+
+    def test_with_exponent(self):
+        out = toons.dumps(1e20, preserve_float=True, allow_exponent=True)
+        assert out == "1e+20"
+        assert type(toons.loads(out)) is float
+
+    @pytest.mark.parametrize(
+        "data",
+        [
+            {"x": 1.0, "n": 1},
+            {"rows": [{"a": 1.0, "b": 2}, {"a": 3.5, "b": 4}]},
+            [0.0, -2.0, 7],
+        ],
+    )
+    def test_roundtrip_keeps_types(self, data):
+        result = toons.loads(toons.dumps(data, preserve_float=True))
+        assert result == data
+        assert repr(result) == repr(data)
+
+
+class TestAllowNan:
+    """Tests for the allow_nan option"""
+
+    @pytest.mark.parametrize(
+        "value", [float("nan"), float("inf"), float("-inf")]
+    )
+    def test_default_writes_null(self, value):
+        assert toons.dumps({"v": value}) == "v: null"
+
+    @pytest.mark.parametrize(
+        "value", [float("nan"), float("inf"), float("-inf")]
+    )
+    def test_disallowed_raises(self, value):
+        with pytest.raises(ValueError, match="Out of range float value"):
+            toons.dumps({"v": value}, allow_nan=False)
+
+    def test_nested_non_finite_raises(self):
+        data = {"rows": [{"a": 1.0}, {"a": float("inf")}]}
+        with pytest.raises(ValueError, match="Out of range float value"):
+            toons.dumps(data, allow_nan=False)
+
+    def test_finite_values_unaffected(self):
+        data = {"a": 1.5, "b": [0.0, -2.25]}
+        assert toons.dumps(data, allow_nan=False) == toons.dumps(data)
+
+    def test_estimate_size_raises(self):
+        with pytest.raises(ValueError, match="Out of range float value"):
+            toons.estimate_size([float("nan")], allow_nan=False)
+
+
+class TestFloatTokens:
+    """Tests for the nan_token, inf_token and neg_inf_token options"""
+
+    TOKENS = {
+        "nan_token": "NaN",
+        "inf_token": "Infinity",
+        "neg_inf_token": "-Infinity",
+    }
+
+    def test_tokens_written_quoted(self):
+        data = {"a": float("nan"), "b": [float("inf"), float("-inf"), 1.5]}
+        assert toons.dumps(data, **self.TOKENS) == (
+            'a: "NaN"\nb[3]: "Infinity","-Infinity",1.5'
+        )
+
+    def test_unset_tokens_still_null(self):
+        data = [float("nan"), float("inf")]
+        assert toons.dumps(data, nan_token="NaN") == '[2]: "NaN",null'
+
+    def test_token_takes_precedence_over_allow_nan(self):
+        out = toons.dumps({"v": float("nan")}, allow_nan=False, nan_token="n")
+        assert out == 'v: "n"'
+        with pytest.raises(ValueError, match="Out of range float value"):
+            toons.dumps(
+                {"v": float("inf")}, allow_nan=False, nan_token="n"
+            )
+
+    def test_tabular_cells(self):
+        data = [{"x": float("nan"), "y": 1.5}, {"x": 2.5, "y": float("inf")}]
+        out = toons.dumps(data, typed_tabular=True, **self.TOKENS)
+        assert out == '[2]{x:float,y:float}:\n  "NaN",1.5\n  2.5,"Infinity"'
+
+    def test_round_trip(self):
+        data = {
+            "a": float("nan"),
+            "rows": [{"x": float("-inf")}, {"x": float("inf")}],
+        }
+        result = toons.loads(toons.dumps(data, **self.TOKENS), **self.TOKENS)
+        assert math.isnan(result["a"])
+        assert result["rows"] == [{"x": float("-inf")}, {"x": float("inf")}]
+
+    @pytest.mark.parametrize(
+        "kwargs",
+        [
+            {"nan_token": ""},
+            {"inf_token": 'a"b'},
+            {"neg_inf_token": "a\\b"},
+            {"nan_token": "x", "neg_inf_token": "x"},
+        ],
+        ids=repr,
+    )
+    def test_invalid_tokens_rejected(self, kwargs):
+        with pytest.raises(ValueError):
+            toons.dumps(1.0, **kwargs)
+        with pytest.raises(ValueError):
+            toons.loads("1", **kwargs)
+
+
+class TestCompact:
+    """Tests for the compact option"""
+
+    DATA = {
+        "name": "Alice",
+        "tags": ["a", "b"],
+        "empty": [],
+        "meta": {"x": 1, "note": "q: r"},
+        "rows": [{"a": 1, "b": 2}, {"a": 3, "b": 4}],
+        "items": [{"k": 1, "m": [1, 2]}, [3, 4], "s"],
+    }
+
+    def test_no_space_after_colons(self):
+        assert toons.dumps(self.DATA, compact=True) == (
+            "name:Alice\n"
+            "tags[2]:a,b\n"
+            "empty[0]:\n"
+            "meta:\n"
+            "  x:1\n"
+            '  note:"q: r"\n'
+            "rows[2]{a,b}:\n"
+            "  1,2\n"
+            "  3,4\n"
+            "items[3]:\n"
+            "  - k:1\n"
+            "    m[2]:1,2\n"
+            "  - [2]:3,4\n"
+            "  - s"
+        )
+
+    @pytest.mark.parametrize("strict", [True, False])
+    def test_round_trips(self, strict):
+        text = toons.dumps(self.DATA, compact=True)
+        assert toons.loads(text, strict=strict) == self.DATA
+
+    def test_folded_keys(self):
+        data = {"a": {"b": {"c": 1}}, "d": {"e": {"f": [1, 2]}}}
+        text = toons.dumps(data, compact=True, key_folding="safe")
+        assert text == "a.b.c:1\nd.e.f[2]:1,2"
+        assert toons.loads(text, expand_paths="safe") == data
+
+    def test_root_inline_array(self):
+        assert toons.dumps([1, 2], compact=True) == "[2]:1,2"
+
+    def test_default_keeps_space(self):
+        assert toons.dumps({"a": 1, "b": [1]}) == "a: 1\nb[1]: 1"
+
+
+class TestTimedelta:
+    """Tests for timedelta values and the timedelta_as option"""
+
+    ROWS = [
+        {"id": 1, "elapsed": datetime.timedelta(minutes=5)},
+        {"id": 2, "elapsed": datetime.timedelta(seconds=1.5)},
+    ]
+
+    def test_table_of_timedeltas_has_no_nulls(self):
+        out = toons.dumps(self.ROWS)
+        assert out == "[2]{id,elapsed}:\n  1,300\n  2,1.5"
+        assert "null" not in out
+
+    def test_iso_table(self):
+        out = toons.dumps(self.ROWS, timedelta_as="iso")
+        assert out == "[2]{id,elapsed}:\n  1,PT5M\n  2,PT1.5S"
+
+    @pytest.mark.parametrize(
+        "value,expected",
+        [
+            (datetime.timedelta(0), "PT0S"),
+            (datetime.timedelta(days=1), "P1D"),
+            (datetime.timedelta(hours=1), "PT1H"),
+            (datetime.timedelta(days=1, hours=2, minutes=30), "P1DT2H30M"),
+            (datetime.timedelta(minutes=1, seconds=1.25), "PT1M1.25S"),
+            (datetime.timedelta(microseconds=10), "PT0.00001S"),
+            (datetime.timedelta(days=3, microseconds=1), "P3DT0.000001S"),
+            (datetime.timedelta(seconds=-1), '"-PT1S"'),
+            (datetime.timedelta(days=-1, seconds=5), '"-PT23H59M55S"'),
+        ],
+        ids=repr,
+    )
+    def test_iso_durations(self, value, expected):
+        out = toons.dumps({"d": value}, timedelta_as="iso")
+        assert out == f"d: {expected}"
+
+    @pytest.mark.parametrize(
+        "value,expected",
+        [
+            (datetime.timedelta(days=1), "86400"),
+            (datetime.timedelta(milliseconds=250), "0.25"),
+            (datetime.timedelta(seconds=-1), "-1"),
+        ],
+        ids=repr,
+    )
+    def test_seconds(self, value, expected):
+        assert toons.dumps({"d": value}) == f"d: {expected}"
+
+    def test_seconds_follow_float_options(self):
+        out = toons.dumps([datetime.timedelta(hours=1)], preserve_float=True)
+        assert out == "[1]: 3600.0"
+
+    def test_invalid_option(self):
+        with pytest.raises(ValueError, match="timedelta_as"):
+            toons.dumps([], timedelta_as="minutes")
+
+
+class Point:
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+
+class PointEncoder(toons.TOONEncoder):
+    def default(self, obj):
+        if isinstance(obj, Point):
+            return {"x": obj.x, "y": obj.y}
+        return super().default(obj)
+
+
+class TestTOONEncoder:
+    OPTION_ROWS = [{"id": 2, "tags": ["a", "b"]}, {"id": 1, "tags": []}]
+    OPTION_DATA = {
+        "name": "Zoë",
+        "ids": list(range(12)),
+        "rows": [{"x": 1, "y": "true"}, {"x": 22, "y": None}],
+        "nested": {"items": [{"k": 1, "v": [1]}, "s"]},
+    }
+
+    @pytest.mark.parametrize(
+        "data, kwargs",
+        [
+            ({("x", 1): "v"}, {"tuple_key_sep": "."}),
+            (OPTION_ROWS, {"tabular_nested_arrays": True}),
+            ([float("nan")], {"nan_token": "NaN"}),
+            (OPTION_DATA, {"compact": True}),
+            ([{"x": 1.0}], {"typed_tabular": True}),
+            ({"f": Fraction(1, 3)}, {"rich_numbers": True}),
+            (OPTION_DATA, {"quote_strings": True}),
+            (
+                {"a": [None, True, "None"]},
+                {
+                    "null_literal": "None",
+                    "true_literal": "True",
+                    "false_literal": "False",
+                },
+            ),
+            (OPTION_DATA, {"inline_threshold": 5}),
+            (OPTION_DATA, {"max_line_length": 10}),
+            (OPTION_DATA, {"align": True}),
+            (OPTION_DATA, {"indent_char": "\t"}),
+            (OPTION_DATA, {"line_separator": "\r\n"}),
+            (OPTION_DATA, {"header_comment": "Zoë"}),
+            (OPTION_DATA, {"ensure_ascii": True}),
+            (OPTION_ROWS, {"sort_rows_by": "id"}),
+        ],
+        ids=repr,
+    )
+    def test_entry_points_accept_option(self, data, kwargs):
+        """Every encoding entry point writes what dumps does for an option."""
+        text = toons.dumps(data, **kwargs)
+        assert toons.TOONEncoder(**kwargs).encode(data) == text
+        assert toons.dumps_bytes(data, **kwargs) == text.encode()
+        fp = io.StringIO()
+        toons.dump(data, fp, **kwargs)
+        assert fp.getvalue() == text
+        assert toons.estimate_size(data, **kwargs) == len(text.encode())
+
+    def test_matches_dumps(self):
+        data = {"users": [{"id": 1, "tags": ["a", "b"]}], "n": 1.5}
+        encoder = toons.TOONEncoder(indent=4, delimiter="|", sort_keys=True)
+        assert encoder.encode(data) == toons.dumps(
+            data, indent=4, delimiter="|", sort_keys=True
+        )
+        assert encoder.indent == 4
+        assert encoder.delimiter == "|"
+
+    def test_base_default_writes_null(self):
+        assert toons.TOONEncoder().encode({"o": object()}) == "o: null"
+
+    def test_default_hook(self):
+        data = {"origin": Point(0, 0), "path": [Point(1, 2), Point(3, 4)]}
+        assert PointEncoder().encode(data) == (
+            "origin:\n  x: 0\n  y: 0\npath[2]{x,y}:\n  1,2\n  3,4"
+        )
+
+    def test_default_result_is_encoded_recursively(self):
+        class Box:
+            def __init__(self, inner):
+                self.inner = inner
+
+        class BoxEncoder(PointEncoder):
+            def default(self, obj):
+                if isinstance(obj, Box):
+                    return {"inner": obj.inner}
+                return super().default(obj)
+
+        result = BoxEncoder().encode(Box(Point(1, 2)))
+        assert result == "inner:\n  x: 1\n  y: 2"
+
+    def test_default_not_called_for_supported_types(self):
+        calls = []
+
+        class Recording(toons.TOONEncoder):
+            def default(self, obj):
+                calls.append(obj)
+                return None
+
+        data = {
+            "d": datetime.date(2024, 1, 15),
+            "b": b"x",
+            "t": (1, 2),
+            "s": "x",
+        }
+        assert Recording().encode(data) == toons.dumps(data)
+        assert calls == []
+
+    def test_default_returning_unsupported_object(self):
+        class Bad(toons.TOONEncoder):
+            def default(self, obj):
+                return object()
+
+        with pytest.raises(TypeError, match="default"):
+            Bad().encode({"o": object()})
+
+    def test_default_returning_obj_again(self):
+        class Cyclic(toons.TOONEncoder):
+            def default(self, obj):
+                return [obj]
+
+        with pytest.raises(ValueError, match="Circular"):
+            Cyclic().encode({"o": object()})
+
+    def test_default_wrapping_new_objects_raises(self):
+        class Wrapping(toons.TOONEncoder):
+            def default(self, obj):
+                return [object()]
+
+        with pytest.raises(RecursionError, match="default"):
+            Wrapping().encode({"o": object()})
+
+    def test_default_exception_propagates(self):
+        class Strict(toons.TOONEncoder):
+            def default(self, obj):
+                raise TypeError(f"cannot encode {type(obj).__name__}")
+
+        with pytest.raises(TypeError, match="cannot encode object"):
+            Strict().encode([object()])
+
+    def test_subclass_init_calls_super(self):
+        class Tagged(toons.TOONEncoder):
+            def __init__(self, *, tag="x", **kwargs):
+                super().__init__(**kwargs)
+                self.tag = tag
+
+            def default(self, obj):
+                return self.tag
+
+        encoder = Tagged(indent=4)
+        assert encoder.tag == "x"
+        assert encoder.indent == 4
+        assert encoder.encode({"a": {"o": object()}}) == "a:\n    o: x"
+
+    def test_invalid_options_raise_on_construction(self):
+        with pytest.raises(ValueError):
+            toons.TOONEncoder(indent=1)
+        with pytest.raises(ValueError):
+            toons.TOONEncoder(tabular="sometimes")
+
+
+class TestAutoDelimiter:
+    def test_comma_when_nothing_needs_quoting(self):
+        data = {"items": ["a", "b"], "n": [1, 2]}
+        assert toons.dumps(data, delimiter="auto") == toons.dumps(data)
+
+    def test_pipe_when_strings_contain_commas(self):
+        data = [{"name": "Smith, John", "city": "Paris"}]
+        result = toons.dumps(data, delimiter="auto")
+        assert result == "[1|]{name|city}:\n  Smith, John|Paris"
+        assert toons.loads(result) == data
+
+    def test_tab_when_strings_contain_commas_and_pipes(self):
+        data = {"t": ["a,b", "c|d", "x"]}
+        result = toons.dumps(data, delimiter="auto")
+        assert result == "t[3\t]: a,b\tc|d\tx"
+        assert toons.loads(result) == data
+
+    def test_comma_kept_when_strings_contain_pipes(self):
+        data = {"t": ["a|b", "c|d"]}
+        result = toons.dumps(data, delimiter="auto")
+        assert result == "t[2]: a|b,c|d"
+        assert toons.loads(result) == data
+
+    def test_nested_values_are_counted(self):
+        data = {"outer": {"inner": [{"v": "x,y"}, {"v": "z"}]}}
+        result = toons.dumps(data, delimiter="auto")
+        assert "[2|]{v}:" in result
+        assert toons.loads(result) == data
+
+    def test_estimate_size_and_encoder(self):
+        data = {"t": ["a,b", "c"]}
+        result = toons.dumps(data, delimiter="auto")
+        assert toons.estimate_size(data, delimiter="auto") == len(result)
+        encoder = toons.TOONEncoder(delimiter="auto")
+        assert encoder.delimiter == "auto"
+        assert encoder.encode(data) == result
+
+    def test_invalid_delimiter(self):
+        with pytest.raises(ValueError, match="delimiter"):
+            toons.dumps([1], delimiter="")
+        with pytest.raises(ValueError, match="delimiter"):
+            toons.dumps([1], delimiter=",,")
+
+
+class TestCustomDelimiter:
+    DATA = {
+        "rows": [{"a": "x;y", "b": 2}, {"a": "z", "b": 3}],
+        "t": ["p", "q"],
+        "s": "a;b",
+    }
+
+    @pytest.mark.parametrize("delimiter", [";", "#", "~", "!", "^", "'"])
+    def test_round_trip(self, delimiter):
+        result = toons.dumps(self.DATA, delimiter=delimiter)
+        assert f"t[2{delimiter}]: p{delimiter}q" in result
+        assert toons.loads(result) == self.DATA
+
+    def test_semicolon_output(self):
+        assert toons.dumps(self.DATA, delimiter=";") == (
+            'rows[2;]{a;b}:\n  "x;y";2\n  z;3\nt[2;]: p;q\ns: "a;b"'
+        )
+
+    @pytest.mark.parametrize(
+        "delimiter", ["1", "a", ":", "[", "{", '"', " ", "-", ".", "_", ";;"]
+    )
+    def test_ambiguous_delimiters_rejected(self, delimiter):
+        with pytest.raises(ValueError, match="delimiter"):
+            toons.dumps([1, 2], delimiter=delimiter)
+
+
+class TestEmptyContainers:
+    @pytest.mark.parametrize(
+        ("value", "expected"),
+        [
+            ({}, ""),
+            ([], "[0]:"),
+            ({"a": {}}, "a:"),
+            ({"a": []}, "a[0]:"),
+            ({"a": {"b": {}}}, "a:\n  b:"),
+            ([{}], "[1]:\n  -"),
+            ([[]], "[1]:\n  - [0]:"),
+            ([{"a": {}}], "[1]:\n  - a:"),
+            ([[[]]], "[1]:\n  - [1]:\n    - [0]:"),
+            ([[{}, []]], "[1]:\n  - [2]:\n    -\n    - [0]:"),
+        ],
+    )
+    def test_encoding_round_trips(self, value, expected):
+        assert toons.dumps(value) == expected
+        assert toons.loads(expected) == value
+
+    @pytest.mark.parametrize(
+        "value",
+        [
+            [[], 1],
+            [[1, []]],
+            [[{"a": 1}, []]],
+            {"a": [[{}], [[], 1]]},
+            [{"a": {}, "b": [{}, []]}],
+            {"a": [{"b": {}}, {"c": []}]},
+        ],
+    )
+    def test_nested_empties_round_trip(self, value):
+        for indent in (2, 4):
+            assert toons.loads(toons.dumps(value, indent=indent)) == value
+
+
+class TestTypedTabular:
+    def test_int_and_float_columns_annotated(self):
+        data = [{"count": 1, "ratio": 2.0}, {"count": 3, "ratio": 0.5}]
+        assert toons.dumps(data, typed_tabular=True) == (
+            "[2]{count:int,ratio:float}:\n  1,2\n  3,0.5"
+        )
+
+    def test_off_by_default(self):
+        assert toons.dumps([{"a": 1}]) == "[1]{a}:\n  1"
+
+    @pytest.mark.parametrize(
+        "values",
+        [[1, 2.5], [True, False], ["a", "b"], [1, "x"], [None, None]],
+    )
+    def test_mixed_or_non_numeric_columns_unannotated(self, values):
+        data = [{"a": value} for value in values]
+        header = toons.dumps(data, typed_tabular=True).split("\n")[0]
+        assert header == "[2]{a}:"
+
+    def test_nulls_do_not_prevent_annotation(self):
+        data = [{"a": None}, {"a": 1.5}]
+        assert toons.dumps(data, typed_tabular=True).startswith(
+            "[2]{a:float}:"
+        )
+
+    @pytest.mark.parametrize(
+        "data",
+        [
+            {"rows": [{"n": 1, "x": 1.0}, {"n": 2, "x": 1e20}]},
+            [{"x": 3.0, "y": None}, {"x": None, "y": 2}],
+            [[{"x": 1.0}, {"x": 2.0}]],
+            {"big": [{"n": 2**62}, {"n": -1}]},
+        ],
+    )
+    def test_types_round_trip(self, data):
+        text = toons.dumps(data, typed_tabular=True)
+        decoded = toons.loads(text)
+        assert decoded == data
+        assert repr(decoded) == repr(data)
+
+    def test_fill_rows_missing_keys(self):
+        data = [{"a": 1.0}, {"b": 2}]
+        text = toons.dumps(data, tabular="fill", typed_tabular=True)
+        assert text == "[2]{a:float,b:int}:\n  1,null\n  null,2"
+
+
+class FakeNumpyScalar:
+    """Stands in for a numpy scalar: zero-dimensional, with `item()`."""
+
+    ndim = 0
+
+    def __init__(self, value, dtype):
+        self.value = value
+        self.dtype = dtype
+
+    def item(self):
+        return self.value
+
+
+class FakeFloat64(float):
+    """Like `np.float64`, a `float` subclass that also has `item()`."""
+
+    ndim = 0
+    dtype = "float64"
+
+    def item(self):
+        return float(self)
+
+
+class TestNumpyScalars:
+    def test_scalars_written_as_native_values(self):
+        data = {
+            "count": FakeNumpyScalar(3, "int64"),
+            "ratio": FakeNumpyScalar(0.5, "float32"),
+            "active": FakeNumpyScalar(True, "bool"),
+            "score": FakeFloat64(2.5),
+        }
+        assert toons.dumps(data) == (
+            "count: 3\nratio: 0.5\nactive: true\nscore: 2.5"
+        )
+
+    def test_tabular_columns_typed_from_scalars(self):
+        data = [{"n": FakeNumpyScalar(1, "int64"), "x": FakeFloat64(1.0)}]
+        text = toons.dumps(data, typed_tabular=True)
+        assert text == "[1]{n:int,x:float}:\n  1,1"
+
+    def test_unsupported_item_goes_to_default(self):
+        data = {"p": FakeNumpyScalar(Point(1, 2), "object")}
+        assert toons.dumps(data) == "p: null"
+        assert PointEncoder().encode(data) == "p:\n  x: 1\n  y: 2"
+
+    def test_object_without_dtype_not_converted(self):
+        class WithItem:
+            def item(self):
+                return 1
+
+        assert toons.dumps({"a": WithItem()}) == "a: null"
+
+    def test_real_numpy_scalars(self):
+        np = pytest.importorskip("numpy")
+        data = {
+            "i": np.int64(7),
+            "f": np.float32(0.5),
+            "b": np.bool_(False),
+            "u": np.uint8(255),
+        }
+        assert toons.dumps(data) == "i: 7\nf: 0.5\nb: false\nu: 255"
+
+
+class FakeDtype:
+    def __init__(self, names=None):
+        self.names = names
+
+
+class FakeNDArray:
+    """Stands in for a numpy array: iterable, with `dtype` and `tolist()`."""
+
+    def __init__(self, data, names=None):
+        self.data = data
+        self.dtype = FakeDtype(names)
+        self.ndim = 1
+
+    def __array__(self):
+        raise NotImplementedError
+
+    def __iter__(self):
+        return iter(self.data)
+
+    def tolist(self):
+        return self.data
+
+
+class TestNumpyArrays:
+    def test_1d_array_inline(self):
+        assert toons.dumps({"v": FakeNDArray([1, 2.5, 3])}) == "v[3]: 1,2.5,3"
+
+    def test_2d_array_expanded(self):
+        data = {"m": FakeNDArray([[1, 2], [3, 4]])}
+        assert toons.dumps(data) == "m[2]:\n  - [2]: 1,2\n  - [2]: 3,4"
+
+    def test_structured_array_tabular(self):
+        records = FakeNDArray([(1, "a"), (2, "b")], names=("id", "name"))
+        assert toons.dumps({"r": records}) == "r[2]{id,name}:\n  1,a\n  2,b"
+
+    def test_empty_array(self):
+        assert toons.dumps(FakeNDArray([])) == "[0]:"
+
+    def test_real_numpy_arrays(self):
+        np = pytest.importorskip("numpy")
+        assert toons.dumps(np.array([1, 2, 3])) == "[3]: 1,2,3"
+        assert toons.dumps({"m": np.array([[1, 2], [3, 4]])}) == (
+            "m[2]:\n  - [2]: 1,2\n  - [2]: 3,4"
+        )
+        records = np.array(
+            [(1, 0.5), (2, 1.5)], dtype=[("id", "i8"), ("w", "f8")]
+        )
+        assert toons.dumps(records) == "[2]{id,w}:\n  1,0.5\n  2,1.5"
+        assert toons.dumps({"s": np.array(4)}) == "s: 4"
+
+
+class FakeDataFrame:
+    """Stands in for a pandas DataFrame: iterating yields column names."""
+
+    def __init__(self, columns, rows):
+        self.columns = columns
+        self.rows = rows
+
+    def __iter__(self):
+        return iter(self.columns)
+
+    def to_dict(self, orient="dict"):
+        assert orient == "records"
+        return [dict(zip(self.columns, row)) for row in self.rows]
+
+
+class TestDataFrames:
+    def test_frame_becomes_table_in_column_order(self):
+        frame = FakeDataFrame(["name", "age"], [["Ann", 31], ["Bob", 27]])
+        assert toons.dumps({"people": frame}) == (
+            "people[2]{name,age}:\n  Ann,31\n  Bob,27"
+        )
+
+    def test_nan_and_datetime_cells(self):
+        at = datetime.datetime(2024, 1, 15, 9, 30)
+        frame = FakeDataFrame(["x", "at"], [[float("nan"), at], [1.5, at]])
+        assert toons.dumps(frame) == (
+            '[2]{x,at}:\n  null,"2024-01-15T09:30:00"\n'
+            '  1.5,"2024-01-15T09:30:00"'
+        )
+
+    def test_empty_frame(self):
+        assert toons.dumps({"df": FakeDataFrame(["a"], [])}) == "df[0]:"
+
+    def test_real_pandas_dataframe(self):
+        pd = pytest.importorskip("pandas")
+        frame = pd.DataFrame(
+            {
+                "id": [1, 2],
+                "score": [0.5, float("nan")],
+                "day": pd.to_datetime(["2024-01-01", "2024-01-02"]),
+            }
+        )
+        assert toons.dumps(frame) == (
+            "[2]{id,score,day}:\n"
+            '  1,0.5,"2024-01-01T00:00:00"\n'
+            '  2,null,"2024-01-02T00:00:00"'
+        )
+
+
+class TestRichNumbers:
+    def test_complex_written_as_string(self):
+        data = {"z": 1 + 2j, "w": [2j, 3 - 1.5j]}
+        assert toons.dumps(data) == "z: (1+2j)\nw[2]: 2j,(3-1.5j)"
+        assert complex(toons.loads(toons.dumps(data))["z"]) == 1 + 2j
+
+    def test_complex_not_passed_to_default(self):
+        assert PointEncoder().encode({"z": 1j}) == "z: 1j"
+
+    def test_fraction_is_float_by_default(self):
+        assert toons.dumps({"f": Fraction(3, 4)}) == "f: 0.75"
+
+    @pytest.mark.parametrize(
+        ("value", "expected"),
+        [
+            (Fraction(3, 4), "f: 3/4"),
+            (Fraction(-1, 3), 'f: "-1/3"'),
+            (Fraction(2), 'f: "2"'),
+        ],
+    )
+    def test_fraction_exact_with_rich_numbers(self, value, expected):
+        text = toons.dumps({"f": value}, rich_numbers=True)
+        assert text == expected
+        assert Fraction(toons.loads(text)["f"]) == value
+
+    def test_fraction_cells_in_tables(self):
+        data = [{"f": Fraction(1, 2)}, {"f": Fraction(5, 4)}]
+        assert toons.dumps(data, rich_numbers=True) == "[2]{f}:\n  1/2\n  5/4"
+
+
+class TestQuoteStrings:
+    DATA = {
+        "name": "Alice",
+        "flag": "true",
+        "tags": ["a", "1", 2],
+        "rows": [{"id": 1, "role": "admin"}, {"id": 2, "role": None}],
+        "items": [{"k": "v", "n": [1]}, "plain"],
+    }
+
+    def test_default_quotes_only_when_needed(self):
+        assert toons.dumps({"a": "x", "b": "true"}) == 'a: x\nb: "true"'
+
+    def test_every_string_value_quoted(self):
+        assert toons.dumps(self.DATA, quote_strings=True) == (
+            'name: "Alice"\n'
+            'flag: "true"\n'
+            'tags[3]: "a","1",2\n'
+            "rows[2]{id,role}:\n"
+            '  1,"admin"\n'
+            "  2,null\n"
+            "items[2]:\n"
+            '  - k: "v"\n'
+            "    n[1]: 1\n"
+            '  - "plain"'
+        )
+
+    def test_keys_and_non_strings_unaffected(self):
+        data = {"key": 1, "my key": True, "x": None, "f": 1.5}
+        assert toons.dumps(data, quote_strings=True) == toons.dumps(data)
+
+    def test_root_string_quoted(self):
+        assert toons.dumps("abc", quote_strings=True) == '"abc"'
+
+    def test_roundtrip_unchanged(self):
+        text = toons.dumps(self.DATA, quote_strings=True)
+        assert toons.loads(text) == self.DATA
+
+
+class TestLiterals:
+    PYTHON = {
+        "null_literal": "None",
+        "true_literal": "True",
+        "false_literal": "False",
+    }
+
+    def test_python_style_tokens(self):
+        data = {"a": None, "b": True, "c": [False, None], "d": float("nan")}
+        assert toons.dumps(data, **self.PYTHON) == (
+            "a: None\nb: True\nc[2]: False,None\nd: None"
+        )
+
+    def test_tabular_cells(self):
+        data = [{"x": True, "y": None}, {"x": False, "y": 1}]
+        assert toons.dumps(data, **self.PYTHON) == (
+            "[2]{x,y}:\n  True,None\n  False,1"
+        )
+
+    def test_colliding_strings_quoted(self):
+        data = ["None", "True", "False", "null", "true", "Nonesuch"]
+        assert toons.dumps(data, **self.PYTHON) == (
+            '[6]: "None","True","False","null","true",Nonesuch'
+        )
+
+    def test_keys_unaffected(self):
+        assert toons.dumps({"None": None}, null_literal="None") == "None: None"
+
+    def test_roundtrip_with_matching_loads_options(self):
+        data = {"a": None, "rows": [{"b": True, "s": "None"}], "t": "True"}
+        text = toons.dumps(data, **self.PYTHON)
+        assert toons.loads(text, **self.PYTHON) == data
+
+    @pytest.mark.parametrize(
+        "options",
+        [
+            {"null_literal": ""},
+            {"null_literal": "nil value"},
+            {"true_literal": "1yes"},
+            {"true_literal": "-"},
+            {"false_literal": "no,"},
+            {"null_literal": "X", "true_literal": "X"},
+            {"null_literal": "true"},
+            {"true_literal": "false", "false_literal": "true"},
+        ],
+        ids=repr,
+    )
+    def test_invalid_literals(self, options):
+        with pytest.raises(ValueError, match="literal"):
+            toons.dumps(None, **options)
+        with pytest.raises(ValueError, match="literal"):
+            toons.loads("a: 1", **options)
+
+
+class TestInlineThreshold:
+    DATA = {
+        "short": [1, 2],
+        "long": ["alpha", "beta", "gamma"],
+        "empty": [],
+        "rows": [{"a": 1}, {"a": 2}],
+        "nested": [[1, 2], ["aaaa", "bbbbbbb"]],
+    }
+
+    def test_default_keeps_primitive_lists_inline(self):
+        assert toons.dumps(self.DATA, inline_threshold=None) == toons.dumps(
+            self.DATA
+        )
+
+    def test_long_lists_expanded(self):
+        assert toons.dumps(self.DATA, inline_threshold=8) == (
+            "short[2]: 1,2\n"
+            "long[3]:\n"
+            "  - alpha\n"
+            "  - beta\n"
+            "  - gamma\n"
+            "empty[0]:\n"
+            "rows[2]{a}:\n"
+            "  1\n"
+            "  2\n"
+            "nested[2]:\n"
+            "  - [2]: 1,2\n"
+            "  - [2]:\n"
+            "    - aaaa\n"
+            "    - bbbbbbb"
+        )
+
+    @pytest.mark.parametrize("threshold", [3, 4])
+    def test_threshold_is_exclusive(self, threshold):
+        # "1,2" is three bytes long
+        out = toons.dumps({"a": [1, 2]}, inline_threshold=threshold)
+        expected = "a[2]: 1,2" if threshold == 4 else "a[2]:\n  - 1\n  - 2"
+        assert out == expected
+
+    def test_zero_expands_every_non_empty_list(self):
+        assert toons.dumps([1], inline_threshold=0) == "[1]:\n  - 1"
+        assert toons.dumps([], inline_threshold=0) == "[0]:"
+
+    def test_list_item_objects_and_folded_keys(self):
+        data = [{"t": ["xxxxxx", "yyyyyy"], "u": 1}]
+        assert toons.dumps(data, inline_threshold=5) == (
+            "[1]:\n  - t[2]:\n      - xxxxxx\n      - yyyyyy\n    u: 1"
+        )
+        nested = {"a": {"b": ["xxxxx", "y"]}}
+        folded = toons.dumps(nested, key_folding="safe", inline_threshold=5)
+        assert folded == "a.b[2]:\n  - xxxxx\n  - y"
+
+    def test_delimiter_counts_toward_length(self):
+        out = toons.dumps({"a": ["x", "y"]}, delimiter="|", inline_threshold=4)
+        assert out == "a[2|]: x|y"
+
+    @pytest.mark.parametrize("threshold", [None, 0, 5, 12])
+    def test_roundtrip(self, threshold):
+        text = toons.dumps(self.DATA, inline_threshold=threshold)
+        assert toons.loads(text) == self.DATA
+
+
+class TestMaxLineLength:
+    DATA = {
+        "ids": list(range(12)),
+        "short": [1, 2],
+        "nested": {"tags": ["alpha", "beta"]},
+    }
+
+    def test_default_keeps_primitive_lists_inline(self):
+        assert toons.dumps(self.DATA, max_line_length=None) == toons.dumps(
+            self.DATA
+        )
+
+    def test_long_lines_expanded(self):
+        text = toons.dumps({"ids": list(range(12))}, max_line_length=20)
+        assert text == "ids[12]:\n" + "\n".join(
+            f"  - {i}" for i in range(12)
+        )
+
+    @pytest.mark.parametrize("limit", [12, 13])
+    def test_limit_is_inclusive(self, limit):
+        # "a[3]: 1,2,3" is eleven characters, "  a[3]: 1,2,3" thirteen
+        out = toons.dumps({"x": {"a": [1, 2, 3]}}, max_line_length=limit)
+        if limit == 13:
+            assert out == "x:\n  a[3]: 1,2,3"
+        else:
+            assert out == "x:\n  a[3]:\n    - 1\n    - 2\n    - 3"
+
+    def test_every_line_fits(self):
+        text = toons.dumps(self.DATA, max_line_length=21)
+        assert max(len(line) for line in text.splitlines()) <= 21
+        assert "short[2]: 1,2" in text
+        assert "  tags[2]: alpha,beta" in text
+
+    def test_counts_characters_not_bytes(self):
+        text = toons.dumps({"k": ["\u00e9"] * 3}, max_line_length=11)
+        assert text == "k[3]: \u00e9,\u00e9,\u00e9"
+
+    def test_list_items_and_folded_keys(self):
+        data = [{"t": ["xx", "yy"], "u": 1}]
+        assert toons.dumps(data, max_line_length=12) == (
+            "[1]:\n  - t[2]:\n      - xx\n      - yy\n    u: 1"
+        )
+        nested = {"a": {"b": ["x", "y"]}}
+        folded = toons.dumps(nested, key_folding="safe", max_line_length=10)
+        assert folded == "a.b[2]:\n  - x\n  - y"
+
+    @pytest.mark.parametrize("limit", [None, 0, 10, 20])
+    def test_roundtrip(self, limit):
+        text = toons.dumps(self.DATA, max_line_length=limit)
+        assert toons.loads(text) == self.DATA
+
+
+class TestAlign:
+    DATA = {
+        "users": [
+            {"id": 1, "name": "Alice", "role": "admin", "n": None},
+            {"id": 22, "name": "Bob", "role": "user, guest", "n": 1.5},
+            {"id": 333, "name": "Zoë", "role": "x", "n": True},
+        ]
+    }
+
+    def test_columns_padded_after_delimiter(self):
+        assert toons.dumps(self.DATA, align=True) == (
+            "users[3]{id,name,role,n}:\n"
+            "  1,  Alice,admin,        null\n"
+            '  22, Bob,  "user, guest",1.5\n'
+            "  333,Zoë,  x,            true"
+        )
+
+    def test_default_not_padded(self):
+        assert "1,Alice,admin,null" in toons.dumps(self.DATA)
+
+    @pytest.mark.parametrize("delimiter", [",", "|", "\t"])
+    def test_padded_output_parses_like_unpadded(self, delimiter):
+        padded = toons.dumps(self.DATA, align=True, delimiter=delimiter)
+        plain = toons.dumps(self.DATA, delimiter=delimiter)
+        assert padded != plain
+        assert toons.loads(padded) == toons.loads(plain) == self.DATA
+
+    def test_mixed_cells_and_fill(self):
+        data = [{"a": Fraction(1, 2), "b": "x"}, {"a": 10}]
+        out = toons.dumps(data, align=True, tabular="fill", rich_numbers=True)
+        assert out == "[2]{a,b}:\n  1/2,x\n  10, null"
+
+    def test_nested_tables_and_typed_headers(self):
+        data = {"t": {"rows": [{"k": 1.5, "v": 2}, {"k": 10.25, "v": 3}]}}
+        out = toons.dumps(data, align=True, typed_tabular=True)
+        assert out == "t:\n  rows[2]{k:float,v:int}:\n    1.5,  2\n    10.25,3"
+        assert toons.loads(out) == data
+
+
+class TestIndent:
+    def test_four_spaces_at_every_level(self):
+        data = {
+            "a": {
+                "b": {
+                    "rows": [{"x": 1, "y": 2}, {"x": 3, "y": 4}],
+                    "items": [{"k": 1, "v": [1, 2]}, [1, 2], "s"],
+                }
+            }
+        }
+        assert toons.dumps(data, indent=4) == (
+            "a:\n"
+            "    b:\n"
+            "        rows[2]{x,y}:\n"
+            "            1,2\n"
+            "            3,4\n"
+            "        items[3]:\n"
+            "            - k: 1\n"
+            "                v[2]: 1,2\n"
+            "            - [2]: 1,2\n"
+            "            - s"
+        )
+
+    @pytest.mark.parametrize("indent", [0, 1])
+    def test_below_two_rejected(self, indent):
+        with pytest.raises(ValueError, match="indent must be >= 2"):
+            toons.dumps({"a": {"b": 1}}, indent=indent)
+        with pytest.raises(ValueError, match="indent must be >= 2"):
+            toons.json_to_toon("{}", indent=indent)
+
+
+class TestIndentChar:
+    DATA = {
+        "a": {"rows": [{"x": 1, "y": 2}], "items": [{"k": 1, "v": [1]}, "s"]},
+        "b": 1,
+    }
+
+    def test_one_tab_per_level(self):
+        assert toons.dumps(self.DATA, indent_char="\t") == (
+            "a:\n"
+            "\trows[1]{x,y}:\n"
+            "\t\t1,2\n"
+            "\titems[2]:\n"
+            "\t\t- k: 1\n"
+            "\t\t\tv[1]: 1\n"
+            "\t\t- s\n"
+            "b: 1"
+        )
+
+    def test_indent_width_ignored_with_tabs(self):
+        text = toons.dumps(self.DATA, indent_char="\t")
+        assert toons.dumps(self.DATA, indent=4, indent_char="\t") == text
+
+    def test_round_trip(self):
+        text = toons.dumps(self.DATA, indent_char="\t", delimiter="\t")
+        assert toons.loads(text, allow_tab_indent=True) == self.DATA
+
+    def test_invalid_indent_char(self):
+        with pytest.raises(ValueError, match="indent_char"):
+            toons.dumps(self.DATA, indent_char="  ")
+
+
+class TestLineSeparator:
+    DATA = {"a": {"b": 1}, "rows": [{"x": 1}, {"x": 2}], "s": "l1\nl2"}
+
+    def test_crlf_bytes(self):
+        assert toons.dumps_bytes(self.DATA, line_separator="\r\n") == (
+            b'a:\r\n  b: 1\r\nrows[2]{x}:\r\n  1\r\n  2\r\ns: "l1\\nl2"'
+        )
+
+    def test_default_is_lf(self):
+        text = toons.dumps(self.DATA)
+        assert "\r" not in text
+        assert toons.dumps(self.DATA, line_separator="\n") == text
+
+    def test_round_trip(self):
+        text = toons.dumps(self.DATA, line_separator="\r\n")
+        assert toons.loads(text) == self.DATA
+
+    def test_dump_to_path_keeps_crlf(self, tmp_path):
+        path = tmp_path / "out.toon"
+        toons.dump(self.DATA, str(path), line_separator="\r\n")
+        expected = toons.dumps_bytes(self.DATA, line_separator="\r\n")
+        assert path.read_bytes() == expected
+
+    def test_invalid_separator(self):
+        with pytest.raises(ValueError, match="line_separator"):
+            toons.dumps(self.DATA, line_separator="\r")
+
+
+class TestHeaderComment:
+    DATA = {"users": [{"id": 1, "name": "Ann"}], "n": 2}
+
+    def test_single_line(self):
+        text = toons.dumps(self.DATA, header_comment="Active users")
+        assert text == "# Active users\nusers[1]{id,name}:\n  1,Ann\nn: 2"
+
+    def test_multi_line(self):
+        text = toons.dumps({"a": 1}, header_comment="first\n\nthird")
+        assert text == "# first\n#\n# third\na: 1"
+
+    def test_crlf(self):
+        text = toons.dumps(
+            {"a": 1}, header_comment="x\r\ny", line_separator="\r\n"
+        )
+        assert text == "# x\r\n# y\r\na: 1"
+
+    def test_default_has_no_comment(self):
+        assert not toons.dumps(self.DATA).startswith("#")
+
+    def test_round_trip_lenient(self):
+        text = toons.dumps(self.DATA, header_comment="a: 1\nb[2]: x,y")
+        value, warnings = toons.loads(
+            text, strict=False, collect_warnings=True
+        )
+        assert value == self.DATA
+        assert warnings == []
+
+    @pytest.mark.parametrize(
+        "data",
+        [
+            [{"tag": "#1", "n": 1}, {"tag": "#2", "n": 2}],
+            {"note": "#todo", "tags": ["#a", "#b"]},
+            "#x",
+        ],
+    )
+    def test_round_trip_hash_values(self, data):
+        text = toons.dumps(data, header_comment="hello")
+        assert toons.loads(text, strict=False) == data
+        assert toons.check_roundtrip(data, header_comment="hello")
+
+    def test_strict_rejects_comment(self):
+        text = toons.dumps(self.DATA, header_comment="note")
+        with pytest.raises(toons.ToonDecodeError):
+            toons.loads(text)
+
+
+class TestEnsureAscii:
+    """Test ensure_ascii escaping of non-ASCII characters."""
+
+    def test_string_value(self):
+        text = toons.dumps({"n": "café"}, ensure_ascii=True)
+        assert text == 'n: "caf\\u00e9"'
+        assert toons.loads(text) == {"n": "café"}
+
+    def test_keys_fields_and_cells(self):
+        data = {"名前": "太郎", "rows": [{"ü": "é", "b": "ok"}]}
+        text = toons.dumps(data, ensure_ascii=True)
+        assert text == (
+            '"\\u540d\\u524d": "\\u592a\\u90ce"\n'
+            'rows[1]{"\\u00fc",b}:\n  "\\u00e9",ok'
+        )
+        assert toons.loads(text) == data
+
+    def test_astral_character_uses_surrogate_pair(self):
+        text = toons.dumps(["😀"], ensure_ascii=True)
+        assert text == '[1]: "\\ud83d\\ude00"'
+        assert toons.loads(text) == ["😀"]
+
+    def test_matches_json_escapes(self):
+        value = "naïve ☃ 😀"
+        text = toons.dumps({"v": value}, ensure_ascii=True)
+        assert text == "v: " + json.dumps(value)
+
+    def test_output_is_ascii(self):
+        data = {
+            "ä": {"ö": [1, "ü"]},
+            "t": [{"k": "ß"}, {"k": "x"}],
+            "s": "ok",
+        }
+        for kwargs in ({}, {"key_folding": "safe"}, {"align": True}):
+            text = toons.dumps(
+                data, ensure_ascii=True, header_comment="über", **kwargs
+            )
+            assert text.isascii()
+            assert toons.loads(text, strict=False) == data
+
+    def test_ascii_strings_unchanged(self):
+        data = {"a": "plain", "b": ["x", "y"]}
+        assert toons.dumps(data, ensure_ascii=True) == toons.dumps(data)
+
+    def test_default_writes_utf8(self):
+        assert toons.dumps({"n": "café"}) == "n: café"
+
+    def test_strict_round_trip(self):
+        data = {"ä": [{"k": "ß"}, {"k": "😀"}], "s": "naïve"}
+        text = toons.dumps(data, ensure_ascii=True)
+        assert toons.loads(text, strict=True) == data
+        assert toons.check_roundtrip(data, ensure_ascii=True)
+
+    def test_non_ascii_literal_rejected(self):
+        with pytest.raises(ValueError, match="ensure_ascii"):
+            toons.dumps({"a": None}, ensure_ascii=True, null_literal="нуль")
+
+
+class TestSortRowsBy:
+    """Test sorting tabular rows by a column."""
+
+    def test_rows_sorted_by_column(self):
+        rows = [{"id": 3, "n": "c"}, {"id": 1, "n": "a"}, {"id": 2, "n": "b"}]
+        assert toons.dumps(rows, sort_rows_by="id") == (
+            "[3]{id,n}:\n  1,a\n  2,b\n  3,c"
+        )
+
+    def test_sort_is_stable(self):
+        rows = [{"k": 2, "v": "x"}, {"k": 1, "v": "y"}, {"k": 2, "v": "z"}]
+        text = toons.dumps({"r": rows}, sort_rows_by="k")
+        assert text == "r[3]{k,v}:\n  1,y\n  2,x\n  2,z"
+
+    def test_input_not_mutated(self):
+        rows = [{"id": 2}, {"id": 1}]
+        toons.dumps(rows, sort_rows_by="id")
+        assert rows == [{"id": 2}, {"id": 1}]
+
+    def test_nested_tables_sorted(self):
+        data = {"a": {"t": [{"id": "b"}, {"id": "a"}]}}
+        assert toons.dumps(data, sort_rows_by="id") == (
+            "a:\n  t[2]{id}:\n    a\n    b"
+        )
+
+    def test_nested_tables_without_column_keep_order(self):
+        data = {
+            "users": [{"id": 3, "tags": [{"x": 2}, {"x": 1}]}],
+            "groups": [{"id": 2}, {"id": 1}],
+        }
+        sorted_data = {**data, "groups": [{"id": 1}, {"id": 2}]}
+        assert toons.dumps(data, sort_rows_by="id") == toons.dumps(sorted_data)
+        assert toons.check_roundtrip(data, sort_rows_by="id")
+
+    def test_root_table_without_column_raises(self):
+        rows = [{"id": 2}, {"id": 1}]
+        with pytest.raises(ValueError, match="no such column"):
+            toons.dumps(rows, sort_rows_by="name")
+        with pytest.raises(ValueError, match="no such column"):
+            toons.estimate_size(rows, sort_rows_by="name")
+
+    def test_top_level_table_without_column_raises(self):
+        data = {"users": [{"id": 3}, {"id": 1}], "tags": [{"x": 2}, {"x": 1}]}
+        with pytest.raises(ValueError, match=r"\"id\".*has x"):
+            toons.dumps(data, sort_rows_by="id")
+
+    def test_rows_missing_value_in_fill_mode_come_last(self):
+        rows = [{"n": "a"}, {"id": 2, "n": "b"}, {"n": "c"}, {"id": 1}]
+        text = toons.dumps(rows, tabular="fill", sort_rows_by="id")
+        assert text == "[4]{n,id}:\n  null,1\n  b,2\n  a,null\n  c,null"
+
+    def test_incomparable_values_raise(self):
+        with pytest.raises(ValueError, match="Cannot sort rows"):
+            toons.dumps([{"id": 1}, {"id": "a"}], sort_rows_by="id")
+
+    def test_non_tabular_lists_keep_order(self):
+        data = {"xs": [3, 1, 2], "ys": [{"id": 2, "z": [1]}, {"id": 1}]}
+        assert toons.dumps(data, sort_rows_by="id") == toons.dumps(data)
+
+    def test_decode_roundtrip(self):
+        rows = [{"id": 2, "n": "b"}, {"id": 1, "n": "a"}]
+        text = toons.dumps(rows, sort_rows_by="id")
+        assert toons.loads(text) == sorted(rows, key=lambda r: r["id"])
+        assert toons.check_roundtrip(rows, sort_rows_by="id")
@@ -1,11 +1,19 @@
 """
 Tests for serialization of non-JSON-serializable Python objects.
 
-Validates that datetime, time, date, and Decimal objects
+Validates that datetime, time, date, Decimal, bytes, UUID and path objects
 serialize to their string representations.
 """
 
+import enum
 import io
+import ipaddress
+import types
+import uuid
+from pathlib import PurePosixPath, PureWindowsPath
+from collections import ChainMap
+from collections.abc import Mapping
+from dataclasses import dataclass, field
 from datetime import date, datetime, time
 from decimal import Decimal
 
@@ -121,6 +129,644 @@ class TestDecimalSerialization:
         assert "value: 10.123456789" == toons.dumps(data)
 
 
+class TestBytesSerialization:
+    """Test serialization of bytes and bytearray objects to strings."""
+
+    def test_bytes_serializes_to_base64(self):
+        """bytes serialize to a stable base64 string by default."""
+        assert "b: AAE=" == toons.dumps({"b": b"\x00\x01"})
+
+    def test_bytearray_serializes_to_base64(self):
+        """bytearray is handled like bytes."""
+        assert "b: aGVsbG8=" == toons.dumps({"b": bytearray(b"hello")})
+
+    def test_base64_padding(self):
+        """All padding lengths are produced correctly."""
+        data = {"b": [b"", b"f", b"fo", b"foo", b"foob"]}
+        assert 'b[5]: "",Zg==,Zm8=,Zm9v,Zm9vYg==' == toons.dumps(data)
+
+    def test_bytes_hex_encoding(self):
+        """bytes_encoding="hex" emits lowercase hex, quoted when numeric."""
+        data = {"a": b"\x00\x01", "b": b"\xde\xad"}
+        assert 'a: "0001"\nb: dead' == toons.dumps(data, bytes_encoding="hex")
+
+    def test_bytes_decode_as_string(self):
+        """Decoding does not reverse the encoding."""
+        assert {"b": "AAE="} == toons.loads(toons.dumps({"b": b"\x00\x01"}))
+
+    def test_invalid_bytes_encoding(self):
+        """Unknown encodings are rejected."""
+        with pytest.raises(ValueError, match="bytes_encoding"):
+            toons.dumps({"b": b"x"}, bytes_encoding="base32")
+
+
+class TestUUIDSerialization:
+    """Test serialization of uuid.UUID objects to strings."""
+
+    def test_uuid_serializes_unquoted(self):
+        """UUIDs emit their canonical form; inner hyphens need no quotes."""
+        value = uuid.UUID("550e8400-e29b-41d4-a716-446655440000")
+        assert "id: 550e8400-e29b-41d4-a716-446655440000" == toons.dumps(
+            {"id": value}
+        )
+
+    def test_numeric_looking_uuid_is_unquoted(self):
+        """A UUID made only of digits and hyphens is not numeric-like."""
+        value = uuid.UUID("12345678-1234-1234-1234-123456789012")
+        assert "id: 12345678-1234-1234-1234-123456789012" == toons.dumps(
+            {"id": value}
+        )
+
+    def test_uuid_in_tabular_array(self):
+        """UUID fields in uniform objects keep the tabular form."""
+        ids = [
+            uuid.UUID("00000000-0000-0000-0000-000000000001"),
+            uuid.UUID("a8098c1a-f86e-11da-bd1a-00112444be1e"),
+        ]
+        data = [{"id": ids[0], "n": 1}, {"id": ids[1], "n": 2}]
+        assert (
+            "[2]{id,n}:\n"
+            "  00000000-0000-0000-0000-000000000001,1\n"
+            "  a8098c1a-f86e-11da-bd1a-00112444be1e,2"
+        ) == toons.dumps(data)
+        assert toons.loads(toons.dumps(data)) == [
+            {"id": str(ids[0]), "n": 1},
+            {"id": str(ids[1]), "n": 2},
+        ]
+
+
+class FsPath:
+    """A minimal os.PathLike that is not a pathlib path."""
+
+    def __init__(self, path):
+        self.path = path
+
+    def __fspath__(self):
+        return self.path
+
+
+class TestPathSerialization:
+    """Test serialization of os.PathLike objects to strings."""
+
+    def test_posix_path_is_unquoted(self):
+        """Slashes need no quoting."""
+        path = PurePosixPath("/var/log/app.log")
+        assert "p: /var/log/app.log" == toons.dumps({"p": path})
+
+    def test_windows_path_escapes_backslashes(self):
+        """Backslashes force quoting and are escaped inside the quotes."""
+        path = PureWindowsPath(r"C:\Users\ada\notes.txt")
+        toon = toons.dumps({"p": path})
+        assert 'p: "C:\\\\Users\\\\ada\\\\notes.txt"' == toon
+        assert {"p": str(path)} == toons.loads(toon)
+
+    def test_path_that_looks_like_a_number_is_quoted(self):
+        """The path string gets the same quoting as any other string."""
+        assert 'p: "42"' == toons.dumps({"p": PurePosixPath("42")})
+
+    def test_custom_pathlike(self):
+        """Any object implementing __fspath__ is supported."""
+        assert "p: data/in.csv" == toons.dumps({"p": FsPath("data/in.csv")})
+
+    def test_bytes_pathlike_is_decoded(self):
+        """A bytes path is decoded with the filesystem encoding."""
+        assert "p: data/in.csv" == toons.dumps({"p": FsPath(b"data/in.csv")})
+
+    def test_paths_in_tabular_array(self):
+        """Path fields in uniform objects keep the tabular form."""
+        data = [
+            {"name": "a", "path": PurePosixPath("/srv/a")},
+            {"name": "b", "path": PureWindowsPath(r"D:\b")},
+        ]
+        toon = toons.dumps(data)
+        assert '[2]{name,path}:\n  a,/srv/a\n  b,"D:\\\\b"' == toon
+        assert [
+            {"name": "a", "path": "/srv/a"},
+            {"name": "b", "path": r"D:\b"},
+        ] == toons.loads(toon)
+
+
+class TestIPAddressSerialization:
+    """Test serialization of ipaddress objects to their str() form."""
+
+    def test_ipv4_address_is_unquoted(self):
+        """Dotted IPv4 addresses are not numeric-like and need no quotes."""
+        addr = ipaddress.ip_address("192.168.0.1")
+        assert "ip: 192.168.0.1" == toons.dumps({"ip": addr})
+
+    def test_ipv6_address_is_quoted(self):
+        """The colons of an IPv6 address force quoting."""
+        addr = ipaddress.ip_address("2001:db8::1")
+        assert 'ip: "2001:db8::1"' == toons.dumps({"ip": addr})
+
+    def test_networks_and_interfaces(self):
+        """Networks are written whole, not iterated over their addresses."""
+        data = {
+            "net": ipaddress.ip_network("10.0.0.0/8"),
+            "net6": ipaddress.ip_network("2001:db8::/32"),
+            "iface": ipaddress.ip_interface("192.168.1.5/24"),
+        }
+        assert (
+            'net: 10.0.0.0/8\nnet6: "2001:db8::/32"\niface: 192.168.1.5/24'
+            == toons.dumps(data)
+        )
+
+    def test_addresses_in_tabular_array(self):
+        """IP fields keep the tabular form and decode back to strings."""
+        data = [
+            {"host": "a", "ip": ipaddress.ip_address("10.0.0.1")},
+            {"host": "b", "ip": ipaddress.ip_address("::1")},
+            {"host": "c", "ip": ipaddress.ip_address("fe80::1")},
+        ]
+        toon = toons.dumps(data)
+        assert (
+            '[3]{host,ip}:\n  a,10.0.0.1\n  b,"::1"\n  c,"fe80::1"' == toon
+        )
+        assert [
+            {"host": "a", "ip": "10.0.0.1"},
+            {"host": "b", "ip": "::1"},
+            {"host": "c", "ip": "fe80::1"},
+        ] == toons.loads(toon)
+
+
+class TestDictViewSerialization:
+    """Test that dict views are materialized into arrays."""
+
+    def test_keys_view(self):
+        """A keys view becomes an inline array."""
+        assert "[2]: a,b" == toons.dumps({"a": 1, "b": 2}.keys())
+
+    def test_values_view(self):
+        """A values view becomes an inline array."""
+        assert "[2]: 1,2" == toons.dumps({"a": 1, "b": 2}.values())
+
+    def test_items_view(self):
+        """An items view becomes an array of [key, value] pairs."""
+        assert toons.loads(toons.dumps({"a": 1, "b": 2}.items())) == [
+            ["a", 1],
+            ["b", 2],
+        ]
+
+    def test_nested_view(self):
+        """Views nested inside containers are materialized too."""
+        data = {
+            "names": {"x": 1, "y": 2}.keys(),
+            "rows": [{"k": {"z": 0}.values()}],
+        }
+        assert toons.loads(toons.dumps(data)) == {
+            "names": ["x", "y"],
+            "rows": [{"k": [0]}],
+        }
+
+    def test_view_values_are_normalized(self):
+        """Dict values inside a values view keep their structure."""
+        data = {"a": {"id": 1}, "b": {"id": 2}}
+        assert "[2]{id}:\n  1\n  2" == toons.dumps(data.values())
+
+    def test_input_is_not_mutated(self):
+        """Materialization copies, leaving the caller's data intact."""
+        view = {"x": 1}.keys()
+        data = {"v": view}
+        toons.dumps(data)
+        assert data["v"] is view
+
+
+class Color(enum.Enum):
+    RED = "red"
+    GREEN = "green"
+
+
+class Priority(enum.IntEnum):
+    LOW = 1
+    HIGH = 3
+
+
+class TestEnumSerialization:
+    """Test serialization of enum.Enum members."""
+
+    def test_enum_by_value(self):
+        """Enum members emit their value by default."""
+        assert "color: red" == toons.dumps({"color": Color.RED})
+
+    def test_int_enum_by_value(self):
+        """IntEnum members emit their integer value."""
+        assert "p: 3" == toons.dumps({"p": Priority.HIGH})
+
+    def test_enum_by_name(self):
+        """enum_as="name" emits the member name."""
+        assert "color: RED" == toons.dumps(
+            {"color": Color.RED}, enum_as="name"
+        )
+
+    def test_int_enum_by_name(self):
+        """IntEnum honors enum_as="name" even though it is an int."""
+        assert "p: HIGH" == toons.dumps({"p": Priority.HIGH}, enum_as="name")
+
+    def test_enum_in_inline_array(self):
+        """Enum values participate in inline arrays."""
+        assert "[2]: red,green" == toons.dumps([Color.RED, Color.GREEN])
+
+    def test_enum_in_tabular_array(self):
+        """Enum fields keep the tabular form."""
+        data = [
+            {"c": Color.RED, "p": Priority.LOW},
+            {"c": Color.GREEN, "p": Priority.HIGH},
+        ]
+        assert "[2]{c,p}:\n  red,1\n  green,3" == toons.dumps(data)
+
+    def test_enum_value_is_quoted_when_needed(self):
+        """Enum values go through the normal string quoting rules."""
+
+        class Flag(enum.Enum):
+            YES = "true"
+
+        assert 'f: "true"' == toons.dumps({"f": Flag.YES})
+
+    def test_invalid_enum_as(self):
+        """Unknown enum_as values are rejected."""
+        with pytest.raises(ValueError, match="enum_as"):
+            toons.dumps({"c": Color.RED}, enum_as="ordinal")
+
+
+@dataclass
+class Point:
+    x: int
+    y: int
+
+
+@dataclass
+class Shape:
+    name: str
+    origin: Point
+    vertices: list
+    secret: str = field(default="hidden", repr=False)
+
+
+class TestDataclassSerialization:
+    """Test serialization of dataclass instances as objects."""
+
+    def test_dataclass_as_object(self):
+        """Fields are emitted in declaration order."""
+        assert "x: 1\ny: 2" == toons.dumps(Point(1, 2))
+
+    def test_nested_dataclass(self):
+        """Nested dataclasses and lists of dataclasses recurse."""
+        shape = Shape("tri", Point(0, 0), [Point(1, 2), Point(3, 4)])
+        assert (
+            "name: tri\n"
+            "origin:\n"
+            "  x: 0\n"
+            "  y: 0\n"
+            "vertices[2]{x,y}:\n"
+            "  1,2\n"
+            "  3,4\n"
+            "secret: hidden"
+        ) == toons.dumps(shape)
+
+    def test_list_of_dataclasses_is_tabular(self):
+        """A uniform list of dataclass instances uses the tabular form."""
+        data = {"points": [Point(1, 2), Point(3, 4)]}
+        assert "points[2]{x,y}:\n  1,2\n  3,4" == toons.dumps(data)
+
+    def test_repr_false_field_included(self):
+        """Fields hidden from repr are still serialized."""
+        shape = Shape("sq", Point(0, 0), [], secret="s3cret")
+        assert toons.loads(toons.dumps(shape))["secret"] == "s3cret"
+
+    def test_dataclass_class_is_not_expanded(self):
+        """The dataclass type itself is not an instance and stays null."""
+        assert "cls: null" == toons.dumps({"cls": Point})
+
+    def test_roundtrip_to_dict(self):
+        """Decoding yields the equivalent dict."""
+        assert toons.loads(toons.dumps(Point(5, 6))) == {"x": 5, "y": 6}
+
+
+class TestModelLibrarySerialization:
+    """Test pydantic models and attrs classes encode like dataclasses."""
+
+    def test_pydantic_models(self):
+        pydantic = pytest.importorskip("pydantic")
+
+        class User(pydantic.BaseModel):
+            id: int
+            name: str
+            joined: date = date(2024, 1, 15)
+
+        class Team(pydantic.BaseModel):
+            owner: User
+            members: list
+
+        team = Team(
+            owner=User(id=1, name="Ann"),
+            members=[User(id=2, name="Bob"), User(id=3, name="Cy")],
+        )
+        assert toons.dumps(team) == (
+            "owner:\n  id: 1\n  name: Ann\n  joined: 2024-01-15\n"
+            "members[2]{id,name,joined}:\n"
+            "  2,Bob,2024-01-15\n  3,Cy,2024-01-15"
+        )
+
+    def test_attrs_classes(self):
+        attrs = pytest.importorskip("attrs")
+
+        @attrs.define
+        class Point:
+            x: int
+            y: int
+
+        @attrs.define
+        class Shape:
+            name: str
+            points: list
+
+        shape = Shape("line", [Point(0, 0), Point(3, 4)])
+        assert toons.dumps(shape) == (
+            "name: line\npoints[2]{x,y}:\n  0,0\n  3,4"
+        )
+
+    def test_duck_typed_model_dump(self):
+        class Model:
+            def __iter__(self):
+                return iter([("a", 1)])
+
+            def model_dump(self):
+                return {"a": 1, "b": [Model()]}
+
+        class Leaf(Model):
+            def model_dump(self):
+                return {"c": True}
+
+        class Root(Model):
+            def model_dump(self):
+                return {"a": 1, "leaves": [Leaf(), Leaf()]}
+
+        assert toons.dumps(Root()) == "a: 1\nleaves[2]{c}:\n  true\n  true"
+
+    def test_duck_typed_attrs_fields(self):
+        class Field:
+            def __init__(self, name):
+                self.name = name
+
+        class Pair:
+            __attrs_attrs__ = (Field("left"), Field("right"))
+
+            def __init__(self, left, right):
+                self.left = left
+                self.right = right
+
+        assert toons.dumps(Pair(1, Pair(2, 3))) == (
+            "left: 1\nright:\n  left: 2\n  right: 3"
+        )
+
+    def test_model_classes_are_not_instances(self):
+        class Model:
+            def model_dump(self):
+                return {}
+
+        assert toons.dumps({"cls": Model}) == "cls: null"
+
+
+class Money:
+    def __init__(self, amount, currency):
+        self.amount = amount
+        self.currency = currency
+
+    def __toon__(self):
+        return {"amount": self.amount, "currency": self.currency}
+
+
+class TestToonProtocol:
+    """Test objects that choose their encoding with __toon__()."""
+
+    def test_result_encoded_in_place(self):
+        data = {"price": Money(5, "EUR")}
+        assert toons.dumps(data) == "price:\n  amount: 5\n  currency: EUR"
+
+    def test_uniform_results_are_tabular(self):
+        data = [Money(1, "EUR"), Money(2, "USD")]
+        assert toons.dumps(data) == "[2]{amount,currency}:\n  1,EUR\n  2,USD"
+
+    def test_result_is_normalized_recursively(self):
+        class Wrapper:
+            def __toon__(self):
+                return (Money(1, "EUR"), date(2024, 1, 15))
+
+        assert toons.dumps({"w": Wrapper()}) == (
+            "w[2]:\n  - amount: 1\n    currency: EUR\n  - 2024-01-15"
+        )
+
+    def test_takes_precedence_over_builtin_handling(self):
+        @dataclass
+        class Version:
+            major: int
+            minor: int
+
+            def __toon__(self):
+                return f"{self.major}.{self.minor}"
+
+        class Tag(str):
+            def __toon__(self):
+                return self.upper()
+
+        data = {"v": Version(1, 2), "t": Tag("beta")}
+        assert toons.dumps(data) == 'v: "1.2"\nt: BETA'
+
+    def test_takes_precedence_over_default(self):
+        class Encoder(toons.TOONEncoder):
+            def default(self, obj):
+                return "from default"
+
+        assert Encoder().encode([Money(1, "EUR"), object()]) == (
+            "[2]:\n  - amount: 1\n    currency: EUR\n  - from default"
+        )
+
+    def test_default_may_return_protocol_objects(self):
+        class Encoder(toons.TOONEncoder):
+            def default(self, obj):
+                return Money(0, "EUR")
+
+        assert Encoder().encode({"x": object()}) == (
+            "x:\n  amount: 0\n  currency: EUR"
+        )
+
+    def test_returning_itself_is_circular(self):
+        class Loop:
+            def __toon__(self):
+                return [self]
+
+        with pytest.raises(ValueError, match="Circular reference"):
+            toons.dumps({"a": Loop()})
+
+    def test_endless_new_replacements_raise(self):
+        class Endless:
+            def __toon__(self):
+                return Endless()
+
+        with pytest.raises(RecursionError, match="__toon__"):
+            toons.dumps(Endless())
+
+    def test_errors_propagate(self):
+        class Broken:
+            def __toon__(self):
+                raise RuntimeError("boom")
+
+        with pytest.raises(RuntimeError, match="boom"):
+            toons.dumps(Broken())
+
+    def test_classes_are_not_converted(self):
+        assert toons.dumps({"cls": Money}) == "cls: null"
+
+
+class TestStrictSerialization:
+    """Test strict=True raising for objects with no TOON form."""
+
+    def test_unsupported_object_raises(self):
+        with pytest.raises(TypeError) as exc:
+            toons.dumps({"m": object()}, strict=True)
+        assert str(exc.value) == (
+            "Object of type object is not TOON serializable"
+        )
+
+    def test_unsupported_column_raises(self):
+        class Custom:
+            pass
+
+        rows = [{"id": 1, "c": Custom()}, {"id": 2, "c": Custom()}]
+        assert toons.dumps(rows) == "[2]{id,c}:\n  1,null\n  2,null"
+        with pytest.raises(TypeError, match="type Custom is not TOON"):
+            toons.dumps(rows, strict=True)
+
+    def test_classes_raise(self):
+        with pytest.raises(TypeError, match="type type"):
+            toons.dumps({"cls": Money}, strict=True)
+
+    def test_supported_types_still_encode(self):
+        data = {
+            "d": Decimal("1.5"),
+            "u": uuid.UUID(int=1),
+            "ip": ipaddress.ip_address("10.0.0.1"),
+            "c": Color.RED,
+            "b": b"ab",
+            "n": None,
+            "t": (1, 2),
+        }
+        assert toons.dumps(data, strict=True) == toons.dumps(data)
+
+    def test_toon_method_is_tried_first(self):
+        class Wrapped:
+            def __toon__(self):
+                return {"v": 1}
+
+        assert toons.dumps({"w": Wrapped()}, strict=True) == "w:\n  v: 1"
+
+    def test_encoder_default_is_tried_first(self):
+        class Point:
+            pass
+
+        class Encoder(toons.TOONEncoder):
+            def default(self, obj):
+                if isinstance(obj, Point):
+                    return "point"
+                return super().default(obj)
+
+        encoder = Encoder(strict=True)
+        assert encoder.encode({"p": Point()}) == "p: point"
+        with pytest.raises(TypeError, match="not TOON serializable"):
+            encoder.encode({"o": object()})
+
+    def test_base_encoder_default(self):
+        assert toons.TOONEncoder().encode({"o": object()}) == "o: null"
+        with pytest.raises(TypeError, match="not TOON serializable"):
+            toons.TOONEncoder(strict=True).encode({"o": object()})
+
+    def test_estimate_size_accepts_option(self):
+        with pytest.raises(TypeError, match="not TOON serializable"):
+            toons.estimate_size([object()], strict=True)
+
+
+class RowProxy(Mapping):
+    """Read-only mapping standing in for an ORM row proxy."""
+
+    def __init__(self, **values):
+        self._values = values
+
+    def __getitem__(self, key):
+        return self._values[key]
+
+    def __iter__(self):
+        return iter(self._values)
+
+    def __len__(self):
+        return len(self._values)
+
+
+class TestMappingAndIterableSerialization:
+    """Test generic Mapping and iterable fallbacks."""
+
+    def test_custom_mapping_as_object(self):
+        """A collections.abc.Mapping serializes like a dict."""
+        assert "id: 1\nname: Ada" == toons.dumps(RowProxy(id=1, name="Ada"))
+
+    def test_list_of_mappings_is_tabular(self):
+        """Uniform mapping rows use the tabular form."""
+        rows = [RowProxy(id=1, name="Ada"), RowProxy(id=2, name="Bob")]
+        assert "[2]{id,name}:\n  1,Ada\n  2,Bob" == toons.dumps(rows)
+
+    def test_mappingproxy_as_object(self):
+        """MappingProxyType views serialize like dicts, nested too."""
+        inner = types.MappingProxyType({"c": [1, 2]})
+        proxy = types.MappingProxyType({"a": 1, "b": inner})
+        assert "a: 1\nb:\n  c[2]: 1,2" == toons.dumps(proxy)
+        assert toons.loads(toons.dumps({"p": proxy})) == {
+            "p": {"a": 1, "b": {"c": [1, 2]}}
+        }
+
+    def test_chainmap_as_object(self):
+        """A ChainMap serializes its merged view; earlier maps win."""
+        nested = ChainMap({"x": 1}, {"y": 2})
+        chain = ChainMap({"a": 1}, {"a": 9, "b": nested})
+        assert toons.loads(toons.dumps(chain)) == {
+            "a": 1,
+            "b": {"x": 1, "y": 2},
+        }
+
+    def test_list_of_read_only_mappings_is_tabular(self):
+        """Uniform MappingProxyType and ChainMap rows use the tabular form."""
+        rows = [types.MappingProxyType({"id": 1}), ChainMap({"id": 2})]
+        assert "[2]{id}:\n  1\n  2" == toons.dumps(rows)
+
+    def test_tuple_as_array(self):
+        """Tuples become arrays."""
+        assert "point[2]: 1,2" == toons.dumps({"point": (1, 2)})
+
+    def test_range_as_array(self):
+        """range() is materialized."""
+        assert "[3]: 0,1,2" == toons.dumps(range(3))
+
+    def test_generator_as_array(self):
+        """Generators are consumed into an array."""
+        assert "[3]: 0,1,4" == toons.dumps(x * x for x in range(3))
+
+    def test_frozenset_as_array(self):
+        """Sets become arrays."""
+        assert "s[1]: a" == toons.dumps({"s": frozenset(["a"])})
+
+    def test_nested_tuples(self):
+        """Tuples nested in tuples become nested arrays."""
+        assert toons.loads(toons.dumps({"m": ((1, 2), (3, 4))})) == {
+            "m": [[1, 2], [3, 4]]
+        }
+
+    def test_strings_and_bytes_are_not_iterated(self):
+        """Strings and bytes stay scalars."""
+        assert "s: abc\nb: YWJj" == toons.dumps({"s": "abc", "b": b"abc"})
+
+    def test_class_is_not_iterated(self):
+        """Iterable classes such as Enum types are not expanded."""
+        assert "cls: null" == toons.dumps({"cls": Color})
+
+
 class TestNonSerializableWithDump:
     """Test that dump() also handles non-serializable objects."""
 
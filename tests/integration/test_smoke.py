@@ -1,4 +1,5 @@
 import io
+import json
 import tempfile
 
 import pytest
@@ -100,6 +101,60 @@ class TestSmokeDump:
         assert "1|2|3" in fp.getvalue()
 
 
+    def test_dump_writes_bytes_to_binary_stream(self):
+        """dump() writes UTF-8 bytes to a binary file object."""
+        fp = io.BytesIO()
+        toons.dump({"name": "Zoë"}, fp)
+        assert fp.getvalue() == "name: Zoë".encode()
+
+    def test_dump_writes_bytes_to_file_opened_in_binary_mode(self, tmp_path):
+        """dump() detects files opened with "wb"."""
+        path = tmp_path / "data.toon"
+        with open(path, "wb") as f:
+            toons.dump({"tags": ["a", "b"]}, f)
+        assert path.read_bytes() == b"tags[2]: a,b"
+
+    def test_dump_honours_binary_mode_attribute(self):
+        """dump() writes bytes to a writer whose mode contains "b"."""
+
+        class Writer:
+            mode = "wb"
+
+            def __init__(self):
+                self.chunks = []
+
+            def write(self, data):
+                self.chunks.append(data)
+
+        writer = Writer()
+        toons.dump({"a": 1}, writer)
+        assert writer.chunks == [b"a: 1"]
+
+
+class TestSmokeDumpsBytes:
+    """Minimal smoke test for dumps_bytes() function."""
+
+    def test_dumps_bytes_matches_dumps(self):
+        """dumps_bytes() returns the UTF-8 encoding of dumps()."""
+        data = {"name": "Zoë", "rows": [{"a": 1}, {"a": 2}]}
+        result = toons.dumps_bytes(data)
+        assert type(result) is bytes
+        assert result == toons.dumps(data).encode("utf-8")
+
+    def test_dumps_bytes_accepts_dumps_options(self):
+        """dumps_bytes() takes the keyword arguments of dumps()."""
+        data = {"parent": {"b": 1, "a": [1, 2]}}
+        options = {"indent": 4, "delimiter": "|", "sort_keys": True}
+        assert toons.dumps_bytes(data, **options) == (
+            toons.dumps(data, **options).encode()
+        )
+
+    def test_dumps_bytes_round_trip(self):
+        """loads() reads what dumps_bytes() writes."""
+        data = {"text": "héllo", "n": [1.5, None, True]}
+        assert toons.loads(toons.dumps_bytes(data)) == data
+
+
 class TestSmokeLoad:
     """Minimal smoke test for load() function."""
 
@@ -120,6 +175,52 @@ class TestSmokeLoad:
             assert result == [1, 2, 3]
 
 
+class TestSmokePathArguments:
+    """load() and dump() also accept a path instead of a file object."""
+
+    def test_dump_and_load_pathlib_path(self, tmp_path):
+        """A pathlib.Path round-trips through dump() and load()."""
+        path = tmp_path / "data.toon"
+        data = {"name": "Zoë", "tags": ["a", "b"]}
+        toons.dump(data, path)
+        assert path.read_bytes() == toons.dumps(data).encode("utf-8")
+        assert toons.load(path) == data
+
+    def test_dump_and_load_str_path(self, tmp_path):
+        """A plain str path works too."""
+        path = str(tmp_path / "data.toon")
+        toons.dump({"a": [1, 2]}, path, indent=4)
+        assert toons.load(path, strict=False) == {"a": [1, 2]}
+
+    def test_dump_overwrites_and_keeps_newlines(self, tmp_path):
+        """dump() truncates an existing file and writes plain newlines."""
+        path = tmp_path / "data.toon"
+        path.write_text("old content that is longer than the new one")
+        toons.dump({"a": 1, "b": 2}, path)
+        assert path.read_bytes() == b"a: 1\nb: 2"
+
+    def test_open_handles_still_work(self, tmp_path):
+        """Open file objects keep working."""
+        path = tmp_path / "data.toon"
+        with open(path, "w", encoding="utf-8") as f:
+            toons.dump({"k": "v"}, f)
+        with open(path, encoding="utf-8") as f:
+            assert toons.load(f) == {"k": "v"}
+
+    def test_load_missing_path(self, tmp_path):
+        """A missing file raises FileNotFoundError."""
+        with pytest.raises(FileNotFoundError):
+            toons.load(tmp_path / "missing.toon")
+
+    @pytest.mark.parametrize("fp", [42, None, ["data.toon"]])
+    def test_rejects_non_paths(self, fp):
+        """Objects that are neither files nor paths raise TypeError."""
+        with pytest.raises(TypeError, match="file object"):
+            toons.load(fp)
+        with pytest.raises(TypeError, match="file object"):
+            toons.dump({"a": 1}, fp)
+
+
 class TestSmokeStrictFlag:
     """Minimal smoke test for strict parameter."""
 
@@ -170,3 +271,63 @@ class TestSmokeUnicode:
         s = toons.dumps(d)
         t = toons.loads(s)
         assert t == d
+
+
+class TestSmokeJsonConversion:
+    """Minimal smoke test for toon_to_json() and json_to_toon()."""
+
+    def test_toon_to_json_basic(self):
+        """toon_to_json() writes compact JSON in document order."""
+        result = toons.toon_to_json("b: 1\na[2]: x,y\nc: null")
+        assert result == '{"b":1,"a":["x","y"],"c":null}'
+
+    def test_toon_to_json_tabular(self):
+        """toon_to_json() expands tabular rows into objects."""
+        result = toons.toon_to_json("rows[2]{id,ok}:\n  1,true\n  2,false")
+        assert json.loads(result) == {
+            "rows": [{"id": 1, "ok": True}, {"id": 2, "ok": False}]
+        }
+
+    def test_toon_to_json_accepts_bytes(self):
+        """toon_to_json() reads UTF-8 bytes like loads()."""
+        assert toons.toon_to_json("name: Zoë".encode()) == '{"name":"Zoë"}'
+
+    def test_toon_to_json_strict_flag(self):
+        """toon_to_json() forwards strict to the decoder."""
+        with pytest.raises(toons.ToonDecodeError):
+            toons.toon_to_json("# note\na: 1")
+        assert toons.toon_to_json("# note\na: 1", strict=False) == '{"a":1}'
+
+    def test_json_to_toon_basic(self):
+        """json_to_toon() writes what dumps() writes."""
+        text = '{"z": 1, "a": [1, 2], "rows": [{"id": 1}, {"id": 2}]}'
+        assert toons.json_to_toon(text) == toons.dumps(json.loads(text))
+
+    def test_json_to_toon_keeps_key_order(self):
+        """json_to_toon() keeps object keys in JSON order."""
+        assert toons.json_to_toon('{"b": 1, "a": 2}') == "b: 1\na: 2"
+
+    def test_json_to_toon_indent(self):
+        """json_to_toon() honours indent."""
+        result = toons.json_to_toon('{"user": {"name": "Bob"}}', indent=4)
+        assert result == "user:\n    name: Bob"
+
+    def test_json_to_toon_large_integers(self):
+        """json_to_toon() writes integers beyond the i64 range exactly."""
+        assert (
+            toons.json_to_toon('{"n": 18446744073709551615}')
+            == "n: 18446744073709551615"
+        )
+
+    def test_json_to_toon_invalid_json(self):
+        """json_to_toon() raises ValueError for malformed JSON."""
+        with pytest.raises(ValueError, match="Invalid JSON"):
+            toons.json_to_toon('{"a": ')
+
+    def test_round_trip(self):
+        """JSON -> TOON -> JSON is lossless for JSON documents."""
+        text = (
+            '{"id":-9007199254740993,"pi":3.5,"name":"x, y",'
+            '"tags":[],"nested":{"deep":[{"a":1,"b":null}]}}'
+        )
+        assert toons.toon_to_json(toons.json_to_toon(text)) == text
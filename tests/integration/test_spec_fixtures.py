@@ -146,6 +146,28 @@ def test_integration_dump(
             assert result == expected, f"Failed: {test_id}\nNote: {note}"
 
 
+@pytest.mark.parametrize(
+    "test_id,input_data,expected,options,should_error,note",
+    [
+        case
+        for case in collect_encode_fixtures()
+        if not case[4] and set(case[3]) <= {"indent"}
+    ],
+    ids=lambda x: x if isinstance(x, str) else "",
+)
+def test_integration_json_to_toon(
+    test_id: str,
+    input_data: Any,
+    expected: str,
+    options: dict[str, Any],
+    should_error: bool,
+    note: str,
+):
+    """Test encoding JSON text to TOON, without Python objects in between."""
+    result = toons.json_to_toon(json.dumps(input_data), **options)
+    assert result == expected, f"Failed: {test_id}\nNote: {note}"
+
+
 @pytest.mark.parametrize(
     "test_id,input_toon,expected,options,should_error,note",
     collect_decode_fixtures(),
@@ -0,0 +1,101 @@
+import ast
+import inspect
+import pathlib
+
+import pytest
+
+import toons
+
+STUB_PATH = pathlib.Path(__file__).parents[2] / "toons.pyi"
+
+
+def _stub_functions():
+    tree = ast.parse(STUB_PATH.read_text(encoding="utf-8"))
+    return {
+        node.name: node
+        for node in tree.body
+        if isinstance(node, ast.FunctionDef)
+    }
+
+
+class TestTypeStubs:
+    """toons.pyi must describe the compiled module's public API."""
+
+    @pytest.mark.parametrize(
+        "name",
+        [
+            "dumps",
+            "dumps_bytes",
+            "dump",
+            "loads",
+            "load",
+            "estimate_size",
+            "check_roundtrip",
+            "validate",
+            "toon_to_json",
+            "json_to_toon",
+            "iter_rows",
+        ],
+    )
+    def test_signature_matches_runtime(self, name):
+        node = _stub_functions()[name]
+        runtime = inspect.signature(getattr(toons, name)).parameters
+
+        positional = [arg.arg for arg in node.args.args]
+        keyword_only = [arg.arg for arg in node.args.kwonlyargs]
+        expected_positional = [
+            p.name
+            for p in runtime.values()
+            if p.kind is not inspect.Parameter.KEYWORD_ONLY
+        ]
+        expected_keyword_only = [
+            p.name
+            for p in runtime.values()
+            if p.kind is inspect.Parameter.KEYWORD_ONLY
+        ]
+        assert positional == expected_positional
+        assert keyword_only == expected_keyword_only
+
+    def test_every_public_function_has_a_stub(self):
+        stubs = _stub_functions()
+        functions = [
+            name
+            for name, value in vars(toons).items()
+            if inspect.isbuiltin(value) and not name.startswith("_")
+        ]
+        assert functions
+        for name in functions:
+            assert name in stubs, name
+
+    def test_module_attributes_declared(self):
+        tree = ast.parse(STUB_PATH.read_text(encoding="utf-8"))
+        declared = {
+            node.target.id
+            for node in tree.body
+            if isinstance(node, ast.AnnAssign)
+        }
+        classes = {
+            node.name for node in tree.body if isinstance(node, ast.ClassDef)
+        }
+        assert "__version__" in declared
+        assert isinstance(toons.__version__, str)
+        assert "ToonDecodeError" in classes
+
+    @pytest.mark.parametrize("name", ["TOONEncoder", "TOONDecoder"])
+    def test_class_constructor_matches_runtime(self, name):
+        tree = ast.parse(STUB_PATH.read_text(encoding="utf-8"))
+        node = next(
+            node
+            for node in tree.body
+            if isinstance(node, ast.ClassDef) and node.name == name
+        )
+        methods = {
+            item.name: item
+            for item in node.body
+            if isinstance(item, ast.FunctionDef)
+        }
+        init = methods["__init__"]
+        runtime = inspect.signature(getattr(toons, name)).parameters
+        assert [arg.arg for arg in init.args.kwonlyargs] == list(runtime)
+        for method in methods:
+            assert hasattr(getattr(toons, name), method), method
@@ -0,0 +1,65 @@
+import sys
+import threading
+import time
+
+import toons
+
+ROWS = [
+    {"id": i, "name": f"user{i}", "score": i * 0.25, "active": i % 2 == 0}
+    for i in range(50_000)
+]
+
+
+def _run_in_threads(func, count=2):
+    results = [None] * count
+
+    def work(index):
+        results[index] = func()
+
+    threads = [
+        threading.Thread(target=work, args=(index,)) for index in range(count)
+    ]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+    return results
+
+
+class TestConcurrentEncoding:
+    """Large tables are encoded with the GIL released."""
+
+    def test_two_threads_match_single_thread(self):
+        expected = toons.dumps({"rows": ROWS})
+        results = _run_in_threads(lambda: toons.dumps({"rows": ROWS}))
+        assert results == [expected, expected]
+
+    def test_two_threads_decode(self):
+        text = toons.dumps({"rows": ROWS})
+        results = _run_in_threads(lambda: toons.loads(text))
+        assert results == [{"rows": ROWS}, {"rows": ROWS}]
+
+    def test_other_threads_run_during_encode(self):
+        progress = [0]
+        stop = threading.Event()
+
+        def spin():
+            while not stop.is_set():
+                progress[0] += 1
+
+        # A long switch interval keeps the GIL with this thread between
+        # bytecodes, so the spinner can only advance while dumps releases it
+        interval = sys.getswitchinterval()
+        sys.setswitchinterval(0.5)
+        spinner = threading.Thread(target=spin)
+        spinner.start()
+        try:
+            time.sleep(0.01)
+            before = progress[0]
+            toons.dumps({"rows": ROWS})
+            after = progress[0]
+        finally:
+            stop.set()
+            spinner.join()
+            sys.setswitchinterval(interval)
+        assert after > before
@@ -0,0 +1,123 @@
+"""
+Tests for multibyte (non-ASCII) content in keys, values and headers.
+
+The parser locates colons and brackets while walking the line; these tests
+guard against byte/char offset mix-ups when slicing around them.
+"""
+
+import toons
+
+
+class TestMultibyteKeys:
+    """Keys containing multibyte characters before the colon."""
+
+    def test_quoted_multibyte_key(self):
+        """A quoted key with a multibyte char parses without panicking."""
+        assert toons.loads('"naïve": 1') == {"naïve": 1}
+
+    def test_unquoted_multibyte_key(self):
+        """An unquoted key with a multibyte char parses without panicking."""
+        assert toons.loads("naïve: 1\ncafé: 2") == {"naïve": 1, "café": 2}
+
+    def test_multibyte_key_nested_object(self):
+        """Multibyte keys work at nested depths."""
+        assert toons.loads("naïve:\n  größe: 3") == {"naïve": {"größe": 3}}
+
+
+class TestMultibyteBeforeArrayBracket:
+    """Multibyte characters preceding an inline array bracket."""
+
+    def test_unquoted_key_inline_array(self):
+        """Inline array header after a multibyte key."""
+        assert toons.loads("naïve[2]: a,b") == {"naïve": ["a", "b"]}
+
+    def test_quoted_key_inline_array(self):
+        """Inline array header after a quoted multibyte key."""
+        assert toons.loads('"ü"[2]: a,b') == {"ü": ["a", "b"]}
+
+    def test_multibyte_values_in_inline_array(self):
+        """Multibyte values inside the inline array body."""
+        assert toons.loads("k[3]: é,ü,名前") == {"k": ["é", "ü", "名前"]}
+
+    def test_tabular_multibyte_key_and_fields(self):
+        """Tabular header with multibyte key and field names."""
+        result = toons.loads("é[1]{ü,名}:\n  1,値")
+        assert result == {"é": [{"ü": 1, "名": "値"}]}
+
+    def test_list_item_multibyte_inline_array(self):
+        """Inline array on a list item whose key is multibyte."""
+        assert toons.loads("[1]:\n  - é[1]: ü") == [{"é": ["ü"]}]
+
+
+class TestUnicodeIdentifierKeys:
+    """Unicode identifier keys are emitted unquoted and round-trip."""
+
+    def test_accented_key_unquoted(self):
+        """Accented letters are identifier characters."""
+        assert toons.dumps({"café": 1}) == "café: 1"
+
+    def test_cjk_key_unquoted(self):
+        """CJK ideographs are identifier characters."""
+        assert toons.dumps({"名前": "太郎"}) == "名前: 太郎"
+
+    def test_unicode_tabular_fields_unquoted(self):
+        """Unicode field names in tabular headers stay unquoted."""
+        data = {"élèves": [{"nom": "A", "âge": 1}, {"nom": "B", "âge": 2}]}
+        assert toons.dumps(data) == "élèves[2]{nom,âge}:\n  A,1\n  B,2"
+
+    def test_unicode_key_with_space_is_quoted(self):
+        """Spaces still force quoting."""
+        assert toons.dumps({"café au lait": 1}) == '"café au lait": 1'
+
+    def test_unicode_key_with_colon_or_bracket_is_quoted(self):
+        """Colons and brackets still force quoting."""
+        assert toons.dumps({"名:前": 1}) == '"名:前": 1'
+        assert toons.dumps({"名[0]": 1}) == '"名[0]": 1'
+
+    def test_symbol_key_is_quoted(self):
+        """Non-identifier symbols are still quoted."""
+        assert toons.dumps({"Test®": 1}) == '"Test®": 1'
+
+    def test_unquoted_unicode_key_round_trip(self):
+        """Unquoted Unicode keys decode back to the same dict."""
+        data = {"café": {"名前": [1, 2]}, "größe": "xl"}
+        assert toons.loads(toons.dumps(data)) == data
+
+    def test_unicode_key_folding_round_trip(self):
+        """Folded Unicode key chains expand back under expand_paths."""
+        data = {"café": {"名前": 1}}
+        encoded = toons.dumps(data, key_folding="safe")
+        assert encoded == "café.名前: 1"
+        assert toons.loads(encoded, expand_paths="safe") == data
+
+
+class TestUnicodeValues:
+    """Non-ASCII values are quoted by the same rules in every position."""
+
+    def test_symbol_value_unquoted(self):
+        """Only structural characters force quoting, not non-ASCII ones."""
+        assert toons.dumps({"mark": "Test®"}) == "mark: Test®"
+        assert toons.dumps("®") == "®"
+
+    def test_symbol_values_in_arrays_and_rows(self):
+        """Inline arrays, tabular cells and list items quote alike."""
+        data = {
+            "tags": ["©", "€5"],
+            "rows": [{"id": 1, "sym": "®"}, {"id": 2, "sym": "™"}],
+            "items": [{"a": [1]}, "é"],
+        }
+        assert toons.dumps(data) == (
+            "tags[2]: ©,€5\n"
+            "rows[2]{id,sym}:\n  1,®\n  2,™\n"
+            "items[2]:\n  - a[1]: 1\n  - é"
+        )
+        assert toons.loads(toons.dumps(data)) == data
+
+    def test_symbol_with_structural_char_quoted(self):
+        """A structural character still forces quoting next to symbols."""
+        assert toons.dumps({"v": "®: x"}) == 'v: "®: x"'
+
+    def test_symbol_key_quoted_value_not(self):
+        """Keys follow identifier rules; values do not."""
+        assert toons.dumps({"®": "®"}) == '"®": ®'
+        assert toons.loads('"®": ®') == {"®": "®"}
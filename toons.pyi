@@ -1,6 +1,20 @@
 """TOONS Python API for parsing and serializing TOON format."""
 
-from typing import IO, Any, Optional
+import os
+from typing import (
+    IO,
+    Any,
+    Callable,
+    Dict,
+    Iterator,
+    List,
+    Literal,
+    Optional,
+    Tuple,
+    Union,
+)
+
+__version__: str
 
 class ToonDecodeError(ValueError):
     """Exception raised by the TOON decoder when input cannot be parsed.
@@ -29,70 +43,547 @@ class ToonDecodeError(ValueError):
     source: Optional[str]
 
 def load(
-    fp: IO[str],
+    fp: Union[
+        IO[str],
+        IO[bytes],
+        Iterator[str],
+        Iterator[bytes],
+        str,
+        bytes,
+        os.PathLike,
+    ],
     *,
     strict: bool = True,
     expand_paths: Optional[str] = None,
     indent: Optional[int] = None,
+    collect_warnings: bool = False,
+    bare_hyphen: Literal["empty_object", "null"] = "empty_object",
+    resolve_aliases: bool = False,
+    parse_datetime: bool = False,
+    max_depth: int = 1000,
+    object_hook: Optional[Callable[[Dict[str, Any]], Any]] = None,
+    object_pairs_hook: Optional[
+        Callable[[List[Tuple[str, Any]]], Any]
+    ] = None,
+    null_literal: str = "null",
+    true_literal: str = "true",
+    false_literal: str = "false",
+    nan_token: Optional[str] = None,
+    inf_token: Optional[str] = None,
+    neg_inf_token: Optional[str] = None,
+    allow_trailing_delimiter: bool = False,
+    allow_tab_indent: bool = False,
+    leading_zero: Literal["string", "number"] = "string",
+    empty_cell: Literal["string", "null"] = "string",
+    schema: Any = None,
 ) -> Any:
     """Parse TOON from a text file object.
 
     Args:
-        fp: File-like object with a .read() method.
-        strict: Enforce strict TOON v3.0 compliance.
+        fp: File-like object with a .read() method, in text mode or in
+            binary mode (UTF-8 bytes, see :func:`loads`); a stream with
+            ``readable()`` but no ``read()``, or an iterator such as a
+            generator, whose lines (``str`` or ``bytes``) are joined; or the
+            path of a UTF-8 file to open, read and close.
+        strict: Enforce strict TOON v3.0 compliance. ``False`` tolerates
+            blank lines in arrays and skips full-line ``#`` comments.
         expand_paths: Path expansion mode: None, "off", "safe", "always".
-        indent: Optional indentation hint for parsing.
+        indent: Optional indentation hint for parsing (minimum 2).
+        collect_warnings: Return ``(value, warnings)`` instead of ``value``,
+            where ``warnings`` is a list of ``(line, message)`` tuples for
+            each deviation the parser tolerated (blank lines inside arrays,
+            non-multiple indentation, skipped over-indented lines).
+        bare_hyphen: How a bare ``-`` list item decodes: ``"empty_object"``
+            (``{}``, what ``dumps`` writes for an empty dict) or ``"null"``.
+        resolve_aliases: Turn unquoted ``*/path`` values written by
+            ``dumps(..., share_by_identity=True)`` into references to the
+            object at that JSON-pointer path, restoring shared (and cyclic)
+            structure. Hooks see the resolved objects; a cycle through an
+            object raises ``ValueError`` when a hook is given.
+        parse_datetime: Decode values (quoted or not) that fully match
+            ISO-8601 ``YYYY-MM-DD``, ``HH:MM:SS[.ffffff][offset]`` or a date
+            and time joined by ``T``/space into ``date``, ``time`` and
+            ``datetime`` objects, reversing what ``dumps`` writes for them.
+            Anything else, such as ``2026`` or invalid dates, stays a string.
+        max_depth: Deepest nesting of objects and lists to accept. Deeper
+            input raises ``ToonDecodeError`` instead of exhausting the stack,
+            which guards against hostile documents.
+        object_hook: Called with every decoded ``dict``, innermost first;
+            its return value takes the dict's place, as with ``json.loads``.
+        object_pairs_hook: Like ``object_hook``, but called with a list of
+            the object's ``(key, value)`` pairs in document order, e.g.
+            ``collections.OrderedDict``. Takes precedence over
+            ``object_hook``. A key repeated in the document appears once
+            per occurrence, as with ``json.loads``.
+        null_literal: An extra token read as ``None`` wherever ``null`` is,
+            e.g. ``"None"`` for documents written by ``dumps`` with the same
+            option. ``null`` itself still decodes to ``None``.
+        true_literal: An extra token read as ``True``, like ``null_literal``.
+        false_literal: An extra token read as ``False``, like
+            ``null_literal``.
+        nan_token, inf_token, neg_inf_token: Read a quoted string equal
+            to one of these as ``float('nan')``, ``float('inf')`` or
+            ``float('-inf')``, undoing the ``dumps`` options of the same
+            names. Unquoted tokens and other strings are unaffected.
+        allow_trailing_delimiter: Accept inline arrays and tabular rows
+            ending in a delimiter (``1,2,``) by dropping the single empty
+            value after it before the length check. Off by default, so such
+            rows are rejected as having one value too many.
+        allow_tab_indent: Accept tabs in indentation, one tab per nesting
+            level, as written by ``dumps(..., indent_char="\t")``. Off by
+            default, so tabs are rejected in strict mode and warned about
+            otherwise.
+        leading_zero: How unquoted numbers with leading zeros (``007``,
+            ``-007``) decode: ``"string"`` keeps them as strings, which
+            preserves IDs and codes; ``"number"`` reads them as numbers, so
+            ``007`` is ``7``. A lone ``0`` is always the integer ``0``.
+        empty_cell: How an empty value between two delimiters, as in
+            ``a,,c``, decodes in inline arrays and tabular rows:
+            ``"string"`` reads it as ``""``, as the spec does; ``"null"``
+            reads it as ``None``, in typed columns too. A quoted ``""`` is
+            always the empty string.
+        schema: Shape the decoded value must have, turning decoding into a
+            validation step for untrusted input. A type (``int``, ``float``,
+            ``str``, ``bool``, ``None`` or any class, checked with
+            ``isinstance``), a dict of key to schema (every key required,
+            other keys kept), ``[item_schema]`` for a list, a tuple of
+            alternatives, a ``TypedDict`` or ``typing`` forms such as
+            ``List[int]`` and ``Optional[str]``. Where unambiguous, values
+            are coerced: an int to ``float``, an integral float or a string
+            of digits to ``int``, a numeric string to ``float`` and
+            ``"true"``/``"false"`` to ``bool``. A mismatch raises
+            ``ToonDecodeError`` naming its path, such as ``/users/0/id``.
+            Checked before ``object_hook`` runs.
 
     Returns:
         The parsed Python object.
 
     Raises:
-        ToonDecodeError: If the input is malformed. Subclass of ValueError.
+        ToonDecodeError: If the input is malformed or does not match
+            ``schema``. Subclass of ValueError.
+        ValueError: If an option is invalid (e.g. ``indent < 2``).
+        TypeError: If ``schema`` is not a supported spec.
+    """
+    ...
+
+def iter_rows(
+    fp: Union[IO[str], IO[bytes], str, bytes, os.PathLike],
+    *,
+    strict: bool = True,
+) -> Iterator[Dict[str, Any]]:
+    """Iterate over the rows of a file holding a root tabular array.
+
+    The file must contain a single ``[N]{fields}:`` array at the root. Rows
+    are decoded a chunk of the file at a time and yielded as dicts, so a
+    large table is never materialized as one list.
+
+    Args:
+        fp: File-like object with a .read() method, in text or binary mode,
+            or the path of a UTF-8 file to open (and close once iteration
+            finishes).
+        strict: Enforce strict TOON v3.0 compliance, as for :func:`load`.
+
+    Returns:
+        An iterator yielding one dict per row.
+
+    Raises:
+        ToonDecodeError: Immediately if the document does not start with a
+            tabular array header; during iteration when a malformed row is
+            reached (after the rows before it), or at the end if the number
+            of rows differs from the declared ``[N]``.
     """
     ...
 
 def loads(
-    s: str,
+    s: Union[str, bytes, bytearray],
     *,
     strict: bool = True,
     expand_paths: Optional[str] = None,
     indent: Optional[int] = None,
+    collect_warnings: bool = False,
+    bare_hyphen: Literal["empty_object", "null"] = "empty_object",
+    resolve_aliases: bool = False,
+    parse_datetime: bool = False,
+    max_depth: int = 1000,
+    object_hook: Optional[Callable[[Dict[str, Any]], Any]] = None,
+    object_pairs_hook: Optional[
+        Callable[[List[Tuple[str, Any]]], Any]
+    ] = None,
+    null_literal: str = "null",
+    true_literal: str = "true",
+    false_literal: str = "false",
+    nan_token: Optional[str] = None,
+    inf_token: Optional[str] = None,
+    neg_inf_token: Optional[str] = None,
+    allow_trailing_delimiter: bool = False,
+    allow_tab_indent: bool = False,
+    leading_zero: Literal["string", "number"] = "string",
+    empty_cell: Literal["string", "null"] = "string",
+    schema: Any = None,
 ) -> Any:
     """Parse a TOON string.
 
+    Tabular fields annotated with a type, as ``dumps(...,
+    typed_tabular=True)`` writes them (``[2]{count:int,ratio:float}:``),
+    decode every non-null cell of the column as that type. The types are
+    ``int``, ``float``, ``bool``, ``str`` and ``null``.
+
     Args:
-        s: TOON-formatted string.
-        strict: Enforce strict TOON v3.0 compliance.
+        s: TOON-formatted string, or ``bytes``/``bytearray`` holding it as
+            UTF-8. A leading byte order mark (``\ufeff``) is ignored.
+        strict: Enforce strict TOON v3.0 compliance. ``False`` tolerates
+            blank lines in arrays and skips full-line ``#`` comments,
+            keeps cells that do not fit their column's type as they are,
+            leaves columns of an unknown type untyped, reads numbers with
+            digit separators (``1_000``) and hexadecimal, octal and binary
+            integers (``0xff``, ``0o17``, ``0b1010``) as numbers, and reads
+            ``True``,
+            ``FALSE``, ``None``, ``NULL``, ``nil`` and other casings of the
+            literals as booleans and null.
         expand_paths: Path expansion mode: None, "off", "safe", "always".
-        indent: Optional indentation hint for parsing.
+        indent: Optional indentation hint for parsing (minimum 2).
+        collect_warnings: Return ``(value, warnings)`` instead of ``value``,
+            where ``warnings`` is a list of ``(line, message)`` tuples for
+            each deviation the parser tolerated (blank lines inside arrays,
+            non-multiple indentation, skipped over-indented lines).
+        bare_hyphen: How a bare ``-`` list item decodes: ``"empty_object"``
+            (``{}``, what ``dumps`` writes for an empty dict) or ``"null"``.
+        resolve_aliases: Turn unquoted ``*/path`` values written by
+            ``dumps(..., share_by_identity=True)`` into references to the
+            object at that JSON-pointer path, restoring shared (and cyclic)
+            structure. Hooks see the resolved objects; a cycle through an
+            object raises ``ValueError`` when a hook is given.
+        parse_datetime: Decode values (quoted or not) that fully match
+            ISO-8601 ``YYYY-MM-DD``, ``HH:MM:SS[.ffffff][offset]`` or a date
+            and time joined by ``T``/space into ``date``, ``time`` and
+            ``datetime`` objects, reversing what ``dumps`` writes for them.
+            Anything else, such as ``2026`` or invalid dates, stays a string.
+        max_depth: Deepest nesting of objects and lists to accept. Deeper
+            input raises ``ToonDecodeError`` instead of exhausting the stack,
+            which guards against hostile documents.
+        object_hook: Called with every decoded ``dict``, innermost first;
+            its return value takes the dict's place, as with ``json.loads``.
+        object_pairs_hook: Like ``object_hook``, but called with a list of
+            the object's ``(key, value)`` pairs in document order, e.g.
+            ``collections.OrderedDict``. Takes precedence over
+            ``object_hook``. A key repeated in the document appears once
+            per occurrence, as with ``json.loads``.
+        null_literal: An extra token read as ``None`` wherever ``null`` is,
+            e.g. ``"None"`` for documents written by ``dumps`` with the same
+            option. ``null`` itself still decodes to ``None``.
+        true_literal: An extra token read as ``True``, like ``null_literal``.
+        false_literal: An extra token read as ``False``, like
+            ``null_literal``.
+        nan_token, inf_token, neg_inf_token: Read a quoted string equal
+            to one of these as ``float('nan')``, ``float('inf')`` or
+            ``float('-inf')``, undoing the ``dumps`` options of the same
+            names. Unquoted tokens and other strings are unaffected.
+        allow_trailing_delimiter: Accept inline arrays and tabular rows
+            ending in a delimiter (``1,2,``) by dropping the single empty
+            value after it before the length check. Off by default, so such
+            rows are rejected as having one value too many.
+        allow_tab_indent: Accept tabs in indentation, one tab per nesting
+            level, as written by ``dumps(..., indent_char="\t")``. Off by
+            default, so tabs are rejected in strict mode and warned about
+            otherwise.
+        leading_zero: How unquoted numbers with leading zeros (``007``,
+            ``-007``) decode: ``"string"`` keeps them as strings, which
+            preserves IDs and codes; ``"number"`` reads them as numbers, so
+            ``007`` is ``7``. A lone ``0`` is always the integer ``0``.
+        empty_cell: How an empty value between two delimiters, as in
+            ``a,,c``, decodes in inline arrays and tabular rows:
+            ``"string"`` reads it as ``""``, as the spec does; ``"null"``
+            reads it as ``None``, in typed columns too. A quoted ``""`` is
+            always the empty string.
+        schema: Shape the decoded value must have, turning decoding into a
+            validation step for untrusted input. A type (``int``, ``float``,
+            ``str``, ``bool``, ``None`` or any class, checked with
+            ``isinstance``), a dict of key to schema (every key required,
+            other keys kept), ``[item_schema]`` for a list, a tuple of
+            alternatives, a ``TypedDict`` or ``typing`` forms such as
+            ``List[int]`` and ``Optional[str]``. Where unambiguous, values
+            are coerced: an int to ``float``, an integral float or a string
+            of digits to ``int``, a numeric string to ``float`` and
+            ``"true"``/``"false"`` to ``bool``. A mismatch raises
+            ``ToonDecodeError`` naming its path, such as ``/users/0/id``.
+            Checked before ``object_hook`` runs.
 
     Returns:
         The parsed Python object.
 
     Raises:
-        ToonDecodeError: If the input is malformed. Subclass of ValueError;
-            carries structured ``.line`` and ``.source`` attributes.
+        ToonDecodeError: If the input is malformed, bytes input is not
+            valid UTF-8 or the value does not match ``schema``. Subclass of
+            ValueError; carries structured ``.line`` and ``.source``
+            attributes (``None`` for a schema mismatch).
+        ValueError: If an option is invalid (e.g. ``indent < 2``).
+        TypeError: If ``s`` is not ``str``, ``bytes`` or ``bytearray``,
+            a hook is not callable or ``schema`` is not a supported spec.
+    """
+    ...
+
+def validate(
+    s: Union[str, bytes, bytearray],
+    *,
+    strict: bool = True,
+    indent: Optional[int] = None,
+    max_depth: int = 1000,
+) -> bool:
+    """Check that a TOON document is well-formed without decoding it.
+
+    Runs the same parser as :func:`loads`, including declared-length and
+    indentation checks, but does not build Python values, so it is cheaper
+    than ``loads`` as a guard for large documents.
+
+    Args:
+        s: TOON-formatted string, or ``bytes``/``bytearray`` holding it as
+            UTF-8.
+        strict: Enforce strict TOON v3.0 compliance.
+        indent: Optional indentation hint for parsing (minimum 2).
+        max_depth: Deepest nesting to accept, as for :func:`loads`.
+
+    Returns:
+        ``True`` when the document is well-formed.
+
+    Raises:
+        ToonDecodeError: If the input is malformed, with the same message,
+            ``.line`` and ``.source`` that :func:`loads` would report.
+    """
+    ...
+
+def toon_to_json(s: Union[str, bytes, bytearray], *, strict: bool = True) -> str:
+    """Convert a TOON document to compact JSON.
+
+    Args:
+        s: TOON-formatted string, or ``bytes``/``bytearray`` holding it as
+            UTF-8.
+        strict: Enforce strict TOON v3.0 compliance, as for :func:`loads`.
+            Either way, lines indented deeper than their block are
+            rejected, where ``loads`` skips them.
+
+    Returns:
+        The document as JSON text, with object keys in document order.
+        Integers beyond 64 bits become floats.
+
+    Raises:
+        ToonDecodeError: If the input is malformed.
+    """
+    ...
+
+def json_to_toon(s: str, *, indent: int = 2) -> str:
+    """Convert a JSON document to TOON.
+
+    Args:
+        s: JSON text.
+        indent: Spaces per indentation level (minimum 2).
+
+    Returns:
+        The document as :func:`dumps` writes it, with object keys in the
+        order the JSON lists them.
+
+    Raises:
+        ValueError: If ``s`` is not valid JSON or ``indent`` is below 2.
     """
     ...
 
 def dump(
     obj: Any,
-    fp: IO[str],
+    fp: Union[IO[str], str, bytes, os.PathLike],
     *,
     indent: int = 2,
     delimiter: str = ",",
     key_folding: Optional[str] = None,
     flatten_depth: Optional[int] = None,
+    sort_keys: Union[bool, Literal["natural"]] = False,
+    bytes_encoding: Literal["base64", "hex"] = "base64",
+    enum_as: Literal["value", "name"] = "value",
+    float_precision: Optional[int] = None,
+    strip_trailing_zeros: bool = True,
+    skipkeys: bool = False,
+    share_by_identity: bool = False,
+    tabular: Literal["auto", "never", "fill"] = "auto",
+    allow_exponent: bool = False,
+    preserve_float: bool = False,
+    allow_nan: bool = True,
+    typed_tabular: bool = False,
+    rich_numbers: bool = False,
+    quote_strings: bool = False,
+    null_literal: str = "null",
+    true_literal: str = "true",
+    false_literal: str = "false",
+    inline_threshold: Optional[int] = None,
+    align: bool = False,
+    nan_token: Optional[str] = None,
+    inf_token: Optional[str] = None,
+    neg_inf_token: Optional[str] = None,
+    tabular_field_order: Literal["first", "sorted", "union"] = "first",
+    compact: bool = False,
+    tabular_nested_arrays: bool = False,
+    timedelta_as: Literal["seconds", "iso"] = "seconds",
+    indent_char: Literal[" ", "\t"] = " ",
+    line_separator: Literal["\n", "\r\n"] = "\n",
+    header_comment: Optional[str] = None,
+    tuple_key_sep: Optional[str] = None,
+    ensure_ascii: bool = False,
+    strict: bool = False,
+    max_line_length: Optional[int] = None,
+    sort_rows_by: Optional[str] = None,
 ) -> None:
     """Serialize an object to TOON and write it to a file object.
 
     Args:
         obj: Python object to serialize.
-        fp: File-like object with a .write() method.
-        indent: Spaces per indentation level.
-        delimiter: Array/tabular delimiter (",", "\t", or "|").
+        fp: File-like object with a .write() method, or the path of a file
+            to create (or overwrite) as UTF-8. Binary file objects (``io``
+            binary streams, or any object whose ``mode`` contains ``"b"``)
+            are written UTF-8 bytes; others are written a ``str``.
+        indent: Spaces per indentation level (minimum 2).
+        delimiter: Array/tabular delimiter: ",", "\t", "|" or another
+            ASCII punctuation character (e.g. ";") other than
+            ``" \\ [ ] { } : - + . _ / * %``; or ``"auto"`` to pick, per
+            document, whichever of ",", "|" and "\t" the fewest string
+            values need quoting for (ties prefer that order). A non-comma
+            delimiter is declared in each array header.
         key_folding: Flatten nested keys: None, "safe", "on", "always".
-        flatten_depth: Maximum depth for key folding.
+        flatten_depth: Maximum number of keys folded into one path,
+            counted from the first key of the chain at any depth.
+        sort_keys: Sort object keys and tabular fields. ``True`` sorts
+            lexicographically; ``"natural"`` compares digit runs numerically
+            so ``item2`` precedes ``item10``.
+        bytes_encoding: Text encoding for ``bytes``/``bytearray`` values,
+            ``"base64"`` or ``"hex"``. Decoding does not reverse this; the
+            value comes back as a ``str``.
+        enum_as: How ``enum.Enum`` members are written: ``"value"``
+            emits the member's ``.value``, ``"name"`` its ``.name``.
+        float_precision: Round floats to this many significant digits.
+            Output uses exponent notation only with ``allow_exponent``.
+        strip_trailing_zeros: Drop trailing fractional zeros produced by
+            ``float_precision`` (``False`` keeps ``1.50``). Has no effect
+            without ``float_precision``.
+        skipkeys: Drop dict keys that are not ``str``, ``int``, ``float``,
+            ``bool`` or ``None`` instead of raising ``TypeError``. Supported
+            non-string keys are always converted as ``json.dumps`` does.
+        share_by_identity: Write each container reached more than once
+            (the same object by ``id()``, including cycles) in full only at
+            its first occurrence; repeats become ``*/path`` aliases that
+            ``loads(..., resolve_aliases=True)`` turns back into shared
+            references. Cannot be combined with ``key_folding``.
+        tabular: When lists of objects use the ``[N]{fields}:`` form.
+            ``"auto"`` requires every row to have the same keys;
+            ``"fill"`` also accepts differing keys, using their union as the
+            header and ``null`` for missing cells; ``"never"`` always writes
+            expanded lists. Rows with nested values are never tabular.
+        allow_exponent: Write floats with magnitude below ``1e-4`` or at
+            least ``1e16`` in exponent form (``1e-10``, ``1.5e+20``) instead
+            of expanding every digit. ``loads`` reads both forms.
+        preserve_float: Write integral ``float`` values with a trailing
+            ``.0`` (``1.0`` rather than ``1``) so ``loads`` returns them as
+            ``float`` instead of ``int``.
+        allow_nan: Write ``NaN``, ``Infinity`` and ``-Infinity`` as
+            ``null``. With ``False`` they raise ``ValueError`` instead, like
+            ``json.dumps(allow_nan=False)``.
+        typed_tabular: Annotate each tabular field whose non-null values
+            are all ``int`` or all ``float`` with its type, as in
+            ``[2]{count:int,ratio:float}:``. ``loads`` converts the cells of
+            an annotated column to that type, so ``2.0`` no longer comes
+            back as ``2``.
+        rich_numbers: Write ``fractions.Fraction`` values exactly, as
+            strings such as ``3/4``, instead of converting them to
+            ``float``. ``loads`` returns them as ``str``; pass the value to
+            ``Fraction()`` to restore it. (``complex`` values are always
+            written as strings such as ``(1+2j)``.)
+        quote_strings: Wrap every string value in quotes, even ones such
+            as ``abc`` that would be written bare, so no string can be
+            mistaken for a number, boolean or ``null`` by a simpler parser.
+            Keys keep their own quoting rule. ``loads`` returns the same
+            values either way.
+        null_literal: Token written for ``None`` (bare, in place of
+            ``null``), e.g. ``"None"`` for a consumer expecting
+            Python-style tokens. Must be a word of letters, digits and
+            underscores that does not start with a digit.
+        true_literal: Token written for ``True``, like ``null_literal``.
+        false_literal: Token written for ``False``, like ``null_literal``.
+            The three must differ. String values equal to any of them (or
+            to the standard ``null``, ``true`` and ``false``) are quoted;
+            decode with the same options to read the tokens back.
+        inline_threshold: Write a list of primitives in the inline
+            ``[N]: a,b,c`` form only while its items, as written after the
+            colon, take fewer UTF-8 bytes than this; longer lists are written
+            as one ``- item`` line per item. ``None`` (the default) keeps every
+            primitive list inline. Empty lists and lists holding objects or
+            lists are unaffected: TOON has no inline form for the latter, which
+            stay tabular or expanded. ``loads`` reads either form back to the
+            same list.
+        align: Pad the cells of tabular rows with spaces after each
+            delimiter so every column starts at the same position. ``loads``
+            trims the padding, so the decoded values are unchanged.
+        nan_token, inf_token, neg_inf_token: Write ``NaN``, ``Infinity``
+            and ``-Infinity`` as these quoted strings (e.g. ``"NaN"``)
+            instead of ``null``, so they survive the round trip when
+            ``loads`` is given the same tokens. A set token takes precedence
+            over ``allow_nan``. Tokens must be distinct, non-empty and free
+            of quotes, backslashes and control characters.
+        tabular_field_order: Order of the fields in a tabular header.
+            ``"first"`` follows the first row's key order, with keys that only
+            later rows have (under ``tabular="fill"``) appended as they are
+            first seen. ``"sorted"`` sorts the fields lexicographically.
+            ``"union"`` merges the key orders of all rows, placing a key
+            missing from earlier rows right after the key that precedes it
+            in the row where it first appears; it differs from ``"first"``
+            only under ``tabular="fill"``, since otherwise every row has the
+            same keys. A ``sort_keys`` other than ``False`` orders tabular
+            fields too and takes precedence over this option.
+        compact: Omit the space after the colon of object fields and inline
+            array headers, writing ``name:Alice`` and ``tags[2]:a,b`` to save
+            tokens. ``loads`` reads this form in strict mode too, but the
+            TOON specification writes the space, so other decoders may not.
+        tabular_nested_arrays: Also use the tabular form when some values
+            are lists of primitives, writing each such cell as an inline
+            array whose items are separated by ``|`` (``,`` when the
+            document delimiter is not a comma), as in ``x,[2|]: a|b``.
+            This extends TOON, whose tables only hold primitives: ``loads``
+            reads these cells back as lists, but other decoders read them
+            as strings. Rows with objects or nested lists still fall back
+            to the expanded form.
+        timedelta_as: How ``datetime.timedelta`` values are written:
+            ``"seconds"`` writes ``total_seconds()`` as a number, ``"iso"``
+            an ISO-8601 duration string such as ``P1DT2H30M`` or ``PT0.5S``
+            (negative durations get a leading ``-``). Either way ``loads``
+            returns a number or string, not a ``timedelta``.
+        indent_char: ``" "`` indents each level with ``indent`` spaces;
+            ``"\t"`` with one tab per level instead (``indent`` is then
+            ignored). Read tab-indented output back with
+            ``loads(..., allow_tab_indent=True)``.
+        line_separator: What separates lines: ``"\n"`` or ``"\r\n"`` for
+            files meant for Windows tools. ``loads`` reads either.
+        header_comment: Text written as ``# `` comment lines before the
+            document, one per line of it. Comments are only read by
+            ``loads(..., strict=False)``, which skips them.
+        tuple_key_sep: Join the items of tuple dict keys, such as pandas
+            MultiIndex labels, with this string; each item is converted like
+            a key, so ``("x", 1)`` becomes ``x.1`` with ``"."``. ``None``
+            treats tuple keys like other unsupported keys.
+        ensure_ascii: Quote strings and keys that contain non-ASCII
+            characters and write those as ``\\uXXXX`` escapes, as
+            ``json.dumps`` does. ``loads`` reads the escapes back, in strict
+            mode too.
+        strict: Raise ``TypeError`` for objects of types TOON has no form
+            for, after ``__toon__()`` and ``default()`` have been tried,
+            instead of writing them as ``null``.
+        max_line_length: Write a list of primitives as one ``- item`` line
+            per item when its inline ``key[N]: a,b,c`` line, indentation
+            included, would be longer than this many characters. ``None``
+            (the default) sets no limit. Like ``inline_threshold``, it only
+            changes how primitive lists are laid out; ``loads`` reads either
+            form back to the same list.
+        sort_rows_by: Write the rows of every tabular array that has this
+            column sorted by its value, keeping the order of equal values,
+            for deterministic, diffable output. Nested tables without the
+            column and lists written in another form keep their order; with
+            ``tabular="fill"``, rows lacking the value come last. Raises
+            ``ValueError`` if the root table, or a table under a key of the
+            root object, lacks the column, or if the values cannot be
+            compared.
     """
     ...
 
@@ -103,17 +594,543 @@ def dumps(
     delimiter: str = ",",
     key_folding: Optional[str] = None,
     flatten_depth: Optional[int] = None,
+    sort_keys: Union[bool, Literal["natural"]] = False,
+    bytes_encoding: Literal["base64", "hex"] = "base64",
+    enum_as: Literal["value", "name"] = "value",
+    float_precision: Optional[int] = None,
+    strip_trailing_zeros: bool = True,
+    skipkeys: bool = False,
+    share_by_identity: bool = False,
+    tabular: Literal["auto", "never", "fill"] = "auto",
+    allow_exponent: bool = False,
+    preserve_float: bool = False,
+    allow_nan: bool = True,
+    typed_tabular: bool = False,
+    rich_numbers: bool = False,
+    quote_strings: bool = False,
+    null_literal: str = "null",
+    true_literal: str = "true",
+    false_literal: str = "false",
+    inline_threshold: Optional[int] = None,
+    align: bool = False,
+    nan_token: Optional[str] = None,
+    inf_token: Optional[str] = None,
+    neg_inf_token: Optional[str] = None,
+    tabular_field_order: Literal["first", "sorted", "union"] = "first",
+    compact: bool = False,
+    tabular_nested_arrays: bool = False,
+    timedelta_as: Literal["seconds", "iso"] = "seconds",
+    indent_char: Literal[" ", "\t"] = " ",
+    line_separator: Literal["\n", "\r\n"] = "\n",
+    header_comment: Optional[str] = None,
+    tuple_key_sep: Optional[str] = None,
+    ensure_ascii: bool = False,
+    strict: bool = False,
+    max_line_length: Optional[int] = None,
+    sort_rows_by: Optional[str] = None,
 ) -> str:
     """Serialize an object to a TOON string.
 
     Args:
         obj: Python object to serialize.
-        indent: Spaces per indentation level.
-        delimiter: Array/tabular delimiter (",", "\t", or "|").
+        indent: Spaces per indentation level (minimum 2).
+        delimiter: Array/tabular delimiter: ",", "\t", "|" or another
+            ASCII punctuation character (e.g. ";") other than
+            ``" \\ [ ] { } : - + . _ / * %``; or ``"auto"`` to pick, per
+            document, whichever of ",", "|" and "\t" the fewest string
+            values need quoting for (ties prefer that order). A non-comma
+            delimiter is declared in each array header.
         key_folding: Flatten nested keys: None, "safe", "on", "always".
-        flatten_depth: Maximum depth for key folding.
+        flatten_depth: Maximum number of keys folded into one path,
+            counted from the first key of the chain at any depth.
+        sort_keys: Sort object keys and tabular fields. ``True`` sorts
+            lexicographically; ``"natural"`` compares digit runs numerically
+            so ``item2`` precedes ``item10``.
+        bytes_encoding: Text encoding for ``bytes``/``bytearray`` values,
+            ``"base64"`` or ``"hex"``. Decoding does not reverse this; the
+            value comes back as a ``str``.
+        enum_as: How ``enum.Enum`` members are written: ``"value"``
+            emits the member's ``.value``, ``"name"`` its ``.name``.
+        float_precision: Round floats to this many significant digits.
+            Output uses exponent notation only with ``allow_exponent``.
+        strip_trailing_zeros: Drop trailing fractional zeros produced by
+            ``float_precision`` (``False`` keeps ``1.50``). Has no effect
+            without ``float_precision``.
+        skipkeys: Drop dict keys that are not ``str``, ``int``, ``float``,
+            ``bool`` or ``None`` instead of raising ``TypeError``. Supported
+            non-string keys are always converted as ``json.dumps`` does.
+        share_by_identity: Write each container reached more than once
+            (the same object by ``id()``, including cycles) in full only at
+            its first occurrence; repeats become ``*/path`` aliases that
+            ``loads(..., resolve_aliases=True)`` turns back into shared
+            references. Cannot be combined with ``key_folding``.
+        tabular: When lists of objects use the ``[N]{fields}:`` form.
+            ``"auto"`` requires every row to have the same keys;
+            ``"fill"`` also accepts differing keys, using their union as the
+            header and ``null`` for missing cells; ``"never"`` always writes
+            expanded lists. Rows with nested values are never tabular.
+        allow_exponent: Write floats with magnitude below ``1e-4`` or at
+            least ``1e16`` in exponent form (``1e-10``, ``1.5e+20``) instead
+            of expanding every digit. ``loads`` reads both forms.
+        preserve_float: Write integral ``float`` values with a trailing
+            ``.0`` (``1.0`` rather than ``1``) so ``loads`` returns them as
+            ``float`` instead of ``int``.
+        allow_nan: Write ``NaN``, ``Infinity`` and ``-Infinity`` as
+            ``null``. With ``False`` they raise ``ValueError`` instead, like
+            ``json.dumps(allow_nan=False)``.
+        typed_tabular: Annotate each tabular field whose non-null values
+            are all ``int`` or all ``float`` with its type, as in
+            ``[2]{count:int,ratio:float}:``. ``loads`` converts the cells of
+            an annotated column to that type, so ``2.0`` no longer comes
+            back as ``2``.
+        rich_numbers: Write ``fractions.Fraction`` values exactly, as
+            strings such as ``3/4``, instead of converting them to
+            ``float``. ``loads`` returns them as ``str``; pass the value to
+            ``Fraction()`` to restore it. (``complex`` values are always
+            written as strings such as ``(1+2j)``.)
+        quote_strings: Wrap every string value in quotes, even ones such
+            as ``abc`` that would be written bare, so no string can be
+            mistaken for a number, boolean or ``null`` by a simpler parser.
+            Keys keep their own quoting rule. ``loads`` returns the same
+            values either way.
+        null_literal: Token written for ``None`` (bare, in place of
+            ``null``), e.g. ``"None"`` for a consumer expecting
+            Python-style tokens. Must be a word of letters, digits and
+            underscores that does not start with a digit.
+        true_literal: Token written for ``True``, like ``null_literal``.
+        false_literal: Token written for ``False``, like ``null_literal``.
+            The three must differ. String values equal to any of them (or
+            to the standard ``null``, ``true`` and ``false``) are quoted;
+            decode with the same options to read the tokens back.
+        inline_threshold: Write a list of primitives in the inline
+            ``[N]: a,b,c`` form only while its items, as written after the
+            colon, take fewer UTF-8 bytes than this; longer lists are written
+            as one ``- item`` line per item. ``None`` (the default) keeps every
+            primitive list inline. Empty lists and lists holding objects or
+            lists are unaffected: TOON has no inline form for the latter, which
+            stay tabular or expanded. ``loads`` reads either form back to the
+            same list.
+        align: Pad the cells of tabular rows with spaces after each
+            delimiter so every column starts at the same position. ``loads``
+            trims the padding, so the decoded values are unchanged.
+        nan_token, inf_token, neg_inf_token: Write ``NaN``, ``Infinity``
+            and ``-Infinity`` as these quoted strings (e.g. ``"NaN"``)
+            instead of ``null``, so they survive the round trip when
+            ``loads`` is given the same tokens. A set token takes precedence
+            over ``allow_nan``. Tokens must be distinct, non-empty and free
+            of quotes, backslashes and control characters.
+        tabular_field_order: Order of the fields in a tabular header.
+            ``"first"`` follows the first row's key order, with keys that only
+            later rows have (under ``tabular="fill"``) appended as they are
+            first seen. ``"sorted"`` sorts the fields lexicographically.
+            ``"union"`` merges the key orders of all rows, placing a key
+            missing from earlier rows right after the key that precedes it
+            in the row where it first appears; it differs from ``"first"``
+            only under ``tabular="fill"``, since otherwise every row has the
+            same keys. A ``sort_keys`` other than ``False`` orders tabular
+            fields too and takes precedence over this option.
+        compact: Omit the space after the colon of object fields and inline
+            array headers, writing ``name:Alice`` and ``tags[2]:a,b`` to save
+            tokens. ``loads`` reads this form in strict mode too, but the
+            TOON specification writes the space, so other decoders may not.
+        tabular_nested_arrays: Also use the tabular form when some values
+            are lists of primitives, writing each such cell as an inline
+            array whose items are separated by ``|`` (``,`` when the
+            document delimiter is not a comma), as in ``x,[2|]: a|b``.
+            This extends TOON, whose tables only hold primitives: ``loads``
+            reads these cells back as lists, but other decoders read them
+            as strings. Rows with objects or nested lists still fall back
+            to the expanded form.
+        timedelta_as: How ``datetime.timedelta`` values are written:
+            ``"seconds"`` writes ``total_seconds()`` as a number, ``"iso"``
+            an ISO-8601 duration string such as ``P1DT2H30M`` or ``PT0.5S``
+            (negative durations get a leading ``-``). Either way ``loads``
+            returns a number or string, not a ``timedelta``.
+        indent_char: ``" "`` indents each level with ``indent`` spaces;
+            ``"\t"`` with one tab per level instead (``indent`` is then
+            ignored). Read tab-indented output back with
+            ``loads(..., allow_tab_indent=True)``.
+        line_separator: What separates lines: ``"\n"`` or ``"\r\n"`` for
+            files meant for Windows tools. ``loads`` reads either.
+        header_comment: Text written as ``# `` comment lines before the
+            document, one per line of it. Comments are only read by
+            ``loads(..., strict=False)``, which skips them.
+        tuple_key_sep: Join the items of tuple dict keys, such as pandas
+            MultiIndex labels, with this string; each item is converted like
+            a key, so ``("x", 1)`` becomes ``x.1`` with ``"."``. ``None``
+            treats tuple keys like other unsupported keys.
+        ensure_ascii: Quote strings and keys that contain non-ASCII
+            characters and write those as ``\\uXXXX`` escapes, as
+            ``json.dumps`` does. ``loads`` reads the escapes back, in strict
+            mode too.
+        strict: Raise ``TypeError`` for objects of types TOON has no form
+            for, after ``__toon__()`` and ``default()`` have been tried,
+            instead of writing them as ``null``.
+        max_line_length: Write a list of primitives as one ``- item`` line
+            per item when its inline ``key[N]: a,b,c`` line, indentation
+            included, would be longer than this many characters. ``None``
+            (the default) sets no limit. Like ``inline_threshold``, it only
+            changes how primitive lists are laid out; ``loads`` reads either
+            form back to the same list.
+        sort_rows_by: Write the rows of every tabular array that has this
+            column sorted by its value, keeping the order of equal values,
+            for deterministic, diffable output. Nested tables without the
+            column and lists written in another form keep their order; with
+            ``tabular="fill"``, rows lacking the value come last. Raises
+            ``ValueError`` if the root table, or a table under a key of the
+            root object, lacks the column, or if the values cannot be
+            compared.
 
     Returns:
         TOON-formatted string.
     """
     ...
+
+def dumps_bytes(
+    obj: Any,
+    *,
+    indent: int = 2,
+    delimiter: str = ",",
+    key_folding: Optional[str] = None,
+    flatten_depth: Optional[int] = None,
+    sort_keys: Union[bool, Literal["natural"]] = False,
+    bytes_encoding: Literal["base64", "hex"] = "base64",
+    enum_as: Literal["value", "name"] = "value",
+    float_precision: Optional[int] = None,
+    strip_trailing_zeros: bool = True,
+    skipkeys: bool = False,
+    share_by_identity: bool = False,
+    tabular: Literal["auto", "never", "fill"] = "auto",
+    allow_exponent: bool = False,
+    preserve_float: bool = False,
+    allow_nan: bool = True,
+    typed_tabular: bool = False,
+    rich_numbers: bool = False,
+    quote_strings: bool = False,
+    null_literal: str = "null",
+    true_literal: str = "true",
+    false_literal: str = "false",
+    inline_threshold: Optional[int] = None,
+    align: bool = False,
+    nan_token: Optional[str] = None,
+    inf_token: Optional[str] = None,
+    neg_inf_token: Optional[str] = None,
+    tabular_field_order: Literal["first", "sorted", "union"] = "first",
+    compact: bool = False,
+    tabular_nested_arrays: bool = False,
+    timedelta_as: Literal["seconds", "iso"] = "seconds",
+    indent_char: Literal[" ", "\t"] = " ",
+    line_separator: Literal["\n", "\r\n"] = "\n",
+    header_comment: Optional[str] = None,
+    tuple_key_sep: Optional[str] = None,
+    ensure_ascii: bool = False,
+    strict: bool = False,
+    max_line_length: Optional[int] = None,
+    sort_rows_by: Optional[str] = None,
+) -> bytes:
+    """Serialize an object to TOON as UTF-8 bytes.
+
+    Equivalent to ``dumps(obj, ...).encode("utf-8")``, but the bytes are
+    built directly from the encoder's output, with no intermediate ``str``.
+    Handy for sockets and files opened in binary mode.
+
+    Args:
+        obj: Python object to serialize.
+        **kwargs: The same keyword arguments as :func:`dumps`.
+
+    Returns:
+        The TOON text, UTF-8 encoded.
+    """
+    ...
+
+def estimate_size(
+    obj: Any,
+    *,
+    indent: int = 2,
+    delimiter: str = ",",
+    key_folding: Optional[str] = None,
+    flatten_depth: Optional[int] = None,
+    sort_keys: Union[bool, Literal["natural"]] = False,
+    bytes_encoding: Literal["base64", "hex"] = "base64",
+    enum_as: Literal["value", "name"] = "value",
+    float_precision: Optional[int] = None,
+    strip_trailing_zeros: bool = True,
+    skipkeys: bool = False,
+    share_by_identity: bool = False,
+    tabular: Literal["auto", "never", "fill"] = "auto",
+    allow_exponent: bool = False,
+    preserve_float: bool = False,
+    allow_nan: bool = True,
+    typed_tabular: bool = False,
+    rich_numbers: bool = False,
+    quote_strings: bool = False,
+    null_literal: str = "null",
+    true_literal: str = "true",
+    false_literal: str = "false",
+    inline_threshold: Optional[int] = None,
+    align: bool = False,
+    nan_token: Optional[str] = None,
+    inf_token: Optional[str] = None,
+    neg_inf_token: Optional[str] = None,
+    tabular_field_order: Literal["first", "sorted", "union"] = "first",
+    compact: bool = False,
+    tabular_nested_arrays: bool = False,
+    timedelta_as: Literal["seconds", "iso"] = "seconds",
+    indent_char: Literal[" ", "\t"] = " ",
+    line_separator: Literal["\n", "\r\n"] = "\n",
+    header_comment: Optional[str] = None,
+    tuple_key_sep: Optional[str] = None,
+    ensure_ascii: bool = False,
+    strict: bool = False,
+    max_line_length: Optional[int] = None,
+    sort_rows_by: Optional[str] = None,
+) -> int:
+    """Return the UTF-8 byte length ``dumps(obj, ...)`` would produce.
+
+    The encoder runs with a byte counter instead of an output buffer, so the
+    result is exact without allocating the TOON string.
+
+    Args:
+        obj: Python object to measure.
+        **kwargs: The same keyword arguments as :func:`dumps`.
+
+    Returns:
+        ``len(dumps(obj, ...).encode("utf-8"))``.
+    """
+    ...
+
+def check_roundtrip(
+    obj: Any,
+    *,
+    indent: int = 2,
+    delimiter: str = ",",
+    key_folding: Optional[str] = None,
+    flatten_depth: Optional[int] = None,
+    sort_keys: Union[bool, Literal["natural"]] = False,
+    bytes_encoding: Literal["base64", "hex"] = "base64",
+    enum_as: Literal["value", "name"] = "value",
+    float_precision: Optional[int] = None,
+    strip_trailing_zeros: bool = True,
+    skipkeys: bool = False,
+    share_by_identity: bool = False,
+    tabular: Literal["auto", "never", "fill"] = "auto",
+    allow_exponent: bool = False,
+    preserve_float: bool = False,
+    allow_nan: bool = True,
+    typed_tabular: bool = False,
+    rich_numbers: bool = False,
+    quote_strings: bool = False,
+    null_literal: str = "null",
+    true_literal: str = "true",
+    false_literal: str = "false",
+    inline_threshold: Optional[int] = None,
+    align: bool = False,
+    nan_token: Optional[str] = None,
+    inf_token: Optional[str] = None,
+    neg_inf_token: Optional[str] = None,
+    tabular_field_order: Literal["first", "sorted", "union"] = "first",
+    compact: bool = False,
+    tabular_nested_arrays: bool = False,
+    timedelta_as: Literal["seconds", "iso"] = "seconds",
+    indent_char: Literal[" ", "\t"] = " ",
+    line_separator: Literal["\n", "\r\n"] = "\n",
+    header_comment: Optional[str] = None,
+    tuple_key_sep: Optional[str] = None,
+    ensure_ascii: bool = False,
+    strict: bool = False,
+    max_line_length: Optional[int] = None,
+    sort_rows_by: Optional[str] = None,
+) -> bool:
+    """Check that the TOON form of ``obj`` decodes back to the same data.
+
+    Serializes ``obj`` as :func:`dumps` would, parses the output with the
+    matching :func:`loads` options and compares the result with ``obj``.
+    Objects are compared by key set and numbers by value; values ``dumps``
+    writes in another form (dates, UUIDs, enums, bytes, ...) are skipped.
+
+    The output is decoded with ``strict=True`` unless ``header_comment`` is
+    set: its comment lines are only read with ``strict=False``. Any warning that lenient decoding then
+    collects is reported as a difference, so leniency cannot hide a value
+    that would not survive a strict read.
+
+    Args:
+        obj: Python object to check.
+        **kwargs: The same keyword arguments as :func:`dumps`.
+
+    Returns:
+        ``True`` if the decoded value matches ``obj``.
+
+    Raises:
+        ValueError: If they differ; the message lists the first differences
+            by JSON-pointer path.
+        ToonDecodeError: If the output cannot be parsed back.
+    """
+    ...
+
+class TOONEncoder:
+    """Reusable encoder holding the options of :func:`dumps`.
+
+    Like ``json.JSONEncoder``, subclass it and override :meth:`default` to
+    encode objects of types TOON has no form for. Invalid options raise
+    when the encoder is constructed rather than on each call.
+
+    Example:
+        >>> class PointEncoder(toons.TOONEncoder):
+        ...     def default(self, obj):
+        ...         if isinstance(obj, Point):
+        ...             return {"x": obj.x, "y": obj.y}
+        ...         return super().default(obj)
+        >>> PointEncoder(indent=4).encode({"origin": Point(0, 0)})
+    """
+
+    indent: int
+    delimiter: str
+
+    def __init__(
+        self,
+        *,
+        indent: int = 2,
+        delimiter: str = ",",
+        key_folding: Optional[str] = None,
+        flatten_depth: Optional[int] = None,
+        sort_keys: Union[bool, Literal["natural"]] = False,
+        bytes_encoding: Literal["base64", "hex"] = "base64",
+        enum_as: Literal["value", "name"] = "value",
+        float_precision: Optional[int] = None,
+        strip_trailing_zeros: bool = True,
+        skipkeys: bool = False,
+        share_by_identity: bool = False,
+        tabular: Literal["auto", "never", "fill"] = "auto",
+        allow_exponent: bool = False,
+        preserve_float: bool = False,
+        allow_nan: bool = True,
+        typed_tabular: bool = False,
+        rich_numbers: bool = False,
+        quote_strings: bool = False,
+        null_literal: str = "null",
+        true_literal: str = "true",
+        false_literal: str = "false",
+        inline_threshold: Optional[int] = None,
+        align: bool = False,
+        nan_token: Optional[str] = None,
+        inf_token: Optional[str] = None,
+        neg_inf_token: Optional[str] = None,
+        tabular_field_order: Literal["first", "sorted", "union"] = "first",
+        compact: bool = False,
+        tabular_nested_arrays: bool = False,
+        timedelta_as: Literal["seconds", "iso"] = "seconds",
+        indent_char: Literal[" ", "\t"] = " ",
+        line_separator: Literal["\n", "\r\n"] = "\n",
+        header_comment: Optional[str] = None,
+        tuple_key_sep: Optional[str] = None,
+        ensure_ascii: bool = False,
+        strict: bool = False,
+        max_line_length: Optional[int] = None,
+        sort_rows_by: Optional[str] = None,
+    ) -> None:
+        """Store the options; see :func:`dumps` for each of them.
+
+        Raises:
+            ValueError: If an option is invalid (e.g. ``indent < 2``).
+        """
+        ...
+
+    def encode(self, obj: Any) -> str:
+        """Serialize ``obj`` to a TOON string with this encoder's options.
+
+        Each object of an unsupported type is passed to :meth:`default` and
+        the result is encoded in its place.
+        """
+        ...
+
+    def default(self, obj: Any) -> Any:
+        """Return an encodable stand-in for ``obj``.
+
+        Called for objects that are neither primitives, containers nor one
+        of the types :func:`dumps` converts (dates, UUIDs, enums, ...), and
+        that have no ``__toon__()`` method of their own. The base
+        implementation returns ``None``, so such objects are written as
+        ``null``, as :func:`dumps` does; with ``strict=True`` it raises
+        ``TypeError`` instead.
+
+        Raises:
+            TypeError: If the returned value is itself unsupported.
+            ValueError: If the returned value contains ``obj`` again.
+        """
+        ...
+
+class TOONDecoder:
+    """Reusable decoder holding the options of :func:`loads`.
+
+    Invalid options raise when the decoder is constructed.
+
+    Example:
+        >>> decoder = toons.TOONDecoder(strict=False)
+        >>> decoder.decode("items[2]: a,b")
+        {'items': ['a', 'b']}
+    """
+
+    strict: bool
+    expand_paths: Optional[str]
+    indent: Optional[int]
+    collect_warnings: bool
+    bare_hyphen: str
+    resolve_aliases: bool
+    parse_datetime: bool
+    max_depth: int
+    object_hook: Optional[Callable[[Dict[str, Any]], Any]]
+    object_pairs_hook: Optional[Callable[[List[Tuple[str, Any]]], Any]]
+    null_literal: str
+    true_literal: str
+    false_literal: str
+    nan_token: Optional[str]
+    inf_token: Optional[str]
+    neg_inf_token: Optional[str]
+    allow_trailing_delimiter: bool
+    allow_tab_indent: bool
+    leading_zero: str
+    empty_cell: str
+    schema: Any
+
+    def __init__(
+        self,
+        *,
+        strict: bool = True,
+        expand_paths: Optional[str] = None,
+        indent: Optional[int] = None,
+        collect_warnings: bool = False,
+        bare_hyphen: Literal["empty_object", "null"] = "empty_object",
+        resolve_aliases: bool = False,
+        parse_datetime: bool = False,
+        max_depth: int = 1000,
+        object_hook: Optional[Callable[[Dict[str, Any]], Any]] = None,
+        object_pairs_hook: Optional[
+            Callable[[List[Tuple[str, Any]]], Any]
+        ] = None,
+        null_literal: str = "null",
+        true_literal: str = "true",
+        false_literal: str = "false",
+        nan_token: Optional[str] = None,
+        inf_token: Optional[str] = None,
+        neg_inf_token: Optional[str] = None,
+        allow_trailing_delimiter: bool = False,
+        allow_tab_indent: bool = False,
+        leading_zero: Literal["string", "number"] = "string",
+        empty_cell: Literal["string", "null"] = "string",
+        schema: Any = None,
+    ) -> None:
+        """Store the options; see :func:`loads` for each of them.
+
+        Raises:
+            ValueError: If an option is invalid.
+            TypeError: If a hook is not callable.
+        """
+        ...
+
+    def decode(self, s: Union[str, bytes, bytearray]) -> Any:
+        """Parse a TOON string (or UTF-8 bytes) with this decoder's options.
+
+        Raises:
+            ToonDecodeError: If the input is malformed.
+        """
+        ...